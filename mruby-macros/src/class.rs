@@ -0,0 +1,251 @@
+use darling::{Error, FromMeta};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Attribute, AttributeArgs, FnArg, Ident, ImplItem, ImplItemMethod, ItemImpl, ItemStruct};
+use syn::{ReturnType, Type};
+
+use crate::wrap_in_dummy_const;
+
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
+struct ClassArgs {
+    name: Option<String>,
+    parent: Option<String>,
+}
+
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
+struct MethodArgs {
+    rename: Option<String>,
+}
+
+/// How a Rust method is exposed to Ruby.
+enum Kind {
+    Initialize,
+    Ref,
+    Mut,
+    Class,
+}
+
+pub fn mruby_class(args: &AttributeArgs, item: ItemStruct) -> Result<TokenStream, Error> {
+    let args = ClassArgs::from_list(args)?;
+    if !item.generics.params.is_empty() {
+        let err = Error::custom("Ruby classes cannot have type or lifetime parameters");
+        return Err(err.with_span(&item.generics));
+    }
+
+    let ty = &item.ident;
+    let name = args.name.unwrap_or_else(|| ty.to_string());
+    let parent = match args.parent {
+        Some(parent) => quote!(Some(#parent)),
+        None => quote!(None),
+    };
+
+    let impls = quote! {
+        impl _mruby::class::Class for #ty {
+            const NAME: &'static str = #name;
+            const PARENT: Option<&'static str> = #parent;
+
+            fn define(builder: &mut _mruby::class::Builder) {
+                <#ty as _mruby::class::Methods>::define_methods(builder);
+            }
+        }
+    };
+
+    let output = wrap_in_dummy_const(impls);
+    Ok(quote! {
+        #item
+        #output
+    })
+}
+
+pub fn methods(mut item: ItemImpl) -> Result<TokenStream, Error> {
+    if let Some((_, ref path, _)) = item.trait_ {
+        let err = Error::custom("`#[methods]` must be placed on an inherent impl block");
+        return Err(err.with_span(path));
+    }
+
+    if !item.generics.params.is_empty() {
+        let err = Error::custom("Ruby classes cannot have type or lifetime parameters");
+        return Err(err.with_span(&item.generics));
+    }
+
+    let ty = item.self_ty.clone();
+    match *ty {
+        Type::Path(ref path) if path.qself.is_none() && !path.path.segments.is_empty() => {}
+        _ => return Err(Error::custom("expected a struct name").with_span(&ty)),
+    }
+
+    let mut defs = Vec::new();
+    let mut errors = Vec::new();
+    let mut has_initialize = false;
+
+    for impl_item in item.items.iter_mut() {
+        if let ImplItem::Method(ref mut method) = *impl_item {
+            match gen_method(&ty, method) {
+                Ok((Kind::Initialize, _)) if has_initialize => {
+                    let err = Error::custom("only one method may be marked `#[initialize]`");
+                    errors.push(err.with_span(&method.sig.ident));
+                }
+                Ok((kind, def)) => {
+                    if let Kind::Initialize = kind {
+                        has_initialize = true;
+                    }
+                    defs.push(def);
+                }
+                Err(err) => errors.push(err),
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(Error::multiple(errors));
+    }
+
+    let impls = quote! {
+        impl _mruby::class::Methods for #ty {
+            fn define_methods(builder: &mut _mruby::class::Builder) {
+                #(#defs;)*
+            }
+        }
+    };
+
+    let output = wrap_in_dummy_const(impls);
+    Ok(quote! {
+        #item
+        #output
+    })
+}
+
+/// Generates the `Builder` call defining `method`, stripping the attributes consumed by this
+/// macro from it.
+fn gen_method(ty: &Type, method: &mut ImplItemMethod) -> Result<(Kind, TokenStream), Error> {
+    let (is_initialize, attrs) = take_method_attrs(&mut method.attrs)?;
+    let sig = &method.sig;
+    let decl = &sig.decl;
+
+    if !decl.generics.params.is_empty() {
+        let err = Error::custom("methods exposed to Ruby cannot be generic");
+        return Err(err.with_span(&decl.generics));
+    } else if sig.unsafety.is_some() || sig.asyncness.is_some() || decl.variadic.is_some() {
+        let err = Error::custom("methods exposed to Ruby must be safe, synchronous functions");
+        return Err(err.with_span(&sig.ident));
+    }
+
+    let mut inputs = decl.inputs.iter().peekable();
+    let kind = match inputs.peek() {
+        Some(FnArg::SelfRef(ref arg)) if arg.mutability.is_some() => Kind::Mut,
+        Some(FnArg::SelfRef(_)) => Kind::Ref,
+        Some(FnArg::SelfValue(ref arg)) => {
+            let err = Error::custom("methods exposed to Ruby cannot take `self` by value");
+            return Err(err.with_span(arg));
+        }
+        _ => Kind::Class,
+    };
+
+    let kind = match (kind, is_initialize) {
+        (Kind::Class, true) => Kind::Initialize,
+        (_, true) => {
+            let err = Error::custom("`#[initialize]` methods cannot take `self`");
+            return Err(err.with_span(&sig.ident));
+        }
+        (kind, false) => kind,
+    };
+
+    if let Kind::Ref | Kind::Mut = kind {
+        inputs.next();
+    }
+
+    let mut arg_names = Vec::new();
+    let mut arg_types = Vec::new();
+    for (i, input) in inputs.enumerate() {
+        match *input {
+            FnArg::Captured(ref arg) => {
+                arg_names.push(Ident::new(&format!("arg{}", i), arg.pat.span()));
+                arg_types.push(&arg.ty);
+            }
+            ref other => {
+                let err = Error::custom("unsupported argument in method exposed to Ruby");
+                return Err(err.with_span(other));
+            }
+        }
+    }
+
+    let fn_name = &sig.ident;
+    let names = &arg_names;
+    let call = match kind {
+        Kind::Ref | Kind::Mut => quote!(<#ty>::#fn_name(this, #(#names),*)),
+        Kind::Initialize | Kind::Class => quote!(<#ty>::#fn_name(#(#names),*)),
+    };
+    let args = quote!((#(#names,)*): (#(#arg_types,)*));
+
+    let infallible = quote!(::std::result::Result::Ok::<_, ::std::convert::Infallible>);
+    let body = match decl.output {
+        ReturnType::Default => quote!({
+            #call;
+            #infallible(())
+        }),
+        ReturnType::Type(_, ref ret) if is_result(ret) => call,
+        ReturnType::Type(..) => quote!(#infallible(#call)),
+    };
+
+    let name = attrs.rename.unwrap_or_else(|| fn_name.to_string());
+    let def = match kind {
+        Kind::Initialize => quote! {
+            builder.def_initialize::<_, #ty, _, _>(|#args| #body)
+        },
+        Kind::Ref => quote! {
+            builder.def_method_ref(#name, |this: &#ty, #args| #body)
+        },
+        Kind::Mut => quote! {
+            builder.def_method_mut(#name, |this: &mut #ty, #args| #body)
+        },
+        Kind::Class => quote! {
+            builder.def_class_method(#name, |_: _mruby::Value, #args| #body)
+        },
+    };
+
+    Ok((kind, def))
+}
+
+/// Removes the `#[initialize]` and `#[method(...)]` attributes from a method, returning them.
+fn take_method_attrs(attrs: &mut Vec<Attribute>) -> Result<(bool, MethodArgs), Error> {
+    let mut is_initialize = false;
+    let mut args = MethodArgs::default();
+    let mut result = Ok(());
+
+    attrs.retain(|attr| {
+        if attr.path.is_ident("initialize") {
+            is_initialize = true;
+            false
+        } else if attr.path.is_ident("method") {
+            match attr.parse_meta() {
+                Ok(meta) => match MethodArgs::from_meta(&meta) {
+                    Ok(parsed) => args = parsed,
+                    Err(err) => result = Err(err.with_span(attr)),
+                },
+                Err(err) => result = Err(Error::custom(err).with_span(attr)),
+            }
+            false
+        } else {
+            true
+        }
+    });
+
+    result.map(|_| (is_initialize, args))
+}
+
+/// Returns whether the return type looks like `Result<T, E>`, including aliases such as
+/// `io::Result<T>`, whose error is then raised as a `RuntimeError`.
+fn is_result(ty: &Type) -> bool {
+    match *ty {
+        Type::Path(ref path) => path
+            .path
+            .segments
+            .last()
+            .map(|seg| seg.value().ident == "Result")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
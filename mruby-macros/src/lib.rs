@@ -5,8 +5,13 @@ extern crate proc_macro;
 
 use darling::Error;
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, DeriveInput};
+use quote::quote;
+use syn::{parse_macro_input, AttributeArgs, DeriveInput, ItemImpl, ItemStruct};
 
+use crate::ruby::IncludeRuby;
+
+mod class;
+mod ruby;
 mod symbol;
 
 /// Custom `#[derive]` macro for defining strongly-typed Ruby symbols.
@@ -19,6 +24,7 @@ mod symbol;
 ///
 ///
 /// ```rust
+/// # use mruby::Symbol;
 /// #[derive(Symbol)]
 /// pub enum Hello {
 ///     Foo,
@@ -32,12 +38,16 @@ mod symbol;
 /// how the symbols should be serialized and deserialized:
 ///
 /// ```rust
+/// # use mruby::symbol::ToSymbol;
+/// # use mruby::Symbol;
 /// #[derive(Symbol)]
 /// #[symbol(rename_all = "snake_case")]
 /// pub enum Hello {
 ///     Foo,    // :foo
 ///     BarBaz, // :bar_baz
 /// }
+/// # assert_eq!(Hello::Foo.as_str(), "foo");
+/// # assert_eq!(Hello::BarBaz.as_str(), "bar_baz");
 /// ```
 ///
 /// Possible values for `rename_all` are identical to [`#[serde(rename_all = "...")]`][rename_all],
@@ -65,58 +75,125 @@ pub fn derive_symbol(input: TokenStream) -> TokenStream {
         .into()
 }
 
-/// Defines a new Ruby class with the given PascalCase name and fields.
+/// Exposes a Rust struct to Ruby as a class of the same name.
 ///
-/// # Design
+/// This implements `mruby::class::Class` for the struct, whose instances are then stored inside
+/// Ruby objects of that class. The Ruby methods themselves are defined by a separate impl block
+/// annotated with `#[methods]`, which must be present.
 ///
-/// ```rust
-/// /// All fields must implement `ToValue`/`FromValue` in order to work, unless skipped,
-/// /// serde-style.
-/// #[ruby::class]
+/// # Example
+///
+/// ```rust,ignore
+/// #[mruby_class]
 /// pub struct Person {
 ///     name: String,
 /// }
 ///
-/// #[ruby::methods]
+/// #[methods]
 /// impl Person {
 ///     /// Defines the `initialize()` method for the class.
 ///     ///
-///     /// 1. All parameters, if any, must implement `FromValue`, or must be at least `T: Into<U>`
-///     ///    where `U: FromValue`.
-///     /// 2. The return value must be `Self` or `Result<Self, T: Error>`. In the case of the
-///     ///    constructor returning an `Err`, the error will be converted into a Ruby
-///     ///    `RuntimeError` and the message will contain the Error's `Display` implementation. If
-///     ///    a more specific Ruby exception type is desired, you can always select one of the
-///     ///    built-in Ruby exceptions included with the `mruby` crate and make that be your `Err`.
+///     /// 1. All parameters, if any, must implement `FromValue`.
+///     /// 2. The return value must be `Self` or `Result<Self, E>` where `E: Display`. In the case
+///     ///    of the constructor returning an `Err`, the error will be converted into a Ruby
+///     ///    `RuntimeError` and the message will contain the error's `Display` implementation.
 ///     ///
 ///     /// The Ruby constructor doesn't have to be `pub`. This is because the restrictions above
 ///     /// might not be desirable for use in a Rust API. Therefore, it might be beneficial to have
 ///     /// a `pub` constructor used within Rust and a private `initialize` constructor which is
 ///     /// more ergonomic to call from Ruby.
 ///     #[initialize]
-///     pub fn new<T>(first_name: String, last_name: T) -> Self
-///     where
-///         T: Into<Option<String>>,
-///     {
+///     fn new(first_name: String, last_name: Option<String>) -> Self {
 ///         Person {
-///             name: format!("{} {}", first_name, last_name.into().unwrap_or_default()),
+///             name: format!("{} {}", first_name, last_name.unwrap_or_default()),
 ///         }
 ///     }
 ///
 ///     /// Defines a Ruby method for the class.
 ///     ///
-///     /// 1. Methods must require `&self` or `&mut self` or other variation.
-///     /// 2. All parameters, if any, must implement `FromValue`, or must be at least `T: Into<U>`
-///     ///    where `U: FromValue`.
-///     /// 3. The return value must implement `ToValue`, or must at least be `T: Into<U>` where
-///     ///    `U: ToValue`.
+///     /// 1. Methods taking `&self` or `&mut self` become instance methods, while methods
+///     ///    without a receiver become class methods.
+///     /// 2. All parameters, if any, must implement `FromValue`.
+///     /// 3. The return value must implement `ToValue`, or be a `Result<T, E>` where `T: ToValue`
+///     ///    and `E: Display`. Errors are raised as a `RuntimeError`, as above.
 ///     pub fn say_hello(&self) -> io::Result<()> {
-///         io::stdout().write(b"Hello/")?;
-///         Ok(())
+///         writeln!(io::stdout(), "Hello, {}!", self.name)
+///     }
+///
+///     /// Methods whose Ruby name is not a valid Rust identifier can be renamed.
+///     #[method(rename = "name=")]
+///     pub fn set_name(&mut self, name: String) {
+///         self.name = name;
 ///     }
 /// }
 /// ```
+///
+/// # Attributes
+///
+/// The class name and its parent class can be customized with
+/// `#[mruby_class(name = "...", parent = "...")]`. By default, the class is named after the
+/// struct and inherits from `Object`.
+///
+/// Methods which are generic, `unsafe`, `async` or take `self` by value cannot be exposed to
+/// Ruby and are rejected at compile time.
+#[proc_macro_attribute]
+pub fn mruby_class(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let item = parse_macro_input!(input as ItemStruct);
+    class::mruby_class(&args, item)
+        .unwrap_or_else(Error::write_errors)
+        .into()
+}
+
+/// Defines the Ruby methods of a struct annotated with `#[mruby_class]`.
+///
+/// See the documentation of [`mruby_class`](attr.mruby_class.html) for details.
 #[proc_macro_attribute]
-pub fn mruby_class(_: TokenStream, _: TokenStream) -> TokenStream {
-    unimplemented!()
+pub fn methods(_: TokenStream, input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as ItemImpl);
+    class::methods(item)
+        .unwrap_or_else(Error::write_errors)
+        .into()
+}
+
+/// Embeds a Ruby script in the binary, checking it for syntax errors at build time.
+///
+/// The path is relative to the root of the calling crate, i.e. the directory containing its
+/// `Cargo.toml`. By default, the script is compiled and the macro expands to its bytecode as a
+/// `&'static [u8]`, ready to be passed to `Mruby::load_bytecode()`. Passing `source` as the second
+/// argument embeds the original source code instead, which is still checked for syntax errors.
+///
+/// Syntax errors are reported as compiler errors, which quote the offending line of the script and
+/// point at the column. Since the script is compiled with the host build of `mruby-sys`, the
+/// expansion also fails to compile if its integer width or float support differs from the one
+/// `mruby` is built with.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// static INIT: &[u8] = include_ruby!("scripts/init.rb");
+/// static HELPERS: &[u8] = include_ruby!("scripts/helpers.rb", source);
+///
+/// let mut ruby = Mruby::new()?;
+/// ruby.load_bytecode::<()>(INIT)?;
+/// ruby.eval::<()>(std::str::from_utf8(HELPERS)?)?;
+/// ```
+#[proc_macro]
+pub fn include_ruby(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as IncludeRuby);
+    ruby::include_ruby(&input)
+        .unwrap_or_else(Error::write_errors)
+        .into()
+}
+
+/// Places the generated trait impls inside an unnamed scope, where the `mruby` crate is reachable
+/// as `_mruby` regardless of how the caller imported it.
+fn wrap_in_dummy_const(impls: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        const _: () = {
+            use ::mruby as _mruby;
+
+            #impls
+        };
+    }
 }
@@ -0,0 +1,155 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use darling::Error;
+use mruby_sys::{MRB_INT_BIT, MRB_USE_FLOAT, MRB_WITHOUT_FLOAT};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitByteStr, LitStr, Token};
+
+/// Arguments of `include_ruby!("path/to/script.rb")` or `include_ruby!("...", source)`.
+pub struct IncludeRuby {
+    path: LitStr,
+    embed_source: bool,
+}
+
+impl Parse for IncludeRuby {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut embed_source = false;
+
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let mode: Ident = input.parse()?;
+            match mode.to_string().as_str() {
+                "bytecode" => embed_source = false,
+                "source" => embed_source = true,
+                _ => {
+                    return Err(syn::Error::new(
+                        mode.span(),
+                        "expected `bytecode` or `source`",
+                    ))
+                }
+            }
+
+            input.parse::<Option<Token![,]>>()?;
+        }
+
+        Ok(IncludeRuby { path, embed_source })
+    }
+}
+
+pub fn include_ruby(input: &IncludeRuby) -> Result<TokenStream, Error> {
+    let root = env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from);
+    let path = root.unwrap_or_default().join(input.path.value());
+    let source = fs::read_to_string(&path).map_err(|e| {
+        let message = format!("couldn't read {}: {}", path.display(), e);
+        Error::custom(message).with_span(&input.path)
+    })?;
+
+    let bytecode = compile(&source, &input.path.value())
+        .map_err(|message| Error::custom(message).with_span(&input.path))?;
+
+    // NOTE: Including the script with `include_bytes!()` makes Cargo rebuild the caller whenever
+    // the script changes.
+    let path = path.to_string_lossy();
+    if input.embed_source {
+        let bytes = LitByteStr::new(source.as_bytes(), Span::call_site());
+        return Ok(quote! {
+            {
+                const _: &[u8] = include_bytes!(#path);
+                #bytes as &'static [u8]
+            }
+        });
+    }
+
+    // NOTE: The script was compiled by the `mruby-sys` of this crate, which is built for the host
+    // and may be configured differently from the one `mruby` links for the target.
+    let bytes = LitByteStr::new(&bytecode, Span::call_site());
+    let (int_bit, use_float, without_float) = (MRB_INT_BIT, MRB_USE_FLOAT, MRB_WITHOUT_FLOAT);
+    let mismatch = format!(
+        "{} was compiled for an mruby with {}-bit integers{}, which differs from the one linked \
+         into `mruby`; enable the same `use-f32` and `disable-floats` features for `mruby-macros`",
+        input.path.value(),
+        int_bit,
+        match (use_float, without_float) {
+            (_, true) => " and without floats",
+            (true, _) => " and 32-bit floats",
+            _ => " and 64-bit floats",
+        },
+    );
+
+    Ok(quote! {
+        {
+            const _: &[u8] = include_bytes!(#path);
+            const _: () = assert!(
+                ::mruby::__bytecode_config_matches(#int_bit, #use_float, #without_float),
+                #mismatch
+            );
+            #bytes as &'static [u8]
+        }
+    })
+}
+
+/// Compiles `source` into mruby bytecode, or describes the first syntax error in the style of a
+/// compiler diagnostic, showing the offending line of the script.
+fn compile(source: &str, filename: &str) -> Result<Vec<u8>, String> {
+    mruby_sys::compile_to_bytecode(source, filename).map_err(|(message, line, column)| {
+        if line == 0 {
+            format!("{}: {}", filename, message)
+        } else {
+            syntax_error(source, filename, line, column, &message)
+        }
+    })
+}
+
+/// Formats a syntax error at the given 1-based `line` and `column` of `source` like a `rustc`
+/// diagnostic, with a caret under the column.
+///
+/// NOTE: Procedural macros can only attach errors to spans of Rust tokens, not to arbitrary
+/// locations in other files, so the location is spelled out in the message instead.
+fn syntax_error(source: &str, filename: &str, line: usize, column: usize, message: &str) -> String {
+    let text = source.lines().nth(line.saturating_sub(1)).unwrap_or("");
+    let gutter = " ".repeat(line.to_string().len());
+    let indent: String = text
+        .chars()
+        .take(column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    format!(
+        "{message}\n{gutter}--> {filename}:{line}:{column}\n{gutter} |\n{line} | {text}\n{gutter} | {indent}^",
+        message = message,
+        gutter = gutter,
+        filename = filename,
+        line = line,
+        column = column,
+        text = text,
+        indent = indent,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compile_valid() {
+        let bytecode = compile("puts 'hello'\n", "hello.rb").unwrap();
+        assert_eq!(&bytecode[..8], b"RITE0006");
+    }
+
+    #[test]
+    fn compile_syntax_error() {
+        let err = compile("x = 1\ny = x +* 2\n", "broken.rb").unwrap_err();
+        let expected = concat!(
+            "syntax error, unexpected tSTAR\n",
+            " --> broken.rb:2:8\n",
+            "  |\n",
+            "2 | y = x +* 2\n",
+            "  |        ^",
+        );
+        assert_eq!(err, expected);
+    }
+}
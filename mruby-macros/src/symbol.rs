@@ -2,9 +2,13 @@ use darling::{ast, Error, FromDeriveInput, FromMeta, FromVariant};
 use heck::*;
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{DeriveInput, Ident, Visibility};
+use syn::{DeriveInput, Ident};
 
-#[derive(Copy, Clone, Debug, FromMeta)]
+use crate::wrap_in_dummy_const;
+
+// NOTE: The variants are named after the conventions they apply, like in `serde`.
+#[allow(clippy::enum_variant_names)]
+#[derive(Copy, Clone, Debug, Default, FromMeta)]
 #[darling(default)]
 enum Case {
     #[darling(rename = "camelCase")]
@@ -20,6 +24,7 @@ enum Case {
     #[darling(rename = "SCREAMING_SNAKE_CASE")]
     ScreamingSnakeCase,
     #[darling(rename = "snake_case")]
+    #[default]
     SnakeCase,
     #[darling(rename = "UPPERCASE")]
     Uppercase,
@@ -43,12 +48,6 @@ impl Case {
     }
 }
 
-impl Default for Case {
-    fn default() -> Self {
-        Case::SnakeCase
-    }
-}
-
 #[derive(Debug, FromVariant)]
 #[darling(attributes(symbol), supports(unit))]
 struct Variant {
@@ -60,7 +59,6 @@ struct Variant {
 #[derive(Debug, FromDeriveInput)]
 #[darling(attributes(symbol), supports(enum_any))]
 struct SymbolInput {
-    vis: Visibility,
     ident: Ident,
     #[darling(default)]
     rename_all: Case,
@@ -69,9 +67,8 @@ struct SymbolInput {
 
 pub fn derive_symbol(ast: &DeriveInput) -> Result<TokenStream, Error> {
     let input = SymbolInput::from_derive_input(ast)?;
-    let ty = input.ident.clone();
     let impls = gen_trait_impls(input)?;
-    let output = wrap_in_dummy_const(ty, impls);
+    let output = wrap_in_dummy_const(impls);
     Ok(output)
 }
 
@@ -112,14 +109,14 @@ fn gen_trait_impls(input: SymbolInput) -> Result<TokenStream, Error> {
             }
         }
 
-        impl _mruby::de::FromValue for #ty_name {
-            fn from_value(de: _mruby::de::Deserializer) -> ::std::result::Result<Self, _mruby::de::CastError> {
+        impl<'mrb> _mruby::de::FromValue<'mrb> for #ty_name {
+            fn from_value(de: _mruby::de::Deserializer<'mrb>) -> ::std::result::Result<Self, _mruby::de::CastError> {
                 de.deserialize_symbol()
             }
         }
 
         impl _mruby::ser::ToValue for #ty_name {
-            fn to_value(&self, ser: _mruby::ser::Serializer) -> _mruby::Value {
+            fn to_value<'mrb>(&self, ser: _mruby::ser::Serializer<'mrb>) -> _mruby::Value<'mrb> {
                 ser.serialize_symbol(self)
             }
         }
@@ -127,18 +124,3 @@ fn gen_trait_impls(input: SymbolInput) -> Result<TokenStream, Error> {
 
     Ok(tokens)
 }
-
-fn wrap_in_dummy_const(ident: Ident, impls: TokenStream) -> TokenStream {
-    let name = format!("_IMPL_SYMBOL_ENUM_FOR_{}", ident.to_string().to_uppercase());
-    let dummy_const = Ident::new(&name, ident.span());
-    quote! {
-        const #dummy_const: () = {
-            #[allow(unknown_lints)]
-            #[cfg_attr(feature = "cargo-clippy", allow(useless_attribute))]
-            #[allow(rust_2018_idioms)]
-            extern crate self as _mruby;
-
-            #impls
-        };
-    }
-}
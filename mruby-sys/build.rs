@@ -1,5 +1,6 @@
 use std::env;
-use std::fs::File;
+use std::fmt::Write as _;
+use std::fs::{self, File};
 use std::path::Path;
 
 use cc::Build;
@@ -9,6 +10,50 @@ use walkdir::{DirEntry, WalkDir};
 const MRUBY_ARCHIVE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/vendor/mruby-out.tar");
 const MRUBY_INCLUDE_DIR: &str = "mruby-out/include";
 const MRUBY_SRC_DIR: &str = "mruby-out/src";
+const MRUBY_GEMS_DIR: &str = "mruby-out/src/mrbgems";
+const WRAPPER_SRC: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/vendor/wrapper.c");
+
+/// The compiler gem, which is always built since the parser and code generator live in it.
+const COMPILER_GEM: &str = "mruby-compiler";
+
+/// Bundled gems which can be enabled with a `gem-*` feature, e.g. `gem-io` for `mruby-io`.
+///
+/// Gems are initialized in this order, so each gem must come after the gems it depends on.
+const GEMS: &[&str] = &[
+    "mruby-metaprog",
+    "mruby-io",
+    "mruby-pack",
+    "mruby-sprintf",
+    "mruby-print",
+    "mruby-math",
+    "mruby-time",
+    "mruby-struct",
+    "mruby-compar-ext",
+    "mruby-enum-ext",
+    "mruby-string-ext",
+    "mruby-numeric-ext",
+    "mruby-array-ext",
+    "mruby-hash-ext",
+    "mruby-range-ext",
+    "mruby-proc-ext",
+    "mruby-symbol-ext",
+    "mruby-random",
+    "mruby-object-ext",
+    "mruby-objectspace",
+    "mruby-fiber",
+    "mruby-enumerator",
+    "mruby-enum-lazy",
+    "mruby-toplevel-ext",
+    "mruby-error",
+    "mruby-kernel-ext",
+    "mruby-class-ext",
+    "mruby-eval",
+    "mruby-exit",
+    "mruby-method",
+    "mruby-rational",
+    "mruby-sleep",
+    "mruby-socket",
+];
 
 fn main() {
     let out_dir = env::var("OUT_DIR").unwrap();
@@ -23,10 +68,13 @@ fn main() {
         build.define("MRB_ENDIAN_BIG", None);
     }
 
+    // The code fetch hook is how interrupts reach running Ruby code, so it is
+    // compiled in regardless of the `debug` feature.
+    build.define("MRB_ENABLE_DEBUG_HOOK", None);
+
     if cfg!(feature = "debug") {
         build.define("MRB_DEBUG", None);
         build.define("MRB_ENABLE_ALL_SYMBOLS", None);
-        build.define("MRB_ENABLE_DEBUG_HOOK", None);
     }
 
     if cfg!(feature = "disable-floats") {
@@ -53,25 +101,103 @@ fn main() {
         build.define("MRB_UTF8_STRING", None);
     }
 
+    // NOTE: `mrb_read_irep()` only copies a binary into memory it owns if `mrb_ro_data_p()` says
+    // it is not read-only data. Otherwise the loaded code points into the binary. The version in
+    // `wrapper.c` treats nothing as read-only, so binaries may be freed as soon as they are loaded.
+    build.define("MRB_USE_CUSTOM_RO_DATA_P", None);
+
     let include_dir = Path::new(&out_dir).join(MRUBY_INCLUDE_DIR);
     build.include(include_dir);
 
     let src_dir = Path::new(&out_dir).join(MRUBY_SRC_DIR);
-    let sources = WalkDir::new(src_dir)
+    let gems_dir = Path::new(&out_dir).join(MRUBY_GEMS_DIR);
+    let core = WalkDir::new(src_dir)
         .into_iter()
-        .filter_entry(|e| e.file_type().is_dir() || is_c_file(e))
-        .flat_map(|e| e.ok());
+        .filter_entry(|e| e.path() != gems_dir);
+    add_c_files(&mut build, core);
+
+    let gems: Vec<&str> = GEMS.iter().cloned().filter(|gem| is_enabled(gem)).collect();
+    for gem in Some(&COMPILER_GEM).into_iter().chain(&gems) {
+        // NOTE: The `test` directories only hold helpers for mruby's own test suite, some of
+        // which create files and sockets, so they are never built.
+        let sources = WalkDir::new(gems_dir.join(gem))
+            .into_iter()
+            .filter_entry(|e| e.depth() != 1 || e.file_name() != "test");
+        add_c_files(&mut build, sources);
+    }
+
+    let gem_init = Path::new(&out_dir).join("gem_init.c");
+    fs::write(&gem_init, generate_gem_init(&gems_dir, &gems)).unwrap();
+    build.file(gem_init);
 
-    for file in sources {
+    println!("cargo:rustc-link-lib=m");
+    println!("cargo:rerun-if-changed={}", MRUBY_ARCHIVE);
+    println!("cargo:rerun-if-changed={}", WRAPPER_SRC);
+
+    build.file(WRAPPER_SRC);
+    build.compile("mruby");
+}
+
+/// Returns whether the `gem-*` feature of the bundled gem `gem` is enabled.
+fn is_enabled(gem: &str) -> bool {
+    let name = gem
+        .trim_start_matches("mruby-")
+        .replace('-', "_")
+        .to_uppercase();
+    env::var_os(format!("CARGO_FEATURE_GEM_{}", name)).is_some()
+}
+
+fn add_c_files<I>(build: &mut Build, entries: I)
+where
+    I: IntoIterator<Item = walkdir::Result<DirEntry>>,
+{
+    for file in entries.into_iter().flat_map(|e| e.ok()) {
         if is_c_file(&file) {
             build.file(file.path());
         }
     }
+}
 
-    println!("cargo:rustc-link-lib=m");
+/// Generates the source of `mrb_init_mrbgems()`, which `mrb_open()` calls to initialize the
+/// enabled gems.
+///
+/// This replaces the `gem_init.c` generated by mruby's own build, which initializes every gem in
+/// the default gembox. Gems with Ruby code come with a `gem_init.c` of their own, which loads it
+/// after calling the C initializer.
+fn generate_gem_init(gems_dir: &Path, gems: &[&str]) -> String {
+    let mut source = String::from("#include <mruby.h>\n\n");
+    let mut init = String::new();
+    let mut fini = String::new();
+
+    for gem in gems {
+        let name = gem.replace('-', "_");
+        let prefix = if gems_dir.join(gem).join("gem_init.c").exists() {
+            "GENERATED_TMP_mrb_"
+        } else {
+            "mrb_"
+        };
+
+        writeln!(source, "void {}{}_gem_init(mrb_state*);", prefix, name).unwrap();
+        writeln!(source, "void {}{}_gem_final(mrb_state*);", prefix, name).unwrap();
+        writeln!(init, "  {}{}_gem_init(mrb);", prefix, name).unwrap();
+        fini.insert_str(0, &format!("  {}{}_gem_final(mrb);\n", prefix, name));
+    }
 
-    build.file(concat!(env!("CARGO_MANIFEST_DIR"), "/vendor/wrapper.c"));
-    build.compile("mruby");
+    writeln!(source).unwrap();
+    writeln!(
+        source,
+        "static void\nmrb_final_mrbgems(mrb_state *mrb) {{\n{}}}\n",
+        fini
+    )
+    .unwrap();
+    writeln!(source, "void\nmrb_init_mrbgems(mrb_state *mrb) {{").unwrap();
+    writeln!(
+        source,
+        "{}  mrb_state_atexit(mrb, mrb_final_mrbgems);\n}}",
+        init
+    )
+    .unwrap();
+    source
 }
 
 fn is_c_file(entry: &DirEntry) -> bool {
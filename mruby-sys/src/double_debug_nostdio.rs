@@ -147,8 +147,41 @@ pub const MRB_FL_CLASS_IS_INHERITED: u32 = 131072;
 pub const MRB_INSTANCE_TT_MASK: u32 = 255;
 pub const MRB_PARSER_TOKBUF_MAX: u32 = 65534;
 pub const MRB_PARSER_TOKBUF_SIZE: u32 = 256;
+pub const MRB_ISEQ_NO_FREE: u32 = 1;
+pub const DUMP_DEBUG_INFO: u32 = 1;
+pub const DUMP_ENDIAN_BIG: u32 = 2;
+pub const DUMP_ENDIAN_LIL: u32 = 4;
+pub const DUMP_ENDIAN_NAT: u32 = 6;
+pub const DUMP_ENDIAN_MASK: u32 = 6;
+pub const MRB_DUMP_OK: u32 = 0;
+pub const MRB_DUMP_GENERAL_FAILURE: i32 = -1;
+pub const MRB_DUMP_WRITE_FAULT: i32 = -2;
+pub const MRB_DUMP_READ_FAULT: i32 = -3;
+pub const MRB_DUMP_CRC_ERROR: i32 = -4;
+pub const MRB_DUMP_INVALID_FILE_HEADER: i32 = -5;
+pub const MRB_DUMP_INVALID_IREP: i32 = -6;
+pub const MRB_DUMP_INVALID_ARGUMENT: i32 = -7;
+pub const MRB_DUMP_NULL_SYM_LEN: u32 = 65535;
+pub const RITE_BINARY_IDENT: &'static [u8; 5usize] = b"RITE\0";
+pub const RITE_BINARY_IDENT_LIL: &'static [u8; 5usize] = b"ETIR\0";
+pub const RITE_BINARY_FORMAT_VER: &'static [u8; 5usize] = b"0006\0";
+pub const RITE_COMPILER_NAME: &'static [u8; 5usize] = b"MATZ\0";
+pub const RITE_COMPILER_VERSION: &'static [u8; 5usize] = b"0000\0";
+pub const RITE_VM_VER: &'static [u8; 5usize] = b"0002\0";
+pub const RITE_BINARY_EOF: &'static [u8; 5usize] = b"END\0\0";
+pub const RITE_SECTION_IREP_IDENT: &'static [u8; 5usize] = b"IREP\0";
+pub const RITE_SECTION_DEBUG_IDENT: &'static [u8; 5usize] = b"DBG\0\0";
+pub const RITE_SECTION_LV_IDENT: &'static [u8; 5usize] = b"LVAR\0";
+pub const MRB_DUMP_DEFAULT_STR_LEN: u32 = 128;
+pub const RITE_LV_NULL_MARK: u32 = 65535;
 pub const MRB_HASH_DEFAULT: u32 = 1;
 pub const MRB_HASH_PROC_DEFAULT: u32 = 2;
+pub const MRB_ENV_STACK_UNSHARED: u32 = 1048576;
+pub const MRB_PROC_CFUNC_FL: u32 = 128;
+pub const MRB_PROC_STRICT: u32 = 256;
+pub const MRB_PROC_ORPHAN: u32 = 512;
+pub const MRB_PROC_ENVSET: u32 = 1024;
+pub const MRB_PROC_SCOPE: u32 = 2048;
 pub const MRB_STR_SHARED: u32 = 1;
 pub const MRB_STR_FSHARED: u32 = 2;
 pub const MRB_STR_NOFREE: u32 = 4;
@@ -203,34 +236,13 @@ pub struct RBasic {
     pub c: *mut RClass,
     pub gcnext: *mut RBasic,
 }
-#[test]
-fn bindgen_test_layout_RBasic() {
-    assert_eq!(
-        ::std::mem::size_of::<RBasic>(),
-        24usize,
-        concat!("Size of: ", stringify!(RBasic))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RBasic>(),
-        8usize,
-        concat!("Alignment of ", stringify!(RBasic))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RBasic>())).c as *const _ as usize },
-        8usize,
-        concat!("Offset of field: ", stringify!(RBasic), "::", stringify!(c))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RBasic>())).gcnext as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RBasic),
-            "::",
-            stringify!(gcnext)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RBasic"][::std::mem::size_of::<RBasic>() - 24usize];
+    ["Alignment of RBasic"][::std::mem::align_of::<RBasic>() - 8usize];
+    ["Offset of field: RBasic::c"][::std::mem::offset_of!(RBasic, c) - 8usize];
+    ["Offset of field: RBasic::gcnext"][::std::mem::offset_of!(RBasic, gcnext) - 16usize];
+};
 impl RBasic {
     #[inline]
     pub fn tt(&self) -> mrb_vtype {
@@ -296,49 +308,14 @@ pub struct RObject {
     pub gcnext: *mut RBasic,
     pub iv: *mut iv_tbl,
 }
-#[test]
-fn bindgen_test_layout_RObject() {
-    assert_eq!(
-        ::std::mem::size_of::<RObject>(),
-        32usize,
-        concat!("Size of: ", stringify!(RObject))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RObject>(),
-        8usize,
-        concat!("Alignment of ", stringify!(RObject))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RObject>())).c as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RObject),
-            "::",
-            stringify!(c)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RObject>())).gcnext as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RObject),
-            "::",
-            stringify!(gcnext)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RObject>())).iv as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RObject),
-            "::",
-            stringify!(iv)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RObject"][::std::mem::size_of::<RObject>() - 32usize];
+    ["Alignment of RObject"][::std::mem::align_of::<RObject>() - 8usize];
+    ["Offset of field: RObject::c"][::std::mem::offset_of!(RObject, c) - 8usize];
+    ["Offset of field: RObject::gcnext"][::std::mem::offset_of!(RObject, gcnext) - 16usize];
+    ["Offset of field: RObject::iv"][::std::mem::offset_of!(RObject, iv) - 24usize];
+};
 impl RObject {
     #[inline]
     pub fn tt(&self) -> mrb_vtype {
@@ -404,44 +381,14 @@ pub struct RFiber {
     pub gcnext: *mut RBasic,
     pub cxt: *mut mrb_context,
 }
-#[test]
-fn bindgen_test_layout_RFiber() {
-    assert_eq!(
-        ::std::mem::size_of::<RFiber>(),
-        32usize,
-        concat!("Size of: ", stringify!(RFiber))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RFiber>(),
-        8usize,
-        concat!("Alignment of ", stringify!(RFiber))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RFiber>())).c as *const _ as usize },
-        8usize,
-        concat!("Offset of field: ", stringify!(RFiber), "::", stringify!(c))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RFiber>())).gcnext as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RFiber),
-            "::",
-            stringify!(gcnext)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RFiber>())).cxt as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RFiber),
-            "::",
-            stringify!(cxt)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RFiber"][::std::mem::size_of::<RFiber>() - 32usize];
+    ["Alignment of RFiber"][::std::mem::align_of::<RFiber>() - 8usize];
+    ["Offset of field: RFiber::c"][::std::mem::offset_of!(RFiber, c) - 8usize];
+    ["Offset of field: RFiber::gcnext"][::std::mem::offset_of!(RFiber, gcnext) - 16usize];
+    ["Offset of field: RFiber::cxt"][::std::mem::offset_of!(RFiber, cxt) - 24usize];
+};
 impl RFiber {
     #[inline]
     pub fn tt(&self) -> mrb_vtype {
@@ -514,97 +461,32 @@ pub union mrb_value__bindgen_ty_1 {
     pub sym: mrb_sym,
     _bindgen_union_align: u64,
 }
-#[test]
-fn bindgen_test_layout_mrb_value__bindgen_ty_1() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_value__bindgen_ty_1>(),
-        8usize,
-        concat!("Size of: ", stringify!(mrb_value__bindgen_ty_1))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_value__bindgen_ty_1>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_value__bindgen_ty_1))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_value__bindgen_ty_1>())).f as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_value__bindgen_ty_1),
-            "::",
-            stringify!(f)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_value__bindgen_ty_1>())).p as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_value__bindgen_ty_1),
-            "::",
-            stringify!(p)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_value__bindgen_ty_1>())).i as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_value__bindgen_ty_1),
-            "::",
-            stringify!(i)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_value__bindgen_ty_1>())).sym as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_value__bindgen_ty_1),
-            "::",
-            stringify!(sym)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_value__bindgen_ty_1"][::std::mem::size_of::<mrb_value__bindgen_ty_1>() - 8usize];
+    ["Alignment of mrb_value__bindgen_ty_1"]
+        [::std::mem::align_of::<mrb_value__bindgen_ty_1>() - 8usize];
+    ["Offset of field: mrb_value__bindgen_ty_1::f"]
+        [::std::mem::offset_of!(mrb_value__bindgen_ty_1, f) - 0usize];
+    ["Offset of field: mrb_value__bindgen_ty_1::p"]
+        [::std::mem::offset_of!(mrb_value__bindgen_ty_1, p) - 0usize];
+    ["Offset of field: mrb_value__bindgen_ty_1::i"]
+        [::std::mem::offset_of!(mrb_value__bindgen_ty_1, i) - 0usize];
+    ["Offset of field: mrb_value__bindgen_ty_1::sym"]
+        [::std::mem::offset_of!(mrb_value__bindgen_ty_1, sym) - 0usize];
+};
 impl ::std::fmt::Debug for mrb_value__bindgen_ty_1 {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(f, "mrb_value__bindgen_ty_1 {{ union }}")
     }
 }
-#[test]
-fn bindgen_test_layout_mrb_value() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_value>(),
-        16usize,
-        concat!("Size of: ", stringify!(mrb_value))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_value>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_value))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_value>())).value as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_value),
-            "::",
-            stringify!(value)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_value>())).tt as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_value),
-            "::",
-            stringify!(tt)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_value"][::std::mem::size_of::<mrb_value>() - 16usize];
+    ["Alignment of mrb_value"][::std::mem::align_of::<mrb_value>() - 8usize];
+    ["Offset of field: mrb_value::value"][::std::mem::offset_of!(mrb_value, value) - 0usize];
+    ["Offset of field: mrb_value::tt"][::std::mem::offset_of!(mrb_value, tt) - 8usize];
+};
 impl ::std::fmt::Debug for mrb_value {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(
@@ -647,19 +529,11 @@ pub struct mrb_heap_page {
     pub objects: __IncompleteArrayField<*mut ::std::os::raw::c_void>,
     pub __bindgen_padding_0: [u8; 7usize],
 }
-#[test]
-fn bindgen_test_layout_mrb_heap_page() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_heap_page>(),
-        48usize,
-        concat!("Size of: ", stringify!(mrb_heap_page))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_heap_page>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_heap_page))
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_heap_page"][::std::mem::size_of::<mrb_heap_page>() - 48usize];
+    ["Alignment of mrb_heap_page"][::std::mem::align_of::<mrb_heap_page>() - 8usize];
+};
 impl mrb_heap_page {
     #[inline]
     pub fn old(&self) -> mrb_bool {
@@ -704,179 +578,32 @@ pub struct mrb_gc {
     pub _bitfield_1: __BindgenBitfieldUnit<[u8; 1usize], u8>,
     pub majorgc_old_threshold: usize,
 }
-#[test]
-fn bindgen_test_layout_mrb_gc() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_gc>(),
-        112usize,
-        concat!("Size of: ", stringify!(mrb_gc))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_gc>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_gc))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).heaps as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(heaps)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).sweeps as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(sweeps)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).free_heaps as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(free_heaps)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).live as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(live)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).arena as *const _ as usize },
-        32usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(arena)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).arena_capa as *const _ as usize },
-        40usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(arena_capa)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).arena_idx as *const _ as usize },
-        44usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(arena_idx)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).state as *const _ as usize },
-        48usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(state)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).current_white_part as *const _ as usize },
-        52usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(current_white_part)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).gray_list as *const _ as usize },
-        56usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(gray_list)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).atomic_gray_list as *const _ as usize },
-        64usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(atomic_gray_list)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).live_after_mark as *const _ as usize },
-        72usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(live_after_mark)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).threshold as *const _ as usize },
-        80usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(threshold)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).interval_ratio as *const _ as usize },
-        88usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(interval_ratio)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).step_ratio as *const _ as usize },
-        92usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(step_ratio)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_gc>())).majorgc_old_threshold as *const _ as usize },
-        104usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_gc),
-            "::",
-            stringify!(majorgc_old_threshold)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_gc"][::std::mem::size_of::<mrb_gc>() - 112usize];
+    ["Alignment of mrb_gc"][::std::mem::align_of::<mrb_gc>() - 8usize];
+    ["Offset of field: mrb_gc::heaps"][::std::mem::offset_of!(mrb_gc, heaps) - 0usize];
+    ["Offset of field: mrb_gc::sweeps"][::std::mem::offset_of!(mrb_gc, sweeps) - 8usize];
+    ["Offset of field: mrb_gc::free_heaps"][::std::mem::offset_of!(mrb_gc, free_heaps) - 16usize];
+    ["Offset of field: mrb_gc::live"][::std::mem::offset_of!(mrb_gc, live) - 24usize];
+    ["Offset of field: mrb_gc::arena"][::std::mem::offset_of!(mrb_gc, arena) - 32usize];
+    ["Offset of field: mrb_gc::arena_capa"][::std::mem::offset_of!(mrb_gc, arena_capa) - 40usize];
+    ["Offset of field: mrb_gc::arena_idx"][::std::mem::offset_of!(mrb_gc, arena_idx) - 44usize];
+    ["Offset of field: mrb_gc::state"][::std::mem::offset_of!(mrb_gc, state) - 48usize];
+    ["Offset of field: mrb_gc::current_white_part"]
+        [::std::mem::offset_of!(mrb_gc, current_white_part) - 52usize];
+    ["Offset of field: mrb_gc::gray_list"][::std::mem::offset_of!(mrb_gc, gray_list) - 56usize];
+    ["Offset of field: mrb_gc::atomic_gray_list"]
+        [::std::mem::offset_of!(mrb_gc, atomic_gray_list) - 64usize];
+    ["Offset of field: mrb_gc::live_after_mark"]
+        [::std::mem::offset_of!(mrb_gc, live_after_mark) - 72usize];
+    ["Offset of field: mrb_gc::threshold"][::std::mem::offset_of!(mrb_gc, threshold) - 80usize];
+    ["Offset of field: mrb_gc::interval_ratio"]
+        [::std::mem::offset_of!(mrb_gc, interval_ratio) - 88usize];
+    ["Offset of field: mrb_gc::step_ratio"][::std::mem::offset_of!(mrb_gc, step_ratio) - 92usize];
+    ["Offset of field: mrb_gc::majorgc_old_threshold"]
+        [::std::mem::offset_of!(mrb_gc, majorgc_old_threshold) - 104usize];
+};
 impl mrb_gc {
     #[inline]
     pub fn iterating(&self) -> mrb_bool {
@@ -973,11 +700,6 @@ extern "C" {
 pub type mrb_code = u8;
 /// Required arguments signature type.
 pub type mrb_aspec = u32;
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct mrb_irep {
-    _unused: [u8; 0],
-}
 /// Function pointer type of custom allocator used in @see mrb_open_allocf.
 ///
 /// The function pointing it must behave similarly as realloc except:
@@ -1008,129 +730,24 @@ pub struct mrb_callinfo {
     pub acc: ::std::os::raw::c_int,
     pub target_class: *mut RClass,
 }
-#[test]
-fn bindgen_test_layout_mrb_callinfo() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_callinfo>(),
-        72usize,
-        concat!("Size of: ", stringify!(mrb_callinfo))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_callinfo>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_callinfo))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).mid as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(mid)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).proc_ as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(proc_)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).stackent as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(stackent)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).ridx as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(ridx)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).epos as *const _ as usize },
-        26usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(epos)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).env as *const _ as usize },
-        32usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(env)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).pc as *const _ as usize },
-        40usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(pc)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).err as *const _ as usize },
-        48usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(err)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).argc as *const _ as usize },
-        56usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(argc)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).acc as *const _ as usize },
-        60usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(acc)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_callinfo>())).target_class as *const _ as usize },
-        64usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_callinfo),
-            "::",
-            stringify!(target_class)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_callinfo"][::std::mem::size_of::<mrb_callinfo>() - 72usize];
+    ["Alignment of mrb_callinfo"][::std::mem::align_of::<mrb_callinfo>() - 8usize];
+    ["Offset of field: mrb_callinfo::mid"][::std::mem::offset_of!(mrb_callinfo, mid) - 0usize];
+    ["Offset of field: mrb_callinfo::proc_"][::std::mem::offset_of!(mrb_callinfo, proc_) - 8usize];
+    ["Offset of field: mrb_callinfo::stackent"]
+        [::std::mem::offset_of!(mrb_callinfo, stackent) - 16usize];
+    ["Offset of field: mrb_callinfo::ridx"][::std::mem::offset_of!(mrb_callinfo, ridx) - 24usize];
+    ["Offset of field: mrb_callinfo::epos"][::std::mem::offset_of!(mrb_callinfo, epos) - 26usize];
+    ["Offset of field: mrb_callinfo::env"][::std::mem::offset_of!(mrb_callinfo, env) - 32usize];
+    ["Offset of field: mrb_callinfo::pc"][::std::mem::offset_of!(mrb_callinfo, pc) - 40usize];
+    ["Offset of field: mrb_callinfo::err"][::std::mem::offset_of!(mrb_callinfo, err) - 48usize];
+    ["Offset of field: mrb_callinfo::argc"][::std::mem::offset_of!(mrb_callinfo, argc) - 56usize];
+    ["Offset of field: mrb_callinfo::acc"][::std::mem::offset_of!(mrb_callinfo, acc) - 60usize];
+    ["Offset of field: mrb_callinfo::target_class"]
+        [::std::mem::offset_of!(mrb_callinfo, target_class) - 64usize];
+};
 pub const MRB_FIBER_CREATED: mrb_fiber_state = 0;
 pub const MRB_FIBER_RUNNING: mrb_fiber_state = 1;
 pub const MRB_FIBER_RESUMED: mrb_fiber_state = 2;
@@ -1157,169 +774,26 @@ pub struct mrb_context {
     pub vmexec: mrb_bool,
     pub fib: *mut RFiber,
 }
-#[test]
-fn bindgen_test_layout_mrb_context() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_context>(),
-        104usize,
-        concat!("Size of: ", stringify!(mrb_context))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_context>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_context))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).prev as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(prev)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).stack as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(stack)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).stbase as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(stbase)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).stend as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(stend)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).ci as *const _ as usize },
-        32usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(ci)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).cibase as *const _ as usize },
-        40usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(cibase)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).ciend as *const _ as usize },
-        48usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(ciend)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).rescue as *const _ as usize },
-        56usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(rescue)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).rsize as *const _ as usize },
-        64usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(rsize)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).ensure as *const _ as usize },
-        72usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(ensure)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).esize as *const _ as usize },
-        80usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(esize)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).eidx as *const _ as usize },
-        82usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(eidx)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).status as *const _ as usize },
-        84usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(status)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).vmexec as *const _ as usize },
-        88usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(vmexec)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_context>())).fib as *const _ as usize },
-        96usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_context),
-            "::",
-            stringify!(fib)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_context"][::std::mem::size_of::<mrb_context>() - 104usize];
+    ["Alignment of mrb_context"][::std::mem::align_of::<mrb_context>() - 8usize];
+    ["Offset of field: mrb_context::prev"][::std::mem::offset_of!(mrb_context, prev) - 0usize];
+    ["Offset of field: mrb_context::stack"][::std::mem::offset_of!(mrb_context, stack) - 8usize];
+    ["Offset of field: mrb_context::stbase"][::std::mem::offset_of!(mrb_context, stbase) - 16usize];
+    ["Offset of field: mrb_context::stend"][::std::mem::offset_of!(mrb_context, stend) - 24usize];
+    ["Offset of field: mrb_context::ci"][::std::mem::offset_of!(mrb_context, ci) - 32usize];
+    ["Offset of field: mrb_context::cibase"][::std::mem::offset_of!(mrb_context, cibase) - 40usize];
+    ["Offset of field: mrb_context::ciend"][::std::mem::offset_of!(mrb_context, ciend) - 48usize];
+    ["Offset of field: mrb_context::rescue"][::std::mem::offset_of!(mrb_context, rescue) - 56usize];
+    ["Offset of field: mrb_context::rsize"][::std::mem::offset_of!(mrb_context, rsize) - 64usize];
+    ["Offset of field: mrb_context::ensure"][::std::mem::offset_of!(mrb_context, ensure) - 72usize];
+    ["Offset of field: mrb_context::esize"][::std::mem::offset_of!(mrb_context, esize) - 80usize];
+    ["Offset of field: mrb_context::eidx"][::std::mem::offset_of!(mrb_context, eidx) - 82usize];
+    ["Offset of field: mrb_context::status"][::std::mem::offset_of!(mrb_context, status) - 84usize];
+    ["Offset of field: mrb_context::vmexec"][::std::mem::offset_of!(mrb_context, vmexec) - 88usize];
+    ["Offset of field: mrb_context::fib"][::std::mem::offset_of!(mrb_context, fib) - 96usize];
+};
 pub type mrb_func_t =
     ::std::option::Option<unsafe extern "C" fn(mrb: *mut mrb_state, arg1: mrb_value) -> mrb_value>;
 #[repr(C)]
@@ -1335,69 +809,29 @@ pub union mrb_method_t__bindgen_ty_1 {
     pub func: mrb_func_t,
     _bindgen_union_align: u64,
 }
-#[test]
-fn bindgen_test_layout_mrb_method_t__bindgen_ty_1() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_method_t__bindgen_ty_1>(),
-        8usize,
-        concat!("Size of: ", stringify!(mrb_method_t__bindgen_ty_1))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_method_t__bindgen_ty_1>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_method_t__bindgen_ty_1))
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<mrb_method_t__bindgen_ty_1>())).proc_ as *const _ as usize
-        },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_method_t__bindgen_ty_1),
-            "::",
-            stringify!(proc_)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_method_t__bindgen_ty_1>())).func as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_method_t__bindgen_ty_1),
-            "::",
-            stringify!(func)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_method_t__bindgen_ty_1"]
+        [::std::mem::size_of::<mrb_method_t__bindgen_ty_1>() - 8usize];
+    ["Alignment of mrb_method_t__bindgen_ty_1"]
+        [::std::mem::align_of::<mrb_method_t__bindgen_ty_1>() - 8usize];
+    ["Offset of field: mrb_method_t__bindgen_ty_1::proc_"]
+        [::std::mem::offset_of!(mrb_method_t__bindgen_ty_1, proc_) - 0usize];
+    ["Offset of field: mrb_method_t__bindgen_ty_1::func"]
+        [::std::mem::offset_of!(mrb_method_t__bindgen_ty_1, func) - 0usize];
+};
 impl ::std::fmt::Debug for mrb_method_t__bindgen_ty_1 {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(f, "mrb_method_t__bindgen_ty_1 {{ union }}")
     }
 }
-#[test]
-fn bindgen_test_layout_mrb_method_t() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_method_t>(),
-        16usize,
-        concat!("Size of: ", stringify!(mrb_method_t))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_method_t>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_method_t))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_method_t>())).func_p as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_method_t),
-            "::",
-            stringify!(func_p)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_method_t"][::std::mem::size_of::<mrb_method_t>() - 16usize];
+    ["Alignment of mrb_method_t"][::std::mem::align_of::<mrb_method_t>() - 8usize];
+    ["Offset of field: mrb_method_t::func_p"]
+        [::std::mem::offset_of!(mrb_method_t, func_p) - 0usize];
+};
 impl ::std::fmt::Debug for mrb_method_t {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(
@@ -1472,419 +906,76 @@ pub struct mrb_state {
     pub atexit_stack_len: u16,
     pub ecall_nest: u16,
 }
-#[test]
-fn bindgen_test_layout_mrb_state() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_state>(),
-        1432usize,
-        concat!("Size of: ", stringify!(mrb_state))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_state>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_state))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).jmp as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(jmp)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).allocf as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(allocf)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).allocf_ud as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(allocf_ud)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).c as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(c)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).root_c as *const _ as usize },
-        32usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(root_c)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).globals as *const _ as usize },
-        40usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(globals)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).exc as *const _ as usize },
-        48usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(exc)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).top_self as *const _ as usize },
-        56usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(top_self)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).object_class as *const _ as usize },
-        64usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(object_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).class_class as *const _ as usize },
-        72usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(class_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).module_class as *const _ as usize },
-        80usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(module_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).proc_class as *const _ as usize },
-        88usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(proc_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).string_class as *const _ as usize },
-        96usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(string_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).array_class as *const _ as usize },
-        104usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(array_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).hash_class as *const _ as usize },
-        112usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(hash_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).range_class as *const _ as usize },
-        120usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(range_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).float_class as *const _ as usize },
-        128usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(float_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).fixnum_class as *const _ as usize },
-        136usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(fixnum_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).true_class as *const _ as usize },
-        144usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(true_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).false_class as *const _ as usize },
-        152usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(false_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).nil_class as *const _ as usize },
-        160usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(nil_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).symbol_class as *const _ as usize },
-        168usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(symbol_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).kernel_module as *const _ as usize },
-        176usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(kernel_module)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).mems as *const _ as usize },
-        184usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(mems)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).gc as *const _ as usize },
-        192usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(gc)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).symidx as *const _ as usize },
-        304usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(symidx)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).symtbl as *const _ as usize },
-        312usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(symtbl)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).symhash as *const _ as usize },
-        320usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(symhash)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).symcapa as *const _ as usize },
-        1344usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(symcapa)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).symbuf as *const _ as usize },
-        1352usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(symbuf)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).code_fetch_hook as *const _ as usize },
-        1360usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(code_fetch_hook)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).debug_op_hook as *const _ as usize },
-        1368usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(debug_op_hook)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).eException_class as *const _ as usize },
-        1376usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(eException_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).eStandardError_class as *const _ as usize },
-        1384usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(eStandardError_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).nomem_err as *const _ as usize },
-        1392usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(nomem_err)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).stack_err as *const _ as usize },
-        1400usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(stack_err)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).ud as *const _ as usize },
-        1408usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(ud)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).atexit_stack as *const _ as usize },
-        1416usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(atexit_stack)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).atexit_stack_len as *const _ as usize },
-        1424usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(atexit_stack_len)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_state>())).ecall_nest as *const _ as usize },
-        1426usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_state),
-            "::",
-            stringify!(ecall_nest)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_state"][::std::mem::size_of::<mrb_state>() - 1432usize];
+    ["Alignment of mrb_state"][::std::mem::align_of::<mrb_state>() - 8usize];
+    ["Offset of field: mrb_state::jmp"][::std::mem::offset_of!(mrb_state, jmp) - 0usize];
+    ["Offset of field: mrb_state::allocf"][::std::mem::offset_of!(mrb_state, allocf) - 8usize];
+    ["Offset of field: mrb_state::allocf_ud"]
+        [::std::mem::offset_of!(mrb_state, allocf_ud) - 16usize];
+    ["Offset of field: mrb_state::c"][::std::mem::offset_of!(mrb_state, c) - 24usize];
+    ["Offset of field: mrb_state::root_c"][::std::mem::offset_of!(mrb_state, root_c) - 32usize];
+    ["Offset of field: mrb_state::globals"][::std::mem::offset_of!(mrb_state, globals) - 40usize];
+    ["Offset of field: mrb_state::exc"][::std::mem::offset_of!(mrb_state, exc) - 48usize];
+    ["Offset of field: mrb_state::top_self"][::std::mem::offset_of!(mrb_state, top_self) - 56usize];
+    ["Offset of field: mrb_state::object_class"]
+        [::std::mem::offset_of!(mrb_state, object_class) - 64usize];
+    ["Offset of field: mrb_state::class_class"]
+        [::std::mem::offset_of!(mrb_state, class_class) - 72usize];
+    ["Offset of field: mrb_state::module_class"]
+        [::std::mem::offset_of!(mrb_state, module_class) - 80usize];
+    ["Offset of field: mrb_state::proc_class"]
+        [::std::mem::offset_of!(mrb_state, proc_class) - 88usize];
+    ["Offset of field: mrb_state::string_class"]
+        [::std::mem::offset_of!(mrb_state, string_class) - 96usize];
+    ["Offset of field: mrb_state::array_class"]
+        [::std::mem::offset_of!(mrb_state, array_class) - 104usize];
+    ["Offset of field: mrb_state::hash_class"]
+        [::std::mem::offset_of!(mrb_state, hash_class) - 112usize];
+    ["Offset of field: mrb_state::range_class"]
+        [::std::mem::offset_of!(mrb_state, range_class) - 120usize];
+    ["Offset of field: mrb_state::float_class"]
+        [::std::mem::offset_of!(mrb_state, float_class) - 128usize];
+    ["Offset of field: mrb_state::fixnum_class"]
+        [::std::mem::offset_of!(mrb_state, fixnum_class) - 136usize];
+    ["Offset of field: mrb_state::true_class"]
+        [::std::mem::offset_of!(mrb_state, true_class) - 144usize];
+    ["Offset of field: mrb_state::false_class"]
+        [::std::mem::offset_of!(mrb_state, false_class) - 152usize];
+    ["Offset of field: mrb_state::nil_class"]
+        [::std::mem::offset_of!(mrb_state, nil_class) - 160usize];
+    ["Offset of field: mrb_state::symbol_class"]
+        [::std::mem::offset_of!(mrb_state, symbol_class) - 168usize];
+    ["Offset of field: mrb_state::kernel_module"]
+        [::std::mem::offset_of!(mrb_state, kernel_module) - 176usize];
+    ["Offset of field: mrb_state::mems"][::std::mem::offset_of!(mrb_state, mems) - 184usize];
+    ["Offset of field: mrb_state::gc"][::std::mem::offset_of!(mrb_state, gc) - 192usize];
+    ["Offset of field: mrb_state::symidx"][::std::mem::offset_of!(mrb_state, symidx) - 304usize];
+    ["Offset of field: mrb_state::symtbl"][::std::mem::offset_of!(mrb_state, symtbl) - 312usize];
+    ["Offset of field: mrb_state::symhash"][::std::mem::offset_of!(mrb_state, symhash) - 320usize];
+    ["Offset of field: mrb_state::symcapa"][::std::mem::offset_of!(mrb_state, symcapa) - 1344usize];
+    ["Offset of field: mrb_state::symbuf"][::std::mem::offset_of!(mrb_state, symbuf) - 1352usize];
+    ["Offset of field: mrb_state::code_fetch_hook"]
+        [::std::mem::offset_of!(mrb_state, code_fetch_hook) - 1360usize];
+    ["Offset of field: mrb_state::debug_op_hook"]
+        [::std::mem::offset_of!(mrb_state, debug_op_hook) - 1368usize];
+    ["Offset of field: mrb_state::eException_class"]
+        [::std::mem::offset_of!(mrb_state, eException_class) - 1376usize];
+    ["Offset of field: mrb_state::eStandardError_class"]
+        [::std::mem::offset_of!(mrb_state, eStandardError_class) - 1384usize];
+    ["Offset of field: mrb_state::nomem_err"]
+        [::std::mem::offset_of!(mrb_state, nomem_err) - 1392usize];
+    ["Offset of field: mrb_state::stack_err"]
+        [::std::mem::offset_of!(mrb_state, stack_err) - 1400usize];
+    ["Offset of field: mrb_state::ud"][::std::mem::offset_of!(mrb_state, ud) - 1408usize];
+    ["Offset of field: mrb_state::atexit_stack"]
+        [::std::mem::offset_of!(mrb_state, atexit_stack) - 1416usize];
+    ["Offset of field: mrb_state::atexit_stack_len"]
+        [::std::mem::offset_of!(mrb_state, atexit_stack_len) - 1424usize];
+    ["Offset of field: mrb_state::ecall_nest"]
+        [::std::mem::offset_of!(mrb_state, ecall_nest) - 1426usize];
+};
 impl ::std::fmt::Debug for mrb_state {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write ! ( f , "mrb_state {{ jmp: {:?}, allocf: {:?}, allocf_ud: {:?}, c: {:?}, root_c: {:?}, globals: {:?}, exc: {:?}, top_self: {:?}, object_class: {:?}, class_class: {:?}, module_class: {:?}, proc_class: {:?}, string_class: {:?}, array_class: {:?}, hash_class: {:?}, range_class: {:?}, float_class: {:?}, fixnum_class: {:?}, true_class: {:?}, false_class: {:?}, nil_class: {:?}, symbol_class: {:?}, kernel_module: {:?}, mems: {:?}, gc: {:?}, symidx: {:?}, symtbl: {:?}, symhash: [{}], symcapa: {:?}, symbuf: {:?}, code_fetch_hook: {:?}, debug_op_hook: {:?}, eException_class: {:?}, eStandardError_class: {:?}, nomem_err: {:?}, stack_err: {:?}, ud: {:?}, atexit_stack: {:?}, atexit_stack_len: {:?}, ecall_nest: {:?} }}" , self . jmp , self . allocf , self . allocf_ud , self . c , self . root_c , self . globals , self . exc , self . top_self , self . object_class , self . class_class , self . module_class , self . proc_class , self . string_class , self . array_class , self . hash_class , self . range_class , self . float_class , self . fixnum_class , self . true_class , self . false_class , self . nil_class , self . symbol_class , self . kernel_module , self . mems , self . gc , self . symidx , self . symtbl , self . symhash . iter ( ) . enumerate ( ) . map ( | ( i , v ) | format ! ( "{}{:?}" , if i > 0 { ", " } else { "" } , v ) ) . collect :: < String > ( ) , self . symcapa , self . symbuf , self . code_fetch_hook , self . debug_op_hook , self . eException_class , self . eStandardError_class , self . nomem_err , self . stack_err , self . ud , self . atexit_stack , self . atexit_stack_len , self . ecall_nest )
@@ -3046,49 +2137,17 @@ pub struct mrb_shared_array {
     pub len: mrb_int,
     pub ptr: *mut mrb_value,
 }
-#[test]
-fn bindgen_test_layout_mrb_shared_array() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_shared_array>(),
-        24usize,
-        concat!("Size of: ", stringify!(mrb_shared_array))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_shared_array>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_shared_array))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_shared_array>())).refcnt as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_shared_array),
-            "::",
-            stringify!(refcnt)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_shared_array>())).len as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_shared_array),
-            "::",
-            stringify!(len)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_shared_array>())).ptr as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_shared_array),
-            "::",
-            stringify!(ptr)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_shared_array"][::std::mem::size_of::<mrb_shared_array>() - 24usize];
+    ["Alignment of mrb_shared_array"][::std::mem::align_of::<mrb_shared_array>() - 8usize];
+    ["Offset of field: mrb_shared_array::refcnt"]
+        [::std::mem::offset_of!(mrb_shared_array, refcnt) - 0usize];
+    ["Offset of field: mrb_shared_array::len"]
+        [::std::mem::offset_of!(mrb_shared_array, len) - 8usize];
+    ["Offset of field: mrb_shared_array::ptr"]
+        [::std::mem::offset_of!(mrb_shared_array, ptr) - 16usize];
+};
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct RArray {
@@ -3117,111 +2176,38 @@ pub union RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1 {
     pub shared: *mut mrb_shared_array,
     _bindgen_union_align: u64,
 }
-#[test]
-fn bindgen_test_layout_RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1() {
-    assert_eq!(
-        ::std::mem::size_of::<RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>(),
-        8usize,
-        concat!(
-            "Size of: ",
-            stringify!(RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1)
-        )
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>(),
-        8usize,
-        concat!(
-            "Alignment of ",
-            stringify!(RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>())).capa
-                as *const _ as usize
-        },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(capa)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>())).shared
-                as *const _ as usize
-        },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(shared)
-        )
-    );
-}
-impl ::std::fmt::Debug for RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1 {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-        write!(
-            f,
-            "RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1 {{ union }}"
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1"]
+        [::std::mem::size_of::<RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>() - 8usize];
+    ["Alignment of RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1"]
+        [::std::mem::align_of::<RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>() - 8usize];
+    ["Offset of field: RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1::capa"]
+        [::std::mem::offset_of!(RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1, capa) - 0usize];
+    ["Offset of field: RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1::shared"]
+        [::std::mem::offset_of!(RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1, shared) - 0usize];
+};
+impl ::std::fmt::Debug for RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1 {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(
+            f,
+            "RArray__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1 {{ union }}"
         )
     }
 }
-#[test]
-fn bindgen_test_layout_RArray__bindgen_ty_1__bindgen_ty_1() {
-    assert_eq!(
-        ::std::mem::size_of::<RArray__bindgen_ty_1__bindgen_ty_1>(),
-        24usize,
-        concat!("Size of: ", stringify!(RArray__bindgen_ty_1__bindgen_ty_1))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RArray__bindgen_ty_1__bindgen_ty_1>(),
-        8usize,
-        concat!(
-            "Alignment of ",
-            stringify!(RArray__bindgen_ty_1__bindgen_ty_1)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RArray__bindgen_ty_1__bindgen_ty_1>())).len as *const _ as usize
-        },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RArray__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(len)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RArray__bindgen_ty_1__bindgen_ty_1>())).aux as *const _ as usize
-        },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RArray__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(aux)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RArray__bindgen_ty_1__bindgen_ty_1>())).ptr as *const _ as usize
-        },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RArray__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(ptr)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RArray__bindgen_ty_1__bindgen_ty_1"]
+        [::std::mem::size_of::<RArray__bindgen_ty_1__bindgen_ty_1>() - 24usize];
+    ["Alignment of RArray__bindgen_ty_1__bindgen_ty_1"]
+        [::std::mem::align_of::<RArray__bindgen_ty_1__bindgen_ty_1>() - 8usize];
+    ["Offset of field: RArray__bindgen_ty_1__bindgen_ty_1::len"]
+        [::std::mem::offset_of!(RArray__bindgen_ty_1__bindgen_ty_1, len) - 0usize];
+    ["Offset of field: RArray__bindgen_ty_1__bindgen_ty_1::aux"]
+        [::std::mem::offset_of!(RArray__bindgen_ty_1__bindgen_ty_1, aux) - 8usize];
+    ["Offset of field: RArray__bindgen_ty_1__bindgen_ty_1::ptr"]
+        [::std::mem::offset_of!(RArray__bindgen_ty_1__bindgen_ty_1, ptr) - 16usize];
+};
 impl ::std::fmt::Debug for RArray__bindgen_ty_1__bindgen_ty_1 {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(
@@ -3231,72 +2217,26 @@ impl ::std::fmt::Debug for RArray__bindgen_ty_1__bindgen_ty_1 {
         )
     }
 }
-#[test]
-fn bindgen_test_layout_RArray__bindgen_ty_1() {
-    assert_eq!(
-        ::std::mem::size_of::<RArray__bindgen_ty_1>(),
-        24usize,
-        concat!("Size of: ", stringify!(RArray__bindgen_ty_1))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RArray__bindgen_ty_1>(),
-        8usize,
-        concat!("Alignment of ", stringify!(RArray__bindgen_ty_1))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RArray__bindgen_ty_1>())).heap as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RArray__bindgen_ty_1),
-            "::",
-            stringify!(heap)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RArray__bindgen_ty_1"][::std::mem::size_of::<RArray__bindgen_ty_1>() - 24usize];
+    ["Alignment of RArray__bindgen_ty_1"][::std::mem::align_of::<RArray__bindgen_ty_1>() - 8usize];
+    ["Offset of field: RArray__bindgen_ty_1::heap"]
+        [::std::mem::offset_of!(RArray__bindgen_ty_1, heap) - 0usize];
+};
 impl ::std::fmt::Debug for RArray__bindgen_ty_1 {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(f, "RArray__bindgen_ty_1 {{ union }}")
     }
 }
-#[test]
-fn bindgen_test_layout_RArray() {
-    assert_eq!(
-        ::std::mem::size_of::<RArray>(),
-        48usize,
-        concat!("Size of: ", stringify!(RArray))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RArray>(),
-        8usize,
-        concat!("Alignment of ", stringify!(RArray))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RArray>())).c as *const _ as usize },
-        8usize,
-        concat!("Offset of field: ", stringify!(RArray), "::", stringify!(c))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RArray>())).gcnext as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RArray),
-            "::",
-            stringify!(gcnext)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RArray>())).as_ as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RArray),
-            "::",
-            stringify!(as_)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RArray"][::std::mem::size_of::<RArray>() - 48usize];
+    ["Alignment of RArray"][::std::mem::align_of::<RArray>() - 8usize];
+    ["Offset of field: RArray::c"][::std::mem::offset_of!(RArray, c) - 8usize];
+    ["Offset of field: RArray::gcnext"][::std::mem::offset_of!(RArray, gcnext) - 16usize];
+    ["Offset of field: RArray::as_"][::std::mem::offset_of!(RArray, as_) - 24usize];
+};
 impl ::std::fmt::Debug for RArray {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(
@@ -3455,64 +2395,16 @@ pub struct RClass {
     pub mt: *mut kh_mt,
     pub super_: *mut RClass,
 }
-#[test]
-fn bindgen_test_layout_RClass() {
-    assert_eq!(
-        ::std::mem::size_of::<RClass>(),
-        48usize,
-        concat!("Size of: ", stringify!(RClass))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RClass>(),
-        8usize,
-        concat!("Alignment of ", stringify!(RClass))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RClass>())).c as *const _ as usize },
-        8usize,
-        concat!("Offset of field: ", stringify!(RClass), "::", stringify!(c))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RClass>())).gcnext as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RClass),
-            "::",
-            stringify!(gcnext)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RClass>())).iv as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RClass),
-            "::",
-            stringify!(iv)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RClass>())).mt as *const _ as usize },
-        32usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RClass),
-            "::",
-            stringify!(mt)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RClass>())).super_ as *const _ as usize },
-        40usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RClass),
-            "::",
-            stringify!(super_)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RClass"][::std::mem::size_of::<RClass>() - 48usize];
+    ["Alignment of RClass"][::std::mem::align_of::<RClass>() - 8usize];
+    ["Offset of field: RClass::c"][::std::mem::offset_of!(RClass, c) - 8usize];
+    ["Offset of field: RClass::gcnext"][::std::mem::offset_of!(RClass, gcnext) - 16usize];
+    ["Offset of field: RClass::iv"][::std::mem::offset_of!(RClass, iv) - 24usize];
+    ["Offset of field: RClass::mt"][::std::mem::offset_of!(RClass, mt) - 32usize];
+    ["Offset of field: RClass::super_"][::std::mem::offset_of!(RClass, super_) - 40usize];
+};
 impl RClass {
     #[inline]
     pub fn tt(&self) -> mrb_vtype {
@@ -3670,99 +2562,25 @@ pub struct mrbc_context {
     pub _bitfield_1: __BindgenBitfieldUnit<[u8; 1usize], u8>,
     pub parser_nerr: usize,
 }
-#[test]
-fn bindgen_test_layout_mrbc_context() {
-    assert_eq!(
-        ::std::mem::size_of::<mrbc_context>(),
-        72usize,
-        concat!("Size of: ", stringify!(mrbc_context))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrbc_context>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrbc_context))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrbc_context>())).syms as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrbc_context),
-            "::",
-            stringify!(syms)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrbc_context>())).slen as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrbc_context),
-            "::",
-            stringify!(slen)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrbc_context>())).filename as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrbc_context),
-            "::",
-            stringify!(filename)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrbc_context>())).lineno as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrbc_context),
-            "::",
-            stringify!(lineno)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrbc_context>())).partial_hook as *const _ as usize },
-        32usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrbc_context),
-            "::",
-            stringify!(partial_hook)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrbc_context>())).partial_data as *const _ as usize },
-        40usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrbc_context),
-            "::",
-            stringify!(partial_data)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrbc_context>())).target_class as *const _ as usize },
-        48usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrbc_context),
-            "::",
-            stringify!(target_class)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrbc_context>())).parser_nerr as *const _ as usize },
-        64usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrbc_context),
-            "::",
-            stringify!(parser_nerr)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrbc_context"][::std::mem::size_of::<mrbc_context>() - 72usize];
+    ["Alignment of mrbc_context"][::std::mem::align_of::<mrbc_context>() - 8usize];
+    ["Offset of field: mrbc_context::syms"][::std::mem::offset_of!(mrbc_context, syms) - 0usize];
+    ["Offset of field: mrbc_context::slen"][::std::mem::offset_of!(mrbc_context, slen) - 8usize];
+    ["Offset of field: mrbc_context::filename"]
+        [::std::mem::offset_of!(mrbc_context, filename) - 16usize];
+    ["Offset of field: mrbc_context::lineno"]
+        [::std::mem::offset_of!(mrbc_context, lineno) - 24usize];
+    ["Offset of field: mrbc_context::partial_hook"]
+        [::std::mem::offset_of!(mrbc_context, partial_hook) - 32usize];
+    ["Offset of field: mrbc_context::partial_data"]
+        [::std::mem::offset_of!(mrbc_context, partial_data) - 40usize];
+    ["Offset of field: mrbc_context::target_class"]
+        [::std::mem::offset_of!(mrbc_context, target_class) - 48usize];
+    ["Offset of field: mrbc_context::parser_nerr"]
+        [::std::mem::offset_of!(mrbc_context, parser_nerr) - 64usize];
+};
 impl mrbc_context {
     #[inline]
     pub fn capture_errors(&self) -> mrb_bool {
@@ -3899,59 +2717,17 @@ pub struct mrb_ast_node {
     pub lineno: u16,
     pub filename_index: u16,
 }
-#[test]
-fn bindgen_test_layout_mrb_ast_node() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_ast_node>(),
-        24usize,
-        concat!("Size of: ", stringify!(mrb_ast_node))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_ast_node>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_ast_node))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_ast_node>())).car as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_ast_node),
-            "::",
-            stringify!(car)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_ast_node>())).cdr as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_ast_node),
-            "::",
-            stringify!(cdr)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_ast_node>())).lineno as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_ast_node),
-            "::",
-            stringify!(lineno)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_ast_node>())).filename_index as *const _ as usize },
-        18usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_ast_node),
-            "::",
-            stringify!(filename_index)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_ast_node"][::std::mem::size_of::<mrb_ast_node>() - 24usize];
+    ["Alignment of mrb_ast_node"][::std::mem::align_of::<mrb_ast_node>() - 8usize];
+    ["Offset of field: mrb_ast_node::car"][::std::mem::offset_of!(mrb_ast_node, car) - 0usize];
+    ["Offset of field: mrb_ast_node::cdr"][::std::mem::offset_of!(mrb_ast_node, cdr) - 8usize];
+    ["Offset of field: mrb_ast_node::lineno"]
+        [::std::mem::offset_of!(mrb_ast_node, lineno) - 16usize];
+    ["Offset of field: mrb_ast_node::filename_index"]
+        [::std::mem::offset_of!(mrb_ast_node, filename_index) - 18usize];
+};
 pub const EXPR_BEG: mrb_lex_state_enum = 0;
 pub const EXPR_END: mrb_lex_state_enum = 1;
 pub const EXPR_ENDARG: mrb_lex_state_enum = 2;
@@ -3972,49 +2748,17 @@ pub struct mrb_parser_message {
     pub column: ::std::os::raw::c_int,
     pub message: *mut ::std::os::raw::c_char,
 }
-#[test]
-fn bindgen_test_layout_mrb_parser_message() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_parser_message>(),
-        16usize,
-        concat!("Size of: ", stringify!(mrb_parser_message))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_parser_message>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_parser_message))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_message>())).lineno as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_message),
-            "::",
-            stringify!(lineno)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_message>())).column as *const _ as usize },
-        4usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_message),
-            "::",
-            stringify!(column)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_message>())).message as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_message),
-            "::",
-            stringify!(message)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_parser_message"][::std::mem::size_of::<mrb_parser_message>() - 16usize];
+    ["Alignment of mrb_parser_message"][::std::mem::align_of::<mrb_parser_message>() - 8usize];
+    ["Offset of field: mrb_parser_message::lineno"]
+        [::std::mem::offset_of!(mrb_parser_message, lineno) - 0usize];
+    ["Offset of field: mrb_parser_message::column"]
+        [::std::mem::offset_of!(mrb_parser_message, column) - 4usize];
+    ["Offset of field: mrb_parser_message::message"]
+        [::std::mem::offset_of!(mrb_parser_message, message) - 8usize];
+};
 pub const str_not_parsing: mrb_string_type = 0;
 pub const str_squote: mrb_string_type = 1;
 pub const str_dquote: mrb_string_type = 3;
@@ -4036,61 +2780,20 @@ pub struct mrb_parser_heredoc_info {
     pub term_len: ::std::os::raw::c_int,
     pub doc: *mut mrb_ast_node,
 }
-#[test]
-fn bindgen_test_layout_mrb_parser_heredoc_info() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_parser_heredoc_info>(),
-        32usize,
-        concat!("Size of: ", stringify!(mrb_parser_heredoc_info))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_parser_heredoc_info>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_parser_heredoc_info))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_heredoc_info>())).type_ as *const _ as usize },
-        4usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_heredoc_info),
-            "::",
-            stringify!(type_)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_heredoc_info>())).term as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_heredoc_info),
-            "::",
-            stringify!(term)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<mrb_parser_heredoc_info>())).term_len as *const _ as usize
-        },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_heredoc_info),
-            "::",
-            stringify!(term_len)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_heredoc_info>())).doc as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_heredoc_info),
-            "::",
-            stringify!(doc)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_parser_heredoc_info"][::std::mem::size_of::<mrb_parser_heredoc_info>() - 32usize];
+    ["Alignment of mrb_parser_heredoc_info"]
+        [::std::mem::align_of::<mrb_parser_heredoc_info>() - 8usize];
+    ["Offset of field: mrb_parser_heredoc_info::type_"]
+        [::std::mem::offset_of!(mrb_parser_heredoc_info, type_) - 4usize];
+    ["Offset of field: mrb_parser_heredoc_info::term"]
+        [::std::mem::offset_of!(mrb_parser_heredoc_info, term) - 8usize];
+    ["Offset of field: mrb_parser_heredoc_info::term_len"]
+        [::std::mem::offset_of!(mrb_parser_heredoc_info, term_len) - 16usize];
+    ["Offset of field: mrb_parser_heredoc_info::doc"]
+        [::std::mem::offset_of!(mrb_parser_heredoc_info, doc) - 24usize];
+};
 impl mrb_parser_heredoc_info {
     #[inline]
     pub fn allow_indent(&self) -> mrb_bool {
@@ -4175,400 +2878,84 @@ pub struct mrb_parser_state {
     pub current_filename_index: u16,
     pub jmp: *mut mrb_jmpbuf,
 }
-#[test]
-fn bindgen_test_layout_mrb_parser_state() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_parser_state>(),
-        808usize,
-        concat!("Size of: ", stringify!(mrb_parser_state))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_parser_state>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_parser_state))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).mrb as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(mrb)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).pool as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(pool)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).cells as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(cells)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).s as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(s)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).send as *const _ as usize },
-        32usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(send)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).cxt as *const _ as usize },
-        40usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(cxt)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).filename_sym as *const _ as usize },
-        48usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(filename_sym)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).lineno as *const _ as usize },
-        52usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(lineno)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).column as *const _ as usize },
-        56usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(column)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).lstate as *const _ as usize },
-        60usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(lstate)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).lex_strterm as *const _ as usize },
-        64usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(lex_strterm)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).cond_stack as *const _ as usize },
-        72usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(cond_stack)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).cmdarg_stack as *const _ as usize },
-        76usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(cmdarg_stack)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).paren_nest as *const _ as usize },
-        80usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(paren_nest)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).lpar_beg as *const _ as usize },
-        84usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(lpar_beg)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).in_def as *const _ as usize },
-        88usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(in_def)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).in_single as *const _ as usize },
-        92usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(in_single)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).locals as *const _ as usize },
-        104usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(locals)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).pb as *const _ as usize },
-        112usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(pb)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).tokbuf as *const _ as usize },
-        120usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(tokbuf)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).buf as *const _ as usize },
-        128usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(buf)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).tidx as *const _ as usize },
-        384usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(tidx)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).tsiz as *const _ as usize },
-        388usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(tsiz)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).all_heredocs as *const _ as usize },
-        392usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(all_heredocs)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<mrb_parser_state>())).heredocs_from_nextline as *const _ as usize
-        },
-        400usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(heredocs_from_nextline)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<mrb_parser_state>())).parsing_heredoc as *const _ as usize
-        },
-        408usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(parsing_heredoc)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<mrb_parser_state>())).lex_strterm_before_heredoc as *const _
-                as usize
-        },
-        416usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(lex_strterm_before_heredoc)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).ylval as *const _ as usize },
-        424usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(ylval)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).nerr as *const _ as usize },
-        432usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(nerr)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).nwarn as *const _ as usize },
-        440usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(nwarn)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).tree as *const _ as usize },
-        448usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(tree)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).error_buffer as *const _ as usize },
-        464usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(error_buffer)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).warn_buffer as *const _ as usize },
-        624usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(warn_buffer)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).filename_table as *const _ as usize },
-        784usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(filename_table)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<mrb_parser_state>())).filename_table_length as *const _ as usize
-        },
-        792usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(filename_table_length)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<mrb_parser_state>())).current_filename_index as *const _ as usize
-        },
-        794usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(current_filename_index)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_parser_state>())).jmp as *const _ as usize },
-        800usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_parser_state),
-            "::",
-            stringify!(jmp)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_parser_state"][::std::mem::size_of::<mrb_parser_state>() - 808usize];
+    ["Alignment of mrb_parser_state"][::std::mem::align_of::<mrb_parser_state>() - 8usize];
+    ["Offset of field: mrb_parser_state::mrb"]
+        [::std::mem::offset_of!(mrb_parser_state, mrb) - 0usize];
+    ["Offset of field: mrb_parser_state::pool"]
+        [::std::mem::offset_of!(mrb_parser_state, pool) - 8usize];
+    ["Offset of field: mrb_parser_state::cells"]
+        [::std::mem::offset_of!(mrb_parser_state, cells) - 16usize];
+    ["Offset of field: mrb_parser_state::s"][::std::mem::offset_of!(mrb_parser_state, s) - 24usize];
+    ["Offset of field: mrb_parser_state::send"]
+        [::std::mem::offset_of!(mrb_parser_state, send) - 32usize];
+    ["Offset of field: mrb_parser_state::cxt"]
+        [::std::mem::offset_of!(mrb_parser_state, cxt) - 40usize];
+    ["Offset of field: mrb_parser_state::filename_sym"]
+        [::std::mem::offset_of!(mrb_parser_state, filename_sym) - 48usize];
+    ["Offset of field: mrb_parser_state::lineno"]
+        [::std::mem::offset_of!(mrb_parser_state, lineno) - 52usize];
+    ["Offset of field: mrb_parser_state::column"]
+        [::std::mem::offset_of!(mrb_parser_state, column) - 56usize];
+    ["Offset of field: mrb_parser_state::lstate"]
+        [::std::mem::offset_of!(mrb_parser_state, lstate) - 60usize];
+    ["Offset of field: mrb_parser_state::lex_strterm"]
+        [::std::mem::offset_of!(mrb_parser_state, lex_strterm) - 64usize];
+    ["Offset of field: mrb_parser_state::cond_stack"]
+        [::std::mem::offset_of!(mrb_parser_state, cond_stack) - 72usize];
+    ["Offset of field: mrb_parser_state::cmdarg_stack"]
+        [::std::mem::offset_of!(mrb_parser_state, cmdarg_stack) - 76usize];
+    ["Offset of field: mrb_parser_state::paren_nest"]
+        [::std::mem::offset_of!(mrb_parser_state, paren_nest) - 80usize];
+    ["Offset of field: mrb_parser_state::lpar_beg"]
+        [::std::mem::offset_of!(mrb_parser_state, lpar_beg) - 84usize];
+    ["Offset of field: mrb_parser_state::in_def"]
+        [::std::mem::offset_of!(mrb_parser_state, in_def) - 88usize];
+    ["Offset of field: mrb_parser_state::in_single"]
+        [::std::mem::offset_of!(mrb_parser_state, in_single) - 92usize];
+    ["Offset of field: mrb_parser_state::locals"]
+        [::std::mem::offset_of!(mrb_parser_state, locals) - 104usize];
+    ["Offset of field: mrb_parser_state::pb"]
+        [::std::mem::offset_of!(mrb_parser_state, pb) - 112usize];
+    ["Offset of field: mrb_parser_state::tokbuf"]
+        [::std::mem::offset_of!(mrb_parser_state, tokbuf) - 120usize];
+    ["Offset of field: mrb_parser_state::buf"]
+        [::std::mem::offset_of!(mrb_parser_state, buf) - 128usize];
+    ["Offset of field: mrb_parser_state::tidx"]
+        [::std::mem::offset_of!(mrb_parser_state, tidx) - 384usize];
+    ["Offset of field: mrb_parser_state::tsiz"]
+        [::std::mem::offset_of!(mrb_parser_state, tsiz) - 388usize];
+    ["Offset of field: mrb_parser_state::all_heredocs"]
+        [::std::mem::offset_of!(mrb_parser_state, all_heredocs) - 392usize];
+    ["Offset of field: mrb_parser_state::heredocs_from_nextline"]
+        [::std::mem::offset_of!(mrb_parser_state, heredocs_from_nextline) - 400usize];
+    ["Offset of field: mrb_parser_state::parsing_heredoc"]
+        [::std::mem::offset_of!(mrb_parser_state, parsing_heredoc) - 408usize];
+    ["Offset of field: mrb_parser_state::lex_strterm_before_heredoc"]
+        [::std::mem::offset_of!(mrb_parser_state, lex_strterm_before_heredoc) - 416usize];
+    ["Offset of field: mrb_parser_state::ylval"]
+        [::std::mem::offset_of!(mrb_parser_state, ylval) - 424usize];
+    ["Offset of field: mrb_parser_state::nerr"]
+        [::std::mem::offset_of!(mrb_parser_state, nerr) - 432usize];
+    ["Offset of field: mrb_parser_state::nwarn"]
+        [::std::mem::offset_of!(mrb_parser_state, nwarn) - 440usize];
+    ["Offset of field: mrb_parser_state::tree"]
+        [::std::mem::offset_of!(mrb_parser_state, tree) - 448usize];
+    ["Offset of field: mrb_parser_state::error_buffer"]
+        [::std::mem::offset_of!(mrb_parser_state, error_buffer) - 464usize];
+    ["Offset of field: mrb_parser_state::warn_buffer"]
+        [::std::mem::offset_of!(mrb_parser_state, warn_buffer) - 624usize];
+    ["Offset of field: mrb_parser_state::filename_table"]
+        [::std::mem::offset_of!(mrb_parser_state, filename_table) - 784usize];
+    ["Offset of field: mrb_parser_state::filename_table_length"]
+        [::std::mem::offset_of!(mrb_parser_state, filename_table_length) - 792usize];
+    ["Offset of field: mrb_parser_state::current_filename_index"]
+        [::std::mem::offset_of!(mrb_parser_state, current_filename_index) - 794usize];
+    ["Offset of field: mrb_parser_state::jmp"]
+        [::std::mem::offset_of!(mrb_parser_state, jmp) - 800usize];
+};
 impl ::std::fmt::Debug for mrb_parser_state {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write ! ( f , "mrb_parser_state {{ mrb: {:?}, pool: {:?}, cells: {:?}, s: {:?}, send: {:?}, cxt: {:?}, filename_sym: {:?}, lineno: {:?}, column: {:?}, lstate: {:?}, lex_strterm: {:?}, cond_stack: {:?}, cmdarg_stack: {:?}, paren_nest: {:?}, lpar_beg: {:?}, in_def: {:?}, in_single: {:?}, cmd_start : {:?}, locals: {:?}, pb: {:?}, tokbuf: {:?}, buf: [{}], tidx: {:?}, tsiz: {:?}, all_heredocs: {:?}, heredocs_from_nextline: {:?}, parsing_heredoc: {:?}, lex_strterm_before_heredoc: {:?}, ylval: {:?}, nerr: {:?}, nwarn: {:?}, tree: {:?}, no_optimize : {:?}, on_eval : {:?}, capture_errors : {:?}, error_buffer: {:?}, warn_buffer: {:?}, filename_table: {:?}, filename_table_length: {:?}, current_filename_index: {:?}, jmp: {:?} }}" , self . mrb , self . pool , self . cells , self . s , self . send , self . cxt , self . filename_sym , self . lineno , self . column , self . lstate , self . lex_strterm , self . cond_stack , self . cmdarg_stack , self . paren_nest , self . lpar_beg , self . in_def , self . in_single , self . cmd_start ( ) , self . locals , self . pb , self . tokbuf , self . buf . iter ( ) . enumerate ( ) . map ( | ( i , v ) | format ! ( "{}{:?}" , if i > 0 { ", " } else { "" } , v ) ) . collect :: < String > ( ) , self . tidx , self . tsiz , self . all_heredocs , self . heredocs_from_nextline , self . parsing_heredoc , self . lex_strterm_before_heredoc , self . ylval , self . nerr , self . nwarn , self . tree , self . no_optimize ( ) , self . on_eval ( ) , self . capture_errors ( ) , self . error_buffer , self . warn_buffer , self . filename_table , self . filename_table_length , self . current_filename_index , self . jmp )
@@ -4720,21 +3107,512 @@ extern "C" {
         cxt: *mut mrbc_context,
     ) -> mrb_value;
 }
-extern "C" {
-    pub fn mrb_sys_fail(mrb: *mut mrb_state, mesg: *const ::std::os::raw::c_char);
+/// Custom data type description.
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mrb_data_type {
+    pub struct_name: *const ::std::os::raw::c_char,
+    pub dfree: ::std::option::Option<
+        unsafe extern "C" fn(mrb: *mut mrb_state, arg1: *mut ::std::os::raw::c_void),
+    >,
 }
-extern "C" {
-    pub fn mrb_exc_new_str(mrb: *mut mrb_state, c: *mut RClass, str: mrb_value) -> mrb_value;
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_data_type"][::std::mem::size_of::<mrb_data_type>() - 16usize];
+    ["Alignment of mrb_data_type"][::std::mem::align_of::<mrb_data_type>() - 8usize];
+    ["Offset of field: mrb_data_type::struct_name"]
+        [::std::mem::offset_of!(mrb_data_type, struct_name) - 0usize];
+    ["Offset of field: mrb_data_type::dfree"]
+        [::std::mem::offset_of!(mrb_data_type, dfree) - 8usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct RData {
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize], u32>,
+    pub c: *mut RClass,
+    pub gcnext: *mut RBasic,
+    pub iv: *mut iv_tbl,
+    pub type_: *const mrb_data_type,
+    pub data: *mut ::std::os::raw::c_void,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RData"][::std::mem::size_of::<RData>() - 48usize];
+    ["Alignment of RData"][::std::mem::align_of::<RData>() - 8usize];
+    ["Offset of field: RData::c"][::std::mem::offset_of!(RData, c) - 8usize];
+    ["Offset of field: RData::gcnext"][::std::mem::offset_of!(RData, gcnext) - 16usize];
+    ["Offset of field: RData::iv"][::std::mem::offset_of!(RData, iv) - 24usize];
+    ["Offset of field: RData::type_"][::std::mem::offset_of!(RData, type_) - 32usize];
+    ["Offset of field: RData::data"][::std::mem::offset_of!(RData, data) - 40usize];
+};
+impl RData {
+    #[inline]
+    pub fn tt(&self) -> mrb_vtype {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 8u8) as u32) }
+    }
+    #[inline]
+    pub fn set_tt(&mut self, val: mrb_vtype) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 8u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn color(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(8usize, 3u8) as u32) }
+    }
+    #[inline]
+    pub fn set_color(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(8usize, 3u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn flags(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(11usize, 21u8) as u32) }
+    }
+    #[inline]
+    pub fn set_flags(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(11usize, 21u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(
+        tt: mrb_vtype,
+        color: u32,
+        flags: u32,
+    ) -> __BindgenBitfieldUnit<[u8; 4usize], u32> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize], u32> =
+            Default::default();
+        __bindgen_bitfield_unit.set(0usize, 8u8, {
+            let tt: u32 = unsafe { ::std::mem::transmute(tt) };
+            tt as u64
+        });
+        __bindgen_bitfield_unit.set(8usize, 3u8, {
+            let color: u32 = unsafe { ::std::mem::transmute(color) };
+            color as u64
+        });
+        __bindgen_bitfield_unit.set(11usize, 21u8, {
+            let flags: u32 = unsafe { ::std::mem::transmute(flags) };
+            flags as u64
+        });
+        __bindgen_bitfield_unit
+    }
 }
 extern "C" {
-    pub fn mrb_make_exception(
+    pub fn mrb_data_object_alloc(
         mrb: *mut mrb_state,
-        argc: mrb_int,
-        argv: *const mrb_value,
-    ) -> mrb_value;
+        klass: *mut RClass,
+        datap: *mut ::std::os::raw::c_void,
+        type_: *const mrb_data_type,
+    ) -> *mut RData;
 }
 extern "C" {
-    pub fn mrb_exc_backtrace(mrb: *mut mrb_state, exc: mrb_value) -> mrb_value;
+    pub fn mrb_data_check_type(mrb: *mut mrb_state, arg1: mrb_value, arg2: *const mrb_data_type);
+}
+extern "C" {
+    pub fn mrb_data_get_ptr(
+        mrb: *mut mrb_state,
+        arg1: mrb_value,
+        arg2: *const mrb_data_type,
+    ) -> *mut ::std::os::raw::c_void;
+}
+extern "C" {
+    pub fn mrb_data_check_get_ptr(
+        mrb: *mut mrb_state,
+        arg1: mrb_value,
+        arg2: *const mrb_data_type,
+    ) -> *mut ::std::os::raw::c_void;
+}
+pub const IREP_TT_STRING: irep_pool_type = 0;
+pub const IREP_TT_FIXNUM: irep_pool_type = 1;
+pub const IREP_TT_FLOAT: irep_pool_type = 2;
+/// Compiled mruby scripts.
+pub type irep_pool_type = u32;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mrb_locals {
+    pub name: mrb_sym,
+    pub r: u16,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_locals"][::std::mem::size_of::<mrb_locals>() - 8usize];
+    ["Alignment of mrb_locals"][::std::mem::align_of::<mrb_locals>() - 4usize];
+    ["Offset of field: mrb_locals::name"][::std::mem::offset_of!(mrb_locals, name) - 0usize];
+    ["Offset of field: mrb_locals::r"][::std::mem::offset_of!(mrb_locals, r) - 4usize];
+};
+/// Program data array struct
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mrb_irep {
+    pub nlocals: u16,
+    pub nregs: u16,
+    pub flags: u8,
+    pub iseq: *mut mrb_code,
+    pub pool: *mut mrb_value,
+    pub syms: *mut mrb_sym,
+    pub reps: *mut *mut mrb_irep,
+    pub lv: *mut mrb_locals,
+    pub debug_info: *mut mrb_irep_debug_info,
+    pub ilen: u16,
+    pub plen: u16,
+    pub slen: u16,
+    pub rlen: u16,
+    pub refcnt: u32,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_irep"][::std::mem::size_of::<mrb_irep>() - 72usize];
+    ["Alignment of mrb_irep"][::std::mem::align_of::<mrb_irep>() - 8usize];
+    ["Offset of field: mrb_irep::nlocals"][::std::mem::offset_of!(mrb_irep, nlocals) - 0usize];
+    ["Offset of field: mrb_irep::nregs"][::std::mem::offset_of!(mrb_irep, nregs) - 2usize];
+    ["Offset of field: mrb_irep::flags"][::std::mem::offset_of!(mrb_irep, flags) - 4usize];
+    ["Offset of field: mrb_irep::iseq"][::std::mem::offset_of!(mrb_irep, iseq) - 8usize];
+    ["Offset of field: mrb_irep::pool"][::std::mem::offset_of!(mrb_irep, pool) - 16usize];
+    ["Offset of field: mrb_irep::syms"][::std::mem::offset_of!(mrb_irep, syms) - 24usize];
+    ["Offset of field: mrb_irep::reps"][::std::mem::offset_of!(mrb_irep, reps) - 32usize];
+    ["Offset of field: mrb_irep::lv"][::std::mem::offset_of!(mrb_irep, lv) - 40usize];
+    ["Offset of field: mrb_irep::debug_info"]
+        [::std::mem::offset_of!(mrb_irep, debug_info) - 48usize];
+    ["Offset of field: mrb_irep::ilen"][::std::mem::offset_of!(mrb_irep, ilen) - 56usize];
+    ["Offset of field: mrb_irep::plen"][::std::mem::offset_of!(mrb_irep, plen) - 58usize];
+    ["Offset of field: mrb_irep::slen"][::std::mem::offset_of!(mrb_irep, slen) - 60usize];
+    ["Offset of field: mrb_irep::rlen"][::std::mem::offset_of!(mrb_irep, rlen) - 62usize];
+    ["Offset of field: mrb_irep::refcnt"][::std::mem::offset_of!(mrb_irep, refcnt) - 64usize];
+};
+extern "C" {
+    pub fn mrb_add_irep(mrb: *mut mrb_state) -> *mut mrb_irep;
+}
+extern "C" {
+    /// load mruby bytecode functions
+    /// Please note! Currently due to interactions with the GC calling these functions will
+    /// leak one RProc object per function call.
+    /// To prevent this save the current memory arena before calling and restore the arena
+    /// right after, like so
+    /// int ai = mrb_gc_arena_save(mrb);
+    /// mrb_value status = mrb_load_irep(mrb, buffer);
+    /// mrb_gc_arena_restore(mrb, ai);
+    pub fn mrb_load_irep(arg1: *mut mrb_state, arg2: *const u8) -> mrb_value;
+}
+extern "C" {
+    pub fn mrb_load_irep_cxt(
+        arg1: *mut mrb_state,
+        arg2: *const u8,
+        arg3: *mut mrbc_context,
+    ) -> mrb_value;
+}
+extern "C" {
+    pub fn mrb_irep_free(arg1: *mut mrb_state, arg2: *mut mrb_irep);
+}
+extern "C" {
+    pub fn mrb_irep_incref(arg1: *mut mrb_state, arg2: *mut mrb_irep);
+}
+extern "C" {
+    pub fn mrb_irep_decref(arg1: *mut mrb_state, arg2: *mut mrb_irep);
+}
+extern "C" {
+    pub fn mrb_irep_cutref(arg1: *mut mrb_state, arg2: *mut mrb_irep);
+}
+extern "C" {
+    pub fn mrb_irep_remove_lv(mrb: *mut mrb_state, irep: *mut mrb_irep);
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mrb_insn_data {
+    pub insn: u8,
+    pub a: u16,
+    pub b: u16,
+    pub c: u8,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_insn_data"][::std::mem::size_of::<mrb_insn_data>() - 8usize];
+    ["Alignment of mrb_insn_data"][::std::mem::align_of::<mrb_insn_data>() - 2usize];
+    ["Offset of field: mrb_insn_data::insn"][::std::mem::offset_of!(mrb_insn_data, insn) - 0usize];
+    ["Offset of field: mrb_insn_data::a"][::std::mem::offset_of!(mrb_insn_data, a) - 2usize];
+    ["Offset of field: mrb_insn_data::b"][::std::mem::offset_of!(mrb_insn_data, b) - 4usize];
+    ["Offset of field: mrb_insn_data::c"][::std::mem::offset_of!(mrb_insn_data, c) - 6usize];
+};
+extern "C" {
+    pub fn mrb_decode_insn(pc: *mut mrb_code) -> mrb_insn_data;
+}
+pub const mrb_debug_line_ary: mrb_debug_line_type = 0;
+pub const mrb_debug_line_flat_map: mrb_debug_line_type = 1;
+/// MRuby Debugging.
+pub type mrb_debug_line_type = u32;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mrb_irep_debug_info_line {
+    pub start_pos: u32,
+    pub line: u16,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_irep_debug_info_line"]
+        [::std::mem::size_of::<mrb_irep_debug_info_line>() - 8usize];
+    ["Alignment of mrb_irep_debug_info_line"]
+        [::std::mem::align_of::<mrb_irep_debug_info_line>() - 4usize];
+    ["Offset of field: mrb_irep_debug_info_line::start_pos"]
+        [::std::mem::offset_of!(mrb_irep_debug_info_line, start_pos) - 0usize];
+    ["Offset of field: mrb_irep_debug_info_line::line"]
+        [::std::mem::offset_of!(mrb_irep_debug_info_line, line) - 4usize];
+};
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mrb_irep_debug_info_file {
+    pub start_pos: u32,
+    pub filename_sym: mrb_sym,
+    pub line_entry_count: u32,
+    pub line_type: mrb_debug_line_type,
+    pub lines: mrb_irep_debug_info_file__bindgen_ty_1,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union mrb_irep_debug_info_file__bindgen_ty_1 {
+    pub ptr: *mut ::std::os::raw::c_void,
+    pub flat_map: *mut mrb_irep_debug_info_line,
+    pub ary: *mut u16,
+    _bindgen_union_align: u64,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_irep_debug_info_file__bindgen_ty_1"]
+        [::std::mem::size_of::<mrb_irep_debug_info_file__bindgen_ty_1>() - 8usize];
+    ["Alignment of mrb_irep_debug_info_file__bindgen_ty_1"]
+        [::std::mem::align_of::<mrb_irep_debug_info_file__bindgen_ty_1>() - 8usize];
+    ["Offset of field: mrb_irep_debug_info_file__bindgen_ty_1::ptr"]
+        [::std::mem::offset_of!(mrb_irep_debug_info_file__bindgen_ty_1, ptr) - 0usize];
+    ["Offset of field: mrb_irep_debug_info_file__bindgen_ty_1::flat_map"]
+        [::std::mem::offset_of!(mrb_irep_debug_info_file__bindgen_ty_1, flat_map) - 0usize];
+    ["Offset of field: mrb_irep_debug_info_file__bindgen_ty_1::ary"]
+        [::std::mem::offset_of!(mrb_irep_debug_info_file__bindgen_ty_1, ary) - 0usize];
+};
+impl ::std::fmt::Debug for mrb_irep_debug_info_file__bindgen_ty_1 {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "mrb_irep_debug_info_file__bindgen_ty_1 {{ union }}")
+    }
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_irep_debug_info_file"]
+        [::std::mem::size_of::<mrb_irep_debug_info_file>() - 24usize];
+    ["Alignment of mrb_irep_debug_info_file"]
+        [::std::mem::align_of::<mrb_irep_debug_info_file>() - 8usize];
+    ["Offset of field: mrb_irep_debug_info_file::start_pos"]
+        [::std::mem::offset_of!(mrb_irep_debug_info_file, start_pos) - 0usize];
+    ["Offset of field: mrb_irep_debug_info_file::filename_sym"]
+        [::std::mem::offset_of!(mrb_irep_debug_info_file, filename_sym) - 4usize];
+    ["Offset of field: mrb_irep_debug_info_file::line_entry_count"]
+        [::std::mem::offset_of!(mrb_irep_debug_info_file, line_entry_count) - 8usize];
+    ["Offset of field: mrb_irep_debug_info_file::line_type"]
+        [::std::mem::offset_of!(mrb_irep_debug_info_file, line_type) - 12usize];
+    ["Offset of field: mrb_irep_debug_info_file::lines"]
+        [::std::mem::offset_of!(mrb_irep_debug_info_file, lines) - 16usize];
+};
+impl ::std::fmt::Debug for mrb_irep_debug_info_file {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(
+            f,
+            "mrb_irep_debug_info_file {{ start_pos: {:?}, filename_sym: {:?}, line_entry_count: {:?}, line_type: {:?}, lines: {:?} }}",
+            self.start_pos, self.filename_sym, self.line_entry_count, self.line_type, self.lines
+        )
+    }
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct mrb_irep_debug_info {
+    pub pc_count: u32,
+    pub flen: u16,
+    pub files: *mut *mut mrb_irep_debug_info_file,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_irep_debug_info"][::std::mem::size_of::<mrb_irep_debug_info>() - 16usize];
+    ["Alignment of mrb_irep_debug_info"][::std::mem::align_of::<mrb_irep_debug_info>() - 8usize];
+    ["Offset of field: mrb_irep_debug_info::pc_count"]
+        [::std::mem::offset_of!(mrb_irep_debug_info, pc_count) - 0usize];
+    ["Offset of field: mrb_irep_debug_info::flen"]
+        [::std::mem::offset_of!(mrb_irep_debug_info, flen) - 4usize];
+    ["Offset of field: mrb_irep_debug_info::files"]
+        [::std::mem::offset_of!(mrb_irep_debug_info, files) - 8usize];
+};
+extern "C" {
+    /// get line from irep's debug info and program counter
+    /// @return returns NULL if not found
+    pub fn mrb_debug_get_filename(
+        mrb: *mut mrb_state,
+        irep: *mut mrb_irep,
+        pc: isize,
+    ) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    /// get line from irep's debug info and program counter
+    /// @return returns -1 if not found
+    pub fn mrb_debug_get_line(mrb: *mut mrb_state, irep: *mut mrb_irep, pc: isize) -> i32;
+}
+extern "C" {
+    pub fn mrb_debug_info_alloc(
+        mrb: *mut mrb_state,
+        irep: *mut mrb_irep,
+    ) -> *mut mrb_irep_debug_info;
+}
+extern "C" {
+    pub fn mrb_debug_info_append_file(
+        mrb: *mut mrb_state,
+        info: *mut mrb_irep_debug_info,
+        filename: *const ::std::os::raw::c_char,
+        lines: *mut u16,
+        start_pos: u32,
+        end_pos: u32,
+    ) -> *mut mrb_irep_debug_info_file;
+}
+extern "C" {
+    pub fn mrb_debug_info_free(mrb: *mut mrb_state, d: *mut mrb_irep_debug_info);
+}
+extern "C" {
+    pub fn mrb_dump_irep(
+        mrb: *mut mrb_state,
+        irep: *mut mrb_irep,
+        flags: u8,
+        bin: *mut *mut u8,
+        bin_size: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn mrb_read_irep(arg1: *mut mrb_state, arg2: *const u8) -> *mut mrb_irep;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rite_binary_header {
+    pub binary_ident: [u8; 4usize],
+    pub binary_version: [u8; 4usize],
+    pub binary_crc: [u8; 2usize],
+    pub binary_size: [u8; 4usize],
+    pub compiler_name: [u8; 4usize],
+    pub compiler_version: [u8; 4usize],
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of rite_binary_header"][::std::mem::size_of::<rite_binary_header>() - 22usize];
+    ["Alignment of rite_binary_header"][::std::mem::align_of::<rite_binary_header>() - 1usize];
+    ["Offset of field: rite_binary_header::binary_ident"]
+        [::std::mem::offset_of!(rite_binary_header, binary_ident) - 0usize];
+    ["Offset of field: rite_binary_header::binary_version"]
+        [::std::mem::offset_of!(rite_binary_header, binary_version) - 4usize];
+    ["Offset of field: rite_binary_header::binary_crc"]
+        [::std::mem::offset_of!(rite_binary_header, binary_crc) - 8usize];
+    ["Offset of field: rite_binary_header::binary_size"]
+        [::std::mem::offset_of!(rite_binary_header, binary_size) - 10usize];
+    ["Offset of field: rite_binary_header::compiler_name"]
+        [::std::mem::offset_of!(rite_binary_header, compiler_name) - 14usize];
+    ["Offset of field: rite_binary_header::compiler_version"]
+        [::std::mem::offset_of!(rite_binary_header, compiler_version) - 18usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rite_section_header {
+    pub section_ident: [u8; 4usize],
+    pub section_size: [u8; 4usize],
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of rite_section_header"][::std::mem::size_of::<rite_section_header>() - 8usize];
+    ["Alignment of rite_section_header"][::std::mem::align_of::<rite_section_header>() - 1usize];
+    ["Offset of field: rite_section_header::section_ident"]
+        [::std::mem::offset_of!(rite_section_header, section_ident) - 0usize];
+    ["Offset of field: rite_section_header::section_size"]
+        [::std::mem::offset_of!(rite_section_header, section_size) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rite_section_irep_header {
+    pub section_ident: [u8; 4usize],
+    pub section_size: [u8; 4usize],
+    pub rite_version: [u8; 4usize],
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of rite_section_irep_header"]
+        [::std::mem::size_of::<rite_section_irep_header>() - 12usize];
+    ["Alignment of rite_section_irep_header"]
+        [::std::mem::align_of::<rite_section_irep_header>() - 1usize];
+    ["Offset of field: rite_section_irep_header::section_ident"]
+        [::std::mem::offset_of!(rite_section_irep_header, section_ident) - 0usize];
+    ["Offset of field: rite_section_irep_header::section_size"]
+        [::std::mem::offset_of!(rite_section_irep_header, section_size) - 4usize];
+    ["Offset of field: rite_section_irep_header::rite_version"]
+        [::std::mem::offset_of!(rite_section_irep_header, rite_version) - 8usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rite_section_debug_header {
+    pub section_ident: [u8; 4usize],
+    pub section_size: [u8; 4usize],
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of rite_section_debug_header"]
+        [::std::mem::size_of::<rite_section_debug_header>() - 8usize];
+    ["Alignment of rite_section_debug_header"]
+        [::std::mem::align_of::<rite_section_debug_header>() - 1usize];
+    ["Offset of field: rite_section_debug_header::section_ident"]
+        [::std::mem::offset_of!(rite_section_debug_header, section_ident) - 0usize];
+    ["Offset of field: rite_section_debug_header::section_size"]
+        [::std::mem::offset_of!(rite_section_debug_header, section_size) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rite_section_lv_header {
+    pub section_ident: [u8; 4usize],
+    pub section_size: [u8; 4usize],
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of rite_section_lv_header"][::std::mem::size_of::<rite_section_lv_header>() - 8usize];
+    ["Alignment of rite_section_lv_header"]
+        [::std::mem::align_of::<rite_section_lv_header>() - 1usize];
+    ["Offset of field: rite_section_lv_header::section_ident"]
+        [::std::mem::offset_of!(rite_section_lv_header, section_ident) - 0usize];
+    ["Offset of field: rite_section_lv_header::section_size"]
+        [::std::mem::offset_of!(rite_section_lv_header, section_size) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rite_binary_footer {
+    pub section_ident: [u8; 4usize],
+    pub section_size: [u8; 4usize],
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of rite_binary_footer"][::std::mem::size_of::<rite_binary_footer>() - 8usize];
+    ["Alignment of rite_binary_footer"][::std::mem::align_of::<rite_binary_footer>() - 1usize];
+    ["Offset of field: rite_binary_footer::section_ident"]
+        [::std::mem::offset_of!(rite_binary_footer, section_ident) - 0usize];
+    ["Offset of field: rite_binary_footer::section_size"]
+        [::std::mem::offset_of!(rite_binary_footer, section_size) - 4usize];
+};
+extern "C" {
+    pub fn calc_crc_16_ccitt(src: *const u8, nbytes: usize, crc: u16) -> u16;
+}
+extern "C" {
+    pub fn mrb_sys_fail(mrb: *mut mrb_state, mesg: *const ::std::os::raw::c_char);
+}
+extern "C" {
+    pub fn mrb_exc_new_str(mrb: *mut mrb_state, c: *mut RClass, str: mrb_value) -> mrb_value;
+}
+extern "C" {
+    pub fn mrb_make_exception(
+        mrb: *mut mrb_state,
+        argc: mrb_int,
+        argv: *const mrb_value,
+    ) -> mrb_value;
+}
+extern "C" {
+    pub fn mrb_exc_backtrace(mrb: *mut mrb_state, exc: mrb_value) -> mrb_value;
 }
 extern "C" {
     pub fn mrb_get_backtrace(mrb: *mut mrb_state) -> mrb_value;
@@ -4810,44 +3688,15 @@ pub struct RHash {
     pub iv: *mut iv_tbl,
     pub ht: *mut htable,
 }
-#[test]
-fn bindgen_test_layout_RHash() {
-    assert_eq!(
-        ::std::mem::size_of::<RHash>(),
-        40usize,
-        concat!("Size of: ", stringify!(RHash))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RHash>(),
-        8usize,
-        concat!("Alignment of ", stringify!(RHash))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RHash>())).c as *const _ as usize },
-        8usize,
-        concat!("Offset of field: ", stringify!(RHash), "::", stringify!(c))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RHash>())).gcnext as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RHash),
-            "::",
-            stringify!(gcnext)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RHash>())).iv as *const _ as usize },
-        24usize,
-        concat!("Offset of field: ", stringify!(RHash), "::", stringify!(iv))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RHash>())).ht as *const _ as usize },
-        32usize,
-        concat!("Offset of field: ", stringify!(RHash), "::", stringify!(ht))
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RHash"][::std::mem::size_of::<RHash>() - 40usize];
+    ["Alignment of RHash"][::std::mem::align_of::<RHash>() - 8usize];
+    ["Offset of field: RHash::c"][::std::mem::offset_of!(RHash, c) - 8usize];
+    ["Offset of field: RHash::gcnext"][::std::mem::offset_of!(RHash, gcnext) - 16usize];
+    ["Offset of field: RHash::iv"][::std::mem::offset_of!(RHash, iv) - 24usize];
+    ["Offset of field: RHash::ht"][::std::mem::offset_of!(RHash, ht) - 32usize];
+};
 impl RHash {
     #[inline]
     pub fn tt(&self) -> mrb_vtype {
@@ -4964,39 +3813,13 @@ pub struct mrb_hash_value {
     pub v: mrb_value,
     pub n: mrb_int,
 }
-#[test]
-fn bindgen_test_layout_mrb_hash_value() {
-    assert_eq!(
-        ::std::mem::size_of::<mrb_hash_value>(),
-        24usize,
-        concat!("Size of: ", stringify!(mrb_hash_value))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<mrb_hash_value>(),
-        8usize,
-        concat!("Alignment of ", stringify!(mrb_hash_value))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_hash_value>())).v as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_hash_value),
-            "::",
-            stringify!(v)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<mrb_hash_value>())).n as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(mrb_hash_value),
-            "::",
-            stringify!(n)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_hash_value"][::std::mem::size_of::<mrb_hash_value>() - 24usize];
+    ["Alignment of mrb_hash_value"][::std::mem::align_of::<mrb_hash_value>() - 8usize];
+    ["Offset of field: mrb_hash_value::v"][::std::mem::offset_of!(mrb_hash_value, v) - 0usize];
+    ["Offset of field: mrb_hash_value::n"][::std::mem::offset_of!(mrb_hash_value, n) - 16usize];
+};
 impl ::std::fmt::Debug for mrb_hash_value {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(f, "mrb_hash_value {{ v: {:?}, n: {:?} }}", self.v, self.n)
@@ -5027,6 +3850,415 @@ extern "C" {
         p: *mut ::std::os::raw::c_void,
     );
 }
+/// Proc class
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct REnv {
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize], u32>,
+    pub c: *mut RClass,
+    pub gcnext: *mut RBasic,
+    pub stack: *mut mrb_value,
+    pub cxt: *mut mrb_context,
+    pub mid: mrb_sym,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of REnv"][::std::mem::size_of::<REnv>() - 48usize];
+    ["Alignment of REnv"][::std::mem::align_of::<REnv>() - 8usize];
+    ["Offset of field: REnv::c"][::std::mem::offset_of!(REnv, c) - 8usize];
+    ["Offset of field: REnv::gcnext"][::std::mem::offset_of!(REnv, gcnext) - 16usize];
+    ["Offset of field: REnv::stack"][::std::mem::offset_of!(REnv, stack) - 24usize];
+    ["Offset of field: REnv::cxt"][::std::mem::offset_of!(REnv, cxt) - 32usize];
+    ["Offset of field: REnv::mid"][::std::mem::offset_of!(REnv, mid) - 40usize];
+};
+impl REnv {
+    #[inline]
+    pub fn tt(&self) -> mrb_vtype {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 8u8) as u32) }
+    }
+    #[inline]
+    pub fn set_tt(&mut self, val: mrb_vtype) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 8u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn color(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(8usize, 3u8) as u32) }
+    }
+    #[inline]
+    pub fn set_color(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(8usize, 3u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn flags(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(11usize, 21u8) as u32) }
+    }
+    #[inline]
+    pub fn set_flags(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(11usize, 21u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(
+        tt: mrb_vtype,
+        color: u32,
+        flags: u32,
+    ) -> __BindgenBitfieldUnit<[u8; 4usize], u32> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize], u32> =
+            Default::default();
+        __bindgen_bitfield_unit.set(0usize, 8u8, {
+            let tt: u32 = unsafe { ::std::mem::transmute(tt) };
+            tt as u64
+        });
+        __bindgen_bitfield_unit.set(8usize, 3u8, {
+            let color: u32 = unsafe { ::std::mem::transmute(color) };
+            color as u64
+        });
+        __bindgen_bitfield_unit.set(11usize, 21u8, {
+            let flags: u32 = unsafe { ::std::mem::transmute(flags) };
+            flags as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+extern "C" {
+    pub fn mrb_env_unshare(arg1: *mut mrb_state, arg2: *mut REnv);
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct RProc {
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize], u32>,
+    pub c: *mut RClass,
+    pub gcnext: *mut RBasic,
+    pub body: RProc__bindgen_ty_1,
+    pub upper: *mut RProc,
+    pub e: RProc__bindgen_ty_2,
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union RProc__bindgen_ty_1 {
+    pub irep: *mut mrb_irep,
+    pub func: mrb_func_t,
+    _bindgen_union_align: u64,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RProc__bindgen_ty_1"][::std::mem::size_of::<RProc__bindgen_ty_1>() - 8usize];
+    ["Alignment of RProc__bindgen_ty_1"][::std::mem::align_of::<RProc__bindgen_ty_1>() - 8usize];
+    ["Offset of field: RProc__bindgen_ty_1::irep"]
+        [::std::mem::offset_of!(RProc__bindgen_ty_1, irep) - 0usize];
+    ["Offset of field: RProc__bindgen_ty_1::func"]
+        [::std::mem::offset_of!(RProc__bindgen_ty_1, func) - 0usize];
+};
+impl ::std::fmt::Debug for RProc__bindgen_ty_1 {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "RProc__bindgen_ty_1 {{ union }}")
+    }
+}
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union RProc__bindgen_ty_2 {
+    pub target_class: *mut RClass,
+    pub env: *mut REnv,
+    _bindgen_union_align: u64,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RProc__bindgen_ty_2"][::std::mem::size_of::<RProc__bindgen_ty_2>() - 8usize];
+    ["Alignment of RProc__bindgen_ty_2"][::std::mem::align_of::<RProc__bindgen_ty_2>() - 8usize];
+    ["Offset of field: RProc__bindgen_ty_2::target_class"]
+        [::std::mem::offset_of!(RProc__bindgen_ty_2, target_class) - 0usize];
+    ["Offset of field: RProc__bindgen_ty_2::env"]
+        [::std::mem::offset_of!(RProc__bindgen_ty_2, env) - 0usize];
+};
+impl ::std::fmt::Debug for RProc__bindgen_ty_2 {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "RProc__bindgen_ty_2 {{ union }}")
+    }
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RProc"][::std::mem::size_of::<RProc>() - 48usize];
+    ["Alignment of RProc"][::std::mem::align_of::<RProc>() - 8usize];
+    ["Offset of field: RProc::c"][::std::mem::offset_of!(RProc, c) - 8usize];
+    ["Offset of field: RProc::gcnext"][::std::mem::offset_of!(RProc, gcnext) - 16usize];
+    ["Offset of field: RProc::body"][::std::mem::offset_of!(RProc, body) - 24usize];
+    ["Offset of field: RProc::upper"][::std::mem::offset_of!(RProc, upper) - 32usize];
+    ["Offset of field: RProc::e"][::std::mem::offset_of!(RProc, e) - 40usize];
+};
+impl ::std::fmt::Debug for RProc {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(
+            f,
+            "RProc {{ tt : {:?}, color : {:?}, flags : {:?}, c: {:?}, gcnext: {:?}, body: {:?}, upper: {:?}, e: {:?} }}",
+            self.tt(),
+            self.color(),
+            self.flags(),
+            self.c,
+            self.gcnext,
+            self.body,
+            self.upper,
+            self.e
+        )
+    }
+}
+impl RProc {
+    #[inline]
+    pub fn tt(&self) -> mrb_vtype {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 8u8) as u32) }
+    }
+    #[inline]
+    pub fn set_tt(&mut self, val: mrb_vtype) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 8u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn color(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(8usize, 3u8) as u32) }
+    }
+    #[inline]
+    pub fn set_color(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(8usize, 3u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn flags(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(11usize, 21u8) as u32) }
+    }
+    #[inline]
+    pub fn set_flags(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(11usize, 21u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(
+        tt: mrb_vtype,
+        color: u32,
+        flags: u32,
+    ) -> __BindgenBitfieldUnit<[u8; 4usize], u32> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize], u32> =
+            Default::default();
+        __bindgen_bitfield_unit.set(0usize, 8u8, {
+            let tt: u32 = unsafe { ::std::mem::transmute(tt) };
+            tt as u64
+        });
+        __bindgen_bitfield_unit.set(8usize, 3u8, {
+            let color: u32 = unsafe { ::std::mem::transmute(color) };
+            color as u64
+        });
+        __bindgen_bitfield_unit.set(11usize, 21u8, {
+            let flags: u32 = unsafe { ::std::mem::transmute(flags) };
+            flags as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+extern "C" {
+    pub fn mrb_proc_new(arg1: *mut mrb_state, arg2: *mut mrb_irep) -> *mut RProc;
+}
+extern "C" {
+    pub fn mrb_closure_new(arg1: *mut mrb_state, arg2: *mut mrb_irep) -> *mut RProc;
+}
+extern "C" {
+    pub fn mrb_proc_new_cfunc(arg1: *mut mrb_state, arg2: mrb_func_t) -> *mut RProc;
+}
+extern "C" {
+    pub fn mrb_closure_new_cfunc(
+        mrb: *mut mrb_state,
+        func: mrb_func_t,
+        nlocals: ::std::os::raw::c_int,
+    ) -> *mut RProc;
+}
+extern "C" {
+    pub fn mrb_proc_copy(a: *mut RProc, b: *mut RProc);
+}
+extern "C" {
+    pub fn mrb_proc_arity(p: *const RProc) -> mrb_int;
+}
+extern "C" {
+    pub fn mrb_f_send(mrb: *mut mrb_state, self_: mrb_value) -> mrb_value;
+}
+extern "C" {
+    pub fn mrb_proc_new_cfunc_with_env(
+        arg1: *mut mrb_state,
+        arg2: mrb_func_t,
+        arg3: mrb_int,
+        arg4: *const mrb_value,
+    ) -> *mut RProc;
+}
+extern "C" {
+    pub fn mrb_proc_cfunc_env_get(arg1: *mut mrb_state, arg2: mrb_int) -> mrb_value;
+}
+pub type khint_t = u32;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct kh_mt {
+    pub n_buckets: khint_t,
+    pub size: khint_t,
+    pub n_occupied: khint_t,
+    pub ed_flags: *mut u8,
+    pub keys: *mut mrb_sym,
+    pub vals: *mut mrb_method_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of kh_mt"][::std::mem::size_of::<kh_mt>() - 40usize];
+    ["Alignment of kh_mt"][::std::mem::align_of::<kh_mt>() - 8usize];
+    ["Offset of field: kh_mt::n_buckets"][::std::mem::offset_of!(kh_mt, n_buckets) - 0usize];
+    ["Offset of field: kh_mt::size"][::std::mem::offset_of!(kh_mt, size) - 4usize];
+    ["Offset of field: kh_mt::n_occupied"][::std::mem::offset_of!(kh_mt, n_occupied) - 8usize];
+    ["Offset of field: kh_mt::ed_flags"][::std::mem::offset_of!(kh_mt, ed_flags) - 16usize];
+    ["Offset of field: kh_mt::keys"][::std::mem::offset_of!(kh_mt, keys) - 24usize];
+    ["Offset of field: kh_mt::vals"][::std::mem::offset_of!(kh_mt, vals) - 32usize];
+};
+/// Range class
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct mrb_range_edges {
+    pub beg: mrb_value,
+    pub end: mrb_value,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of mrb_range_edges"][::std::mem::size_of::<mrb_range_edges>() - 32usize];
+    ["Alignment of mrb_range_edges"][::std::mem::align_of::<mrb_range_edges>() - 8usize];
+    ["Offset of field: mrb_range_edges::beg"]
+        [::std::mem::offset_of!(mrb_range_edges, beg) - 0usize];
+    ["Offset of field: mrb_range_edges::end"]
+        [::std::mem::offset_of!(mrb_range_edges, end) - 16usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct RRange {
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 4usize], u32>,
+    pub c: *mut RClass,
+    pub gcnext: *mut RBasic,
+    pub edges: *mut mrb_range_edges,
+    pub excl: mrb_bool,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RRange"][::std::mem::size_of::<RRange>() - 40usize];
+    ["Alignment of RRange"][::std::mem::align_of::<RRange>() - 8usize];
+    ["Offset of field: RRange::c"][::std::mem::offset_of!(RRange, c) - 8usize];
+    ["Offset of field: RRange::gcnext"][::std::mem::offset_of!(RRange, gcnext) - 16usize];
+    ["Offset of field: RRange::edges"][::std::mem::offset_of!(RRange, edges) - 24usize];
+    ["Offset of field: RRange::excl"][::std::mem::offset_of!(RRange, excl) - 32usize];
+};
+impl RRange {
+    #[inline]
+    pub fn tt(&self) -> mrb_vtype {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(0usize, 8u8) as u32) }
+    }
+    #[inline]
+    pub fn set_tt(&mut self, val: mrb_vtype) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(0usize, 8u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn color(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(8usize, 3u8) as u32) }
+    }
+    #[inline]
+    pub fn set_color(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(8usize, 3u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn flags(&self) -> u32 {
+        unsafe { ::std::mem::transmute(self._bitfield_1.get(11usize, 21u8) as u32) }
+    }
+    #[inline]
+    pub fn set_flags(&mut self, val: u32) {
+        unsafe {
+            let val: u32 = ::std::mem::transmute(val);
+            self._bitfield_1.set(11usize, 21u8, val as u64)
+        }
+    }
+    #[inline]
+    pub fn new_bitfield_1(
+        tt: mrb_vtype,
+        color: u32,
+        flags: u32,
+    ) -> __BindgenBitfieldUnit<[u8; 4usize], u32> {
+        let mut __bindgen_bitfield_unit: __BindgenBitfieldUnit<[u8; 4usize], u32> =
+            Default::default();
+        __bindgen_bitfield_unit.set(0usize, 8u8, {
+            let tt: u32 = unsafe { ::std::mem::transmute(tt) };
+            tt as u64
+        });
+        __bindgen_bitfield_unit.set(8usize, 3u8, {
+            let color: u32 = unsafe { ::std::mem::transmute(color) };
+            color as u64
+        });
+        __bindgen_bitfield_unit.set(11usize, 21u8, {
+            let flags: u32 = unsafe { ::std::mem::transmute(flags) };
+            flags as u64
+        });
+        __bindgen_bitfield_unit
+    }
+}
+extern "C" {
+    pub fn mrb_range_ptr(mrb: *mut mrb_state, range: mrb_value) -> *mut RRange;
+}
+extern "C" {
+    /// Initializes a Range.
+    ///
+    /// If the third parameter is FALSE then it includes the last value in the range.
+    /// If the third parameter is TRUE then it excludes the last value in the range.
+    ///
+    /// @param start the beginning value.
+    /// @param end the ending value.
+    /// @param exclude represents the inclusion or exclusion of the last value.
+    pub fn mrb_range_new(
+        mrb: *mut mrb_state,
+        start: mrb_value,
+        end: mrb_value,
+        exclude: mrb_bool,
+    ) -> mrb_value;
+}
+extern "C" {
+    pub fn mrb_range_beg_len(
+        mrb: *mut mrb_state,
+        range: mrb_value,
+        begp: *mut mrb_int,
+        lenp: *mut mrb_int,
+        len: mrb_int,
+        trunc: mrb_bool,
+    ) -> mrb_int;
+}
+extern "C" {
+    pub fn mrb_get_values_at(
+        mrb: *mut mrb_state,
+        obj: mrb_value,
+        olen: mrb_int,
+        argc: mrb_int,
+        argv: *const mrb_value,
+        func: ::std::option::Option<
+            unsafe extern "C" fn(arg1: *mut mrb_state, arg2: mrb_value, arg3: mrb_int) -> mrb_value,
+        >,
+    ) -> mrb_value;
+}
+extern "C" {
+    pub fn mrb_gc_mark_range(mrb: *mut mrb_state, r: *mut RRange);
+}
 extern "C" {
     pub static mut mrb_digitmap: [::std::os::raw::c_char; 0usize];
 }
@@ -5060,64 +4292,23 @@ pub union RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1 {
     pub fshared: *mut RString,
     _bindgen_union_align: u64,
 }
-#[test]
-fn bindgen_test_layout_RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1() {
-    assert_eq!(
-        ::std::mem::size_of::<RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>(),
-        8usize,
-        concat!(
-            "Size of: ",
-            stringify!(RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1)
-        )
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>(),
-        8usize,
-        concat!(
-            "Alignment of ",
-            stringify!(RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>())).capa
-                as *const _ as usize
-        },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(capa)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>())).shared
-                as *const _ as usize
-        },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(shared)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>())).fshared
-                as *const _ as usize
-        },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(fshared)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1"]
+        [::std::mem::size_of::<RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>() - 8usize];
+    ["Alignment of RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1"]
+        [::std::mem::align_of::<RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1>() - 8usize];
+    ["Offset of field: RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1::capa"]
+        [::std::mem::offset_of!(RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1, capa) - 0usize];
+    ["Offset of field: RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1::shared"][::std::mem::offset_of!(
+        RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1,
+        shared
+    ) - 0usize];
+    ["Offset of field: RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1::fshared"][::std::mem::offset_of!(
+        RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1,
+        fshared
+    ) - 0usize];
+};
 impl ::std::fmt::Debug for RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1 {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(
@@ -5126,58 +4317,19 @@ impl ::std::fmt::Debug for RString__bindgen_ty_1__bindgen_ty_1__bindgen_ty_1 {
         )
     }
 }
-#[test]
-fn bindgen_test_layout_RString__bindgen_ty_1__bindgen_ty_1() {
-    assert_eq!(
-        ::std::mem::size_of::<RString__bindgen_ty_1__bindgen_ty_1>(),
-        24usize,
-        concat!("Size of: ", stringify!(RString__bindgen_ty_1__bindgen_ty_1))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RString__bindgen_ty_1__bindgen_ty_1>(),
-        8usize,
-        concat!(
-            "Alignment of ",
-            stringify!(RString__bindgen_ty_1__bindgen_ty_1)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RString__bindgen_ty_1__bindgen_ty_1>())).len as *const _ as usize
-        },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(len)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RString__bindgen_ty_1__bindgen_ty_1>())).aux as *const _ as usize
-        },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(aux)
-        )
-    );
-    assert_eq!(
-        unsafe {
-            &(*(::std::ptr::null::<RString__bindgen_ty_1__bindgen_ty_1>())).ptr as *const _ as usize
-        },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString__bindgen_ty_1__bindgen_ty_1),
-            "::",
-            stringify!(ptr)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RString__bindgen_ty_1__bindgen_ty_1"]
+        [::std::mem::size_of::<RString__bindgen_ty_1__bindgen_ty_1>() - 24usize];
+    ["Alignment of RString__bindgen_ty_1__bindgen_ty_1"]
+        [::std::mem::align_of::<RString__bindgen_ty_1__bindgen_ty_1>() - 8usize];
+    ["Offset of field: RString__bindgen_ty_1__bindgen_ty_1::len"]
+        [::std::mem::offset_of!(RString__bindgen_ty_1__bindgen_ty_1, len) - 0usize];
+    ["Offset of field: RString__bindgen_ty_1__bindgen_ty_1::aux"]
+        [::std::mem::offset_of!(RString__bindgen_ty_1__bindgen_ty_1, aux) - 8usize];
+    ["Offset of field: RString__bindgen_ty_1__bindgen_ty_1::ptr"]
+        [::std::mem::offset_of!(RString__bindgen_ty_1__bindgen_ty_1, ptr) - 16usize];
+};
 impl ::std::fmt::Debug for RString__bindgen_ty_1__bindgen_ty_1 {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(
@@ -5187,87 +4339,29 @@ impl ::std::fmt::Debug for RString__bindgen_ty_1__bindgen_ty_1 {
         )
     }
 }
-#[test]
-fn bindgen_test_layout_RString__bindgen_ty_1() {
-    assert_eq!(
-        ::std::mem::size_of::<RString__bindgen_ty_1>(),
-        24usize,
-        concat!("Size of: ", stringify!(RString__bindgen_ty_1))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RString__bindgen_ty_1>(),
-        8usize,
-        concat!("Alignment of ", stringify!(RString__bindgen_ty_1))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RString__bindgen_ty_1>())).heap as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString__bindgen_ty_1),
-            "::",
-            stringify!(heap)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RString__bindgen_ty_1>())).ary as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString__bindgen_ty_1),
-            "::",
-            stringify!(ary)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RString__bindgen_ty_1"][::std::mem::size_of::<RString__bindgen_ty_1>() - 24usize];
+    ["Alignment of RString__bindgen_ty_1"]
+        [::std::mem::align_of::<RString__bindgen_ty_1>() - 8usize];
+    ["Offset of field: RString__bindgen_ty_1::heap"]
+        [::std::mem::offset_of!(RString__bindgen_ty_1, heap) - 0usize];
+    ["Offset of field: RString__bindgen_ty_1::ary"]
+        [::std::mem::offset_of!(RString__bindgen_ty_1, ary) - 0usize];
+};
 impl ::std::fmt::Debug for RString__bindgen_ty_1 {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(f, "RString__bindgen_ty_1 {{ union }}")
     }
 }
-#[test]
-fn bindgen_test_layout_RString() {
-    assert_eq!(
-        ::std::mem::size_of::<RString>(),
-        48usize,
-        concat!("Size of: ", stringify!(RString))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<RString>(),
-        8usize,
-        concat!("Alignment of ", stringify!(RString))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RString>())).c as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString),
-            "::",
-            stringify!(c)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RString>())).gcnext as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString),
-            "::",
-            stringify!(gcnext)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RString>())).as_ as *const _ as usize },
-        24usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(RString),
-            "::",
-            stringify!(as_)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of RString"][::std::mem::size_of::<RString>() - 48usize];
+    ["Alignment of RString"][::std::mem::align_of::<RString>() - 8usize];
+    ["Offset of field: RString::c"][::std::mem::offset_of!(RString, c) - 8usize];
+    ["Offset of field: RString::gcnext"][::std::mem::offset_of!(RString, gcnext) - 16usize];
+    ["Offset of field: RString::as_"][::std::mem::offset_of!(RString, as_) - 24usize];
+};
 impl ::std::fmt::Debug for RString {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(
@@ -5703,59 +4797,19 @@ pub struct __va_list_tag {
     pub overflow_arg_area: *mut ::std::os::raw::c_void,
     pub reg_save_area: *mut ::std::os::raw::c_void,
 }
-#[test]
-fn bindgen_test_layout___va_list_tag() {
-    assert_eq!(
-        ::std::mem::size_of::<__va_list_tag>(),
-        24usize,
-        concat!("Size of: ", stringify!(__va_list_tag))
-    );
-    assert_eq!(
-        ::std::mem::align_of::<__va_list_tag>(),
-        8usize,
-        concat!("Alignment of ", stringify!(__va_list_tag))
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__va_list_tag>())).gp_offset as *const _ as usize },
-        0usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(__va_list_tag),
-            "::",
-            stringify!(gp_offset)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__va_list_tag>())).fp_offset as *const _ as usize },
-        4usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(__va_list_tag),
-            "::",
-            stringify!(fp_offset)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__va_list_tag>())).overflow_arg_area as *const _ as usize },
-        8usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(__va_list_tag),
-            "::",
-            stringify!(overflow_arg_area)
-        )
-    );
-    assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__va_list_tag>())).reg_save_area as *const _ as usize },
-        16usize,
-        concat!(
-            "Offset of field: ",
-            stringify!(__va_list_tag),
-            "::",
-            stringify!(reg_save_area)
-        )
-    );
-}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of __va_list_tag"][::std::mem::size_of::<__va_list_tag>() - 24usize];
+    ["Alignment of __va_list_tag"][::std::mem::align_of::<__va_list_tag>() - 8usize];
+    ["Offset of field: __va_list_tag::gp_offset"]
+        [::std::mem::offset_of!(__va_list_tag, gp_offset) - 0usize];
+    ["Offset of field: __va_list_tag::fp_offset"]
+        [::std::mem::offset_of!(__va_list_tag, fp_offset) - 4usize];
+    ["Offset of field: __va_list_tag::overflow_arg_area"]
+        [::std::mem::offset_of!(__va_list_tag, overflow_arg_area) - 8usize];
+    ["Offset of field: __va_list_tag::reg_save_area"]
+        [::std::mem::offset_of!(__va_list_tag, reg_save_area) - 16usize];
+};
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct iv_tbl {
@@ -5763,16 +4817,6 @@ pub struct iv_tbl {
 }
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct RProc {
-    pub _address: u8,
-}
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct REnv {
-    pub _address: u8,
-}
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
 pub struct alloca_header {
     pub _address: u8,
 }
@@ -5783,11 +4827,6 @@ pub struct symbol_name {
 }
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct kh_mt {
-    pub _address: u8,
-}
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
 pub struct htable {
     pub _address: u8,
 }
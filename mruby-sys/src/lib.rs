@@ -8,107 +8,176 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
-use std::os::raw::{c_char, c_void};
-
-#[cfg(feature = "stdio")]
-use libc::FILE;
-
-#[cfg(not(feature = "use-f32"))]
-#[cfg(not(feature = "debug"))]
-#[cfg(feature = "stdio")]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/double_nodebug_stdio.rs"
-));
-
-#[cfg(not(feature = "use-f32"))]
-#[cfg(not(feature = "debug"))]
-#[cfg(not(feature = "stdio"))]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/double_nodebug_nostdio.rs"
-));
-
-#[cfg(not(feature = "use-f32"))]
-#[cfg(feature = "debug")]
-#[cfg(feature = "stdio")]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/double_debug_stdio.rs"
-));
-
-#[cfg(not(feature = "use-f32"))]
-#[cfg(feature = "debug")]
-#[cfg(not(feature = "stdio"))]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/double_debug_nostdio.rs"
-));
-
-#[cfg(feature = "use-f32")]
-#[cfg(not(feature = "debug"))]
-#[cfg(feature = "stdio")]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/float_nodebug_stdio.rs"
-));
-
-#[cfg(feature = "use-f32")]
-#[cfg(not(feature = "debug"))]
-#[cfg(not(feature = "stdio"))]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/float_nodebug_nostdio.rs"
-));
-
-#[cfg(feature = "use-f32")]
-#[cfg(feature = "debug")]
-#[cfg(feature = "stdio")]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/float_debug_stdio.rs"
-));
-
-#[cfg(feature = "use-f32")]
-#[cfg(feature = "debug")]
-#[cfg(not(feature = "stdio"))]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/float_debug_nostdio.rs"
-));
-
-#[cfg(feature = "disable-floats")]
-#[cfg(not(feature = "debug"))]
-#[cfg(feature = "stdio")]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/nofloat_nodebug_stdio.rs"
-));
-
-#[cfg(feature = "disable-floats")]
-#[cfg(not(feature = "debug"))]
-#[cfg(not(feature = "stdio"))]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/nofloat_nodebug_nostdio.rs"
-));
-
-#[cfg(feature = "disable-floats")]
-#[cfg(feature = "debug")]
-#[cfg(feature = "stdio")]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/nofloat_debug_stdio.rs"
-));
-
-#[cfg(feature = "disable-floats")]
-#[cfg(feature = "debug")]
-#[cfg(not(feature = "stdio"))]
-include!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/src/nofloat_debug_nostdio.rs"
-));
+use std::os::raw::{c_char, c_int, c_void};
+
+/// The bindings generated by bindgen for the enabled features.
+///
+/// Generated code is not held to the lints of hand-written code.
+mod bindings {
+    #![allow(clippy::all)]
+
+    #[cfg(feature = "stdio")]
+    use libc::FILE;
+
+    #[cfg(not(feature = "use-f32"))]
+    #[cfg(not(feature = "debug"))]
+    #[cfg(feature = "stdio")]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/double_nodebug_stdio.rs"
+    ));
+
+    #[cfg(not(feature = "use-f32"))]
+    #[cfg(not(feature = "debug"))]
+    #[cfg(not(feature = "stdio"))]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/double_nodebug_nostdio.rs"
+    ));
+
+    #[cfg(not(feature = "use-f32"))]
+    #[cfg(feature = "debug")]
+    #[cfg(feature = "stdio")]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/double_debug_stdio.rs"
+    ));
+
+    #[cfg(not(feature = "use-f32"))]
+    #[cfg(feature = "debug")]
+    #[cfg(not(feature = "stdio"))]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/double_debug_nostdio.rs"
+    ));
+
+    #[cfg(feature = "use-f32")]
+    #[cfg(not(feature = "debug"))]
+    #[cfg(feature = "stdio")]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/float_nodebug_stdio.rs"
+    ));
+
+    #[cfg(feature = "use-f32")]
+    #[cfg(not(feature = "debug"))]
+    #[cfg(not(feature = "stdio"))]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/float_nodebug_nostdio.rs"
+    ));
+
+    #[cfg(feature = "use-f32")]
+    #[cfg(feature = "debug")]
+    #[cfg(feature = "stdio")]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/float_debug_stdio.rs"
+    ));
+
+    #[cfg(feature = "use-f32")]
+    #[cfg(feature = "debug")]
+    #[cfg(not(feature = "stdio"))]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/float_debug_nostdio.rs"
+    ));
+
+    #[cfg(feature = "disable-floats")]
+    #[cfg(not(feature = "debug"))]
+    #[cfg(feature = "stdio")]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/nofloat_nodebug_stdio.rs"
+    ));
+
+    #[cfg(feature = "disable-floats")]
+    #[cfg(not(feature = "debug"))]
+    #[cfg(not(feature = "stdio"))]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/nofloat_nodebug_nostdio.rs"
+    ));
+
+    #[cfg(feature = "disable-floats")]
+    #[cfg(feature = "debug")]
+    #[cfg(feature = "stdio")]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/nofloat_debug_stdio.rs"
+    ));
+
+    #[cfg(feature = "disable-floats")]
+    #[cfg(feature = "debug")]
+    #[cfg(not(feature = "stdio"))]
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/nofloat_debug_nostdio.rs"
+    ));
+}
+
+pub use bindings::*;
+
+/// Whether mruby was built with `MRB_USE_FLOAT`, which makes `mrb_float` an `f32`.
+pub const MRB_USE_FLOAT: bool = cfg!(feature = "use-f32");
+
+/// Whether mruby was built with `MRB_WITHOUT_FLOAT`, which leaves out `Float` altogether.
+pub const MRB_WITHOUT_FLOAT: bool = cfg!(feature = "disable-floats");
+
+/// Compiles `source` into mruby bytecode with debug info, like `mrbc -g`, using a temporary
+/// interpreter.
+///
+/// On failure, returns the error message along with the 1-based line and column of the syntax
+/// error, both of which are 0 if the failure is not a syntax error.
+pub fn compile_to_bytecode(
+    source: &str,
+    filename: &str,
+) -> Result<Vec<u8>, (String, usize, usize)> {
+    use std::ffi::{CStr, CString};
+    use std::{mem, ptr, slice};
+
+    let name = CString::new(filename).map_err(|e| (e.to_string(), 0, 0))?;
+    unsafe {
+        let state = mrb_open();
+        if state.is_null() {
+            return Err(("failed to initialize mruby".to_owned(), 0, 0));
+        }
 
+        let code = source.as_ptr() as *const c_char;
+        let flags = DUMP_DEBUG_INFO as u8;
+        let mut bin = ptr::null_mut();
+        let mut len = 0;
+        let mut error: mrb_parser_message = mem::zeroed();
+        let status = mrb_ext_compile(
+            state,
+            code,
+            source.len(),
+            name.as_ptr(),
+            flags,
+            &mut bin,
+            &mut len,
+            &mut error,
+        );
+
+        let result = if !error.message.is_null() {
+            let message = CStr::from_ptr(error.message).to_string_lossy().into_owned();
+            let (line, column) = (error.lineno as usize, error.column as usize);
+            mrb_free(state, error.message as *mut _);
+            Err((message, line, column))
+        } else if status != MRB_DUMP_OK as i32 {
+            Err(("code generation failed".to_owned(), 0, 0))
+        } else {
+            let bytecode = slice::from_raw_parts(bin, len).to_vec();
+            mrb_free(state, bin as *mut _);
+            Ok(bytecode)
+        };
+
+        mrb_close(state);
+        result
+    }
+}
+
+#[allow(unused_attributes)]
 extern "C" {
     #[inline]
     pub fn mrb_ext_ary_len(array: mrb_value) -> mrb_int;
@@ -119,9 +188,33 @@ extern "C" {
     #[inline]
     pub fn mrb_ext_class_value(c: *mut RClass) -> mrb_value;
 
+    #[inline]
+    pub fn mrb_ext_compile(
+        mrb: *mut mrb_state,
+        code: *const c_char,
+        len: usize,
+        filename: *const c_char,
+        flags: u8,
+        bin: *mut *mut u8,
+        bin_size: *mut usize,
+        error: *mut mrb_parser_message,
+    ) -> c_int;
+
+    #[inline]
+    pub fn mrb_ext_cptr_to_ptr(cptr: mrb_value) -> *mut c_void;
+
     #[inline]
     pub fn mrb_ext_cptr_value(mrb: *mut mrb_state, p: *mut c_void) -> mrb_value;
 
+    #[inline]
+    pub fn mrb_ext_data_init(v: mrb_value, ptr: *mut c_void, type_: *const mrb_data_type);
+
+    #[inline]
+    pub fn mrb_ext_data_ptr(v: mrb_value) -> *mut c_void;
+
+    #[inline]
+    pub fn mrb_ext_data_type(v: mrb_value) -> *const mrb_data_type;
+
     #[inline]
     pub fn mrb_ext_fixnum_to_cint(num: mrb_value) -> mrb_int;
 
@@ -142,9 +235,34 @@ extern "C" {
     #[inline]
     pub fn mrb_ext_nil_value() -> mrb_value;
 
+    #[inline]
+    pub fn mrb_ext_obj_value(p: *mut c_void) -> mrb_value;
+
+    #[inline]
+    pub fn mrb_ext_proc_irep(p: *mut RProc) -> *mut mrb_irep;
+
+    #[inline]
+    pub fn mrb_ext_protect(
+        mrb: *mut mrb_state,
+        body: Option<unsafe extern "C" fn(mrb: *mut mrb_state, data: *mut c_void)>,
+        data: *mut c_void,
+    ) -> mrb_bool;
+
+    #[inline]
+    pub fn mrb_ext_range_beg(mrb: *mut mrb_state, range: mrb_value) -> mrb_value;
+
+    #[inline]
+    pub fn mrb_ext_range_end(mrb: *mut mrb_state, range: mrb_value) -> mrb_value;
+
+    #[inline]
+    pub fn mrb_ext_range_excl(mrb: *mut mrb_state, range: mrb_value) -> mrb_bool;
+
     #[inline]
     pub fn mrb_ext_raise(mrb: *mut mrb_state, err: *const c_char, msg: *const c_char) -> c_void;
 
+    #[inline]
+    pub fn mrb_ext_set_instance_tt(c: *mut RClass, tt: mrb_vtype);
+
     #[inline]
     pub fn mrb_ext_symbol_to_sym(sym: mrb_value) -> mrb_sym;
 
@@ -194,6 +312,20 @@ mod tests {
         assert_eq!(false_val.tt, MRB_TT_FALSE);
     }
 
+    #[test]
+    fn ext_cptr_to_ptr() {
+        unsafe {
+            let state = mrb_open();
+
+            let mut input = 42u32;
+            let cptr = mrb_ext_cptr_value(state, &mut input as *mut u32 as *mut c_void);
+            let output = mrb_ext_cptr_to_ptr(cptr) as *mut u32;
+            assert_eq!(*output, input);
+
+            mrb_close(state);
+        }
+    }
+
     #[test]
     fn ext_cptr_value() {
         unsafe {
@@ -211,6 +343,17 @@ mod tests {
         assert_eq!(input, output);
     }
 
+    #[test]
+    fn compile_to_bytecode_result() {
+        let bytecode = compile_to_bytecode("puts 'hello'\n", "hello.rb").unwrap();
+        assert_eq!(&bytecode[..8], b"RITE0006");
+
+        let (message, line, column) =
+            compile_to_bytecode("x = 1\ny = x +* 2\n", "broken.rb").unwrap_err();
+        assert_eq!(message, "syntax error, unexpected tSTAR");
+        assert_eq!((line, column), (2, 8));
+    }
+
     #[test]
     fn ext_fixnum_value() {
         unsafe {
@@ -238,6 +381,7 @@ mod tests {
 
     #[cfg(not(feature = "disable-floats"))]
     #[test]
+    #[allow(clippy::approx_constant)]
     fn ext_float_value() {
         unsafe {
             let state = mrb_open();
@@ -265,6 +409,129 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ext_obj_value() {
+        unsafe {
+            let state = mrb_open();
+
+            let object = (*state).top_self;
+            let val = mrb_ext_obj_value(object as *mut c_void);
+            assert_eq!(val.tt, MRB_TT_OBJECT);
+            assert_eq!(val.value.p, object as *mut c_void);
+
+            mrb_close(state);
+        }
+    }
+
+    #[test]
+    fn ext_proc_irep() {
+        unsafe {
+            let state = mrb_open();
+
+            let context = mrbc_context_new(state);
+            let filename = CString::new("sum.rb").unwrap();
+            mrbc_filename(state, context, filename.as_ptr());
+
+            let code = CString::new("a = 1\nb = 2\na + b").unwrap();
+            let parser = mrb_parse_string(state, code.as_ptr(), context);
+            let proc_ = mrb_generate_code(state, parser);
+            mrb_parser_free(parser);
+            assert!(!proc_.is_null());
+
+            let irep = mrb_ext_proc_irep(proc_);
+            assert!(!irep.is_null());
+            assert_eq!((*irep).nlocals, 3);
+
+            let file = CStr::from_ptr(mrb_debug_get_filename(state, irep, 0));
+            assert_eq!(file, filename.as_c_str());
+            let last = (*irep).ilen as isize - 1;
+            assert_eq!(mrb_debug_get_line(state, irep, last), 3);
+
+            let mut bin = ptr::null_mut();
+            let mut bin_size = 0;
+            let flags = (DUMP_DEBUG_INFO | DUMP_ENDIAN_BIG) as u8;
+            let status = mrb_dump_irep(state, irep, flags, &mut bin, &mut bin_size);
+            assert_eq!(status, MRB_DUMP_OK as i32);
+            assert!(bin_size > std::mem::size_of::<rite_binary_header>());
+
+            let header = &*(bin as *const rite_binary_header);
+            assert_eq!(&header.binary_ident, &RITE_BINARY_IDENT[..4]);
+            assert_eq!(&header.binary_version, &RITE_BINARY_FORMAT_VER[..4]);
+
+            let loaded = mrb_read_irep(state, bin);
+            assert!(!loaded.is_null());
+            assert_eq!((*loaded).ilen, (*irep).ilen);
+            mrb_irep_decref(state, loaded);
+
+            let result = mrb_load_irep(state, bin);
+            assert_eq!(mrb_ext_fixnum_to_cint(result), 3);
+
+            mrb_free(state, bin as *mut c_void);
+            mrbc_context_free(state, context);
+            mrb_close(state);
+        }
+    }
+
+    #[test]
+    fn ext_protect() {
+        unsafe extern "C" fn raise(mrb: *mut mrb_state, data: *mut c_void) {
+            *(data as *mut bool) = true;
+            let error = b"RuntimeError\0".as_ptr() as *const c_char;
+            mrb_ext_raise(mrb, error, b"hello world\0".as_ptr() as *const c_char);
+        }
+
+        unsafe extern "C" fn succeed(_: *mut mrb_state, data: *mut c_void) {
+            *(data as *mut bool) = true;
+        }
+
+        unsafe {
+            let state = mrb_open();
+
+            let mut called = false;
+            let data = &mut called as *mut bool as *mut c_void;
+            assert_eq!(mrb_ext_protect(state, Some(succeed), data), 0);
+            assert!(called && (*state).exc.is_null() && (*state).jmp.is_null());
+
+            called = false;
+            let data = &mut called as *mut bool as *mut c_void;
+            assert_eq!(mrb_ext_protect(state, Some(raise), data), 1);
+            assert!(called && !(*state).exc.is_null() && (*state).jmp.is_null());
+
+            let exc = mrb_ext_obj_value((*state).exc as *mut c_void);
+            let name = CString::new("message").unwrap();
+            let mut message = mrb_funcall(state, exc, name.as_ptr(), 0);
+            let message = CStr::from_ptr(mrb_string_value_cstr(state, &mut message));
+            assert_eq!(message.to_str(), Ok("hello world"));
+
+            mrb_close(state);
+        }
+    }
+
+    #[test]
+    fn ext_range() {
+        unsafe {
+            let state = mrb_open();
+
+            let beg = mrb_ext_fixnum_value(2);
+            let end = mrb_ext_fixnum_value(5);
+            let range = mrb_range_new(state, beg, end, 1);
+            assert_eq!(range.tt, MRB_TT_RANGE);
+            assert_eq!(mrb_ext_fixnum_to_cint(mrb_ext_range_beg(state, range)), 2);
+            assert_eq!(mrb_ext_fixnum_to_cint(mrb_ext_range_end(state, range)), 5);
+            assert_eq!(mrb_ext_range_excl(state, range), 1);
+
+            let raw = mrb_range_ptr(state, range);
+            assert_eq!((*raw).tt(), MRB_TT_RANGE);
+            assert_eq!((*raw).excl, 1);
+
+            let (mut begp, mut lenp) = (0, 0);
+            mrb_range_beg_len(state, range, &mut begp, &mut lenp, 10, 1);
+            assert_eq!((begp, lenp), (2, 3));
+
+            mrb_close(state);
+        }
+    }
+
     #[test]
     #[ignore]
     fn ext_raise_success() {
@@ -277,6 +544,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn proc_cfunc_env() {
+        unsafe extern "C" fn get_env(mrb: *mut mrb_state, _: mrb_value) -> mrb_value {
+            mrb_proc_cfunc_env_get(mrb, 0)
+        }
+
+        unsafe {
+            let state = mrb_open();
+
+            let env = [mrb_ext_fixnum_value(42)];
+            let proc_ = mrb_proc_new_cfunc_with_env(state, Some(get_env), 1, env.as_ptr());
+            assert!(!proc_.is_null());
+
+            let name = CString::new("get_env").unwrap();
+            let method = mrb_method_t {
+                func_p: 0,
+                __bindgen_anon_1: mrb_method_t__bindgen_ty_1 { proc_ },
+            };
+            mrb_define_method_raw(
+                state,
+                (*state).object_class,
+                mrb_intern_cstr(state, name.as_ptr()),
+                method,
+            );
+
+            let code = CString::new("get_env").unwrap();
+            let result = mrb_load_string(state, code.as_ptr());
+            assert_eq!(mrb_ext_fixnum_to_cint(result), 42);
+
+            mrb_close(state);
+        }
+    }
+
+    #[test]
+    fn ext_data() {
+        static mut FREED: bool = false;
+
+        unsafe extern "C" fn free_u32(_: *mut mrb_state, ptr: *mut c_void) {
+            drop(Box::from_raw(ptr as *mut u32));
+            FREED = true;
+        }
+
+        unsafe {
+            let state = mrb_open();
+
+            let name = CString::new("Boxed").unwrap();
+            let data_type = mrb_data_type {
+                struct_name: name.as_ptr(),
+                dfree: Some(free_u32),
+            };
+
+            let class = mrb_define_class(state, name.as_ptr(), (*state).object_class);
+            mrb_ext_set_instance_tt(class, MRB_TT_DATA);
+
+            let ptr = Box::into_raw(Box::new(42u32)) as *mut c_void;
+            let data = mrb_data_object_alloc(state, class, ptr, &data_type);
+            let value = mrb_ext_obj_value(data as *mut c_void);
+            assert_eq!(value.tt, MRB_TT_DATA);
+            assert_eq!(mrb_ext_data_type(value), &data_type as *const _);
+            assert_eq!(*(mrb_ext_data_ptr(value) as *mut u32), 42);
+
+            let other = Box::into_raw(Box::new(7u32)) as *mut c_void;
+            free_u32(state, mrb_ext_data_ptr(value));
+            mrb_ext_data_init(value, other, &data_type);
+            assert_eq!(*(mrb_ext_data_ptr(value) as *mut u32), 7);
+
+            FREED = false;
+            mrb_close(state);
+            assert!(FREED);
+        }
+    }
+
     #[test]
     fn ext_symbol_value() {
         unsafe {
@@ -0,0 +1,126 @@
+use std::os::raw::c_int;
+
+use mruby_sys::{mrb_state, mrb_value};
+
+use crate::function::raising;
+use crate::userdata::UserData;
+
+/// A saved position of the GC arena of an interpreter.
+///
+/// Objects created from C or Rust code are kept in the arena, which protects them from the garbage
+/// collector until it is restored to an earlier position. Code creating many temporary objects
+/// without returning to Ruby in between, such as the conversion of a large collection, restores the
+/// arena after each one so it does not grow without bound.
+///
+/// This is the equivalent of `mrb_gc_arena_save()` and `mrb_gc_arena_restore()`, which are inline
+/// functions in the C API. The arena is also restored when the index is dropped without calling
+/// `restore()`, e.g. when a conversion panics, so no slots are leaked. Values handed out to Rust
+/// code with `keep()` since the position was saved are pushed into the arena again afterwards.
+#[derive(Debug)]
+pub(crate) struct ArenaIndex {
+    state: *mut mrb_state,
+    index: c_int,
+    kept: usize,
+}
+
+impl ArenaIndex {
+    /// Saves the current position of the arena.
+    pub unsafe fn save(state: *mut mrb_state) -> Self {
+        ArenaIndex {
+            state,
+            index: (*state).gc.arena_idx,
+            kept: UserData::get(state).kept().len(),
+        }
+    }
+
+    /// Releases every object added to the arena since this position was saved.
+    pub unsafe fn restore(self) {
+        drop(self);
+    }
+}
+
+impl Drop for ArenaIndex {
+    fn drop(&mut self) {
+        unsafe {
+            (*self.state).gc.arena_idx = self.index;
+            let kept = UserData::get(self.state).kept();
+            kept.protect_since(self.state, self.kept);
+        }
+    }
+}
+
+/// The values handed out to Rust code, which stay in the GC arena for as long as the interpreter
+/// is borrowed by them, or until the scope they were obtained in ends.
+#[derive(Debug, Default)]
+pub(crate) struct Kept {
+    values: Vec<mrb_value>,
+}
+
+impl Kept {
+    /// Returns the number of kept values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Forgets the values kept since there were `len` of them, once the arena they were kept in
+    /// is released, e.g. when a Rust function called from Ruby returns.
+    pub fn truncate(&mut self, len: usize) {
+        self.values.truncate(len);
+    }
+
+    /// Pushes the values kept since there were `len` of them into the arena again.
+    ///
+    /// This must only be called right after the arena was restored to a position saved before
+    /// those values were kept. Each of them took a slot above that position, so this never grows
+    /// the arena and cannot raise.
+    unsafe fn protect_since(&self, state: *mut mrb_state, len: usize) {
+        use mruby_sys::mrb_gc_protect;
+
+        for &value in self.values.get(len..).unwrap_or_default() {
+            mrb_gc_protect(state, value);
+        }
+    }
+}
+
+/// Keeps `value` in the GC arena until the scope it was obtained in ends.
+pub(crate) unsafe fn keep(state: *mut mrb_state, value: mrb_value) {
+    use mruby_sys::mrb_gc_protect;
+
+    // NOTE: Growing the arena may fail when the memory limit is reached. Outside of a protected
+    // call, mruby cannot raise that error and the limit is not enforced.
+    if (*state).jmp.is_null() {
+        mrb_gc_protect(state, value);
+    } else {
+        raising(state, || mrb_gc_protect(state, value));
+    }
+
+    UserData::get(state).kept().values.push(value);
+}
+
+/// A scope entered by `Mruby::arena_scope()`, which releases the objects created within it from
+/// the GC arena when it is dropped.
+#[derive(Debug)]
+pub(crate) struct ArenaScope {
+    state: *mut mrb_state,
+    index: c_int,
+    kept: usize,
+}
+
+impl ArenaScope {
+    pub unsafe fn enter(state: *mut mrb_state) -> Self {
+        ArenaScope {
+            state,
+            index: (*state).gc.arena_idx,
+            kept: UserData::get(state).kept().len(),
+        }
+    }
+}
+
+impl Drop for ArenaScope {
+    fn drop(&mut self) {
+        unsafe {
+            (*self.state).gc.arena_idx = self.index;
+            UserData::get(self.state).kept().truncate(self.kept);
+        }
+    }
+}
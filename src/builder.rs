@@ -0,0 +1,83 @@
+use crate::exit;
+use crate::function::catch_raised;
+use crate::memory::allocf;
+use crate::sandbox::{self, Capabilities};
+use crate::userdata::UserData;
+use crate::{Error, Mruby};
+
+/// Builder for configuring an `Mruby` interpreter before it is created.
+///
+/// # Example
+///
+/// ```rust
+/// # use mruby::Mruby;
+/// let ruby = Mruby::builder().memory_limit(64 << 20).build().unwrap();
+/// assert_eq!(ruby.memory_limit(), Some(64 << 20));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MrubyBuilder {
+    memory_limit: Option<usize>,
+    capabilities: Option<Capabilities>,
+}
+
+impl MrubyBuilder {
+    /// Limits the memory the interpreter may allocate to `bytes`.
+    ///
+    /// Allocations which would exceed the limit fail, which raises a `NoMemoryError` once a full
+    /// garbage collection cannot free enough memory either. Ruby code may rescue it like any other
+    /// exception. Every method of `Mruby` which allocates returns it as an error instead, e.g.
+    /// `eval()`, `compile()`, `register_global()` or `define_global_fn()`. Rust functions called
+    /// from Ruby raise it into the calling Ruby code, e.g. when converting their return value.
+    ///
+    /// Since mruby aborts the process when it runs out of memory without a handler to raise the
+    /// error to, the few hundred kilobytes allocated on startup are never refused. They count
+    /// towards the limit like everything else, though, and `build()` fails with
+    /// `Error::MemoryLimit` if they exceed it.
+    pub fn memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Sandboxes the interpreter, only allowing Ruby code to use the given capabilities.
+    ///
+    /// Methods giving access to anything else are replaced with methods raising `SecurityError`.
+    /// See `Mruby::sandboxed()` for details.
+    pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Creates the interpreter.
+    pub fn build(self) -> Result<Mruby, Error> {
+        use mruby_sys::mrb_open_allocf;
+
+        // NOTE: The limit is only set once the interpreter is open. mruby cannot recover from
+        // running out of memory while it runs the Ruby code of gems during `mrb_open()`.
+        let mut user_data = Box::new(UserData::default());
+        let allocator = user_data.allocator();
+
+        let state = unsafe { mrb_open_allocf(Some(allocf), allocator as *mut _ as *mut _) };
+        if state.is_null() {
+            return Err(Error::Init);
+        }
+
+        unsafe { (*state).ud = Box::into_raw(user_data) as *mut _ };
+        let ruby = Mruby { state };
+        unsafe {
+            let allocator = UserData::get(state).allocator();
+            allocator.set_limit(self.memory_limit);
+            if allocator.is_exceeded() {
+                return Err(Error::MemoryLimit);
+            }
+
+            catch_raised(state, || exit::init(state)).map_err(|_| Error::Init)?;
+            if let Some(capabilities) = self.capabilities {
+                catch_raised(state, || sandbox::restrict(state, capabilities))
+                    .and_then(|result| result)
+                    .map_err(|_| Error::Init)?;
+            }
+        }
+
+        Ok(ruby)
+    }
+}
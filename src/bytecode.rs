@@ -0,0 +1,160 @@
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::raw::c_char;
+use std::{ptr, slice};
+
+use mruby_sys::{mrb_state, mrb_value, rite_binary_header};
+
+use crate::error::{BytecodeError, CompileError, RubyException};
+use crate::function::protect;
+
+/// Offset of the first byte covered by the checksum, right after the `binary_crc` field.
+const CRC_BODY_OFFSET: usize = 10;
+
+/// Compiled mruby bytecode in the RITE binary format.
+///
+/// This is the same format written by the upstream `mrbc -g` compiler, so it can be saved to a
+/// `.mrb` file and loaded again later with `Mruby::load_bytecode()`, even by a different
+/// interpreter.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Bytecode(Vec<u8>);
+
+impl Bytecode {
+    /// Returns the raw RITE binary.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Consumes the bytecode, returning the raw RITE binary.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for Bytecode {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Bytecode> for Vec<u8> {
+    fn from(bytecode: Bytecode) -> Self {
+        bytecode.0
+    }
+}
+
+/// Returns whether bytecode compiled by an mruby with the given configuration is compatible with
+/// the one linked into this crate, which `include_ruby!()` checks at build time.
+pub const fn config_matches(int_bit: u32, use_float: bool, without_float: bool) -> bool {
+    use mruby_sys::{MRB_INT_BIT, MRB_USE_FLOAT, MRB_WITHOUT_FLOAT};
+
+    int_bit == MRB_INT_BIT && use_float == MRB_USE_FLOAT && without_float == MRB_WITHOUT_FLOAT
+}
+
+/// Parses `source` and serializes the resulting program without running it, including its debug
+/// info if `debug_info` is set.
+pub(crate) unsafe fn compile(
+    state: *mut mrb_state,
+    source: &str,
+    filename: &str,
+    debug_info: bool,
+) -> Result<Bytecode, CompileError> {
+    use mruby_sys::{mrb_ext_compile, mrb_free, mrb_parser_message, DUMP_DEBUG_INFO, MRB_DUMP_OK};
+
+    let name = CString::new(filename).expect("String contains null byte");
+    let code = source.as_ptr() as *const c_char;
+    let flags = if debug_info { DUMP_DEBUG_INFO as u8 } else { 0 };
+    let mut bin = ptr::null_mut();
+    let mut len = 0;
+    let mut error: mrb_parser_message = mem::zeroed();
+    let status = protect(state, || {
+        mrb_ext_compile(
+            state,
+            code,
+            source.len(),
+            name.as_ptr(),
+            flags,
+            &mut bin,
+            &mut len,
+            &mut error,
+        )
+    });
+
+    // NOTE: Compiling only raises when running out of memory, e.g. when the memory limit is reached.
+    let status = status.map_err(|exc| CompileError::new(filename, None, exc.to_string()))?;
+
+    if !error.message.is_null() {
+        let message = CStr::from_ptr(error.message).to_string_lossy().into_owned();
+        let location = (error.lineno as usize, error.column as usize);
+        mrb_free(state, error.message as *mut _);
+        return Err(CompileError::new(filename, Some(location), message));
+    } else if status != MRB_DUMP_OK as i32 {
+        return Err(CompileError::new(filename, None, "code generation failed"));
+    }
+
+    let bytes = slice::from_raw_parts(bin, len).to_vec();
+    mrb_free(state, bin as *mut _);
+    Ok(Bytecode(bytes))
+}
+
+/// Checks that `bytes` holds a complete RITE binary which this version of mruby can read.
+pub(crate) fn validate(bytes: &[u8]) -> Result<(), BytecodeError> {
+    use mruby_sys::{calc_crc_16_ccitt, RITE_BINARY_EOF, RITE_BINARY_FORMAT_VER};
+    use mruby_sys::{RITE_BINARY_IDENT, RITE_BINARY_IDENT_LIL};
+
+    let header_len = mem::size_of::<rite_binary_header>();
+    if bytes.len() < header_len {
+        return Err(BytecodeError::Truncated);
+    }
+
+    // NOTE: The header only consists of byte arrays, so it has no alignment requirements.
+    let header = unsafe { &*(bytes.as_ptr() as *const rite_binary_header) };
+    let ident = &header.binary_ident[..];
+    if ident != &RITE_BINARY_IDENT[..4] && ident != &RITE_BINARY_IDENT_LIL[..4] {
+        return Err(BytecodeError::InvalidHeader);
+    }
+
+    if header.binary_version[..] != RITE_BINARY_FORMAT_VER[..4] {
+        return Err(BytecodeError::UnsupportedVersion);
+    }
+
+    // NOTE: The header fields are big-endian regardless of the byte order of the sections.
+    let footer_len = mem::size_of::<mruby_sys::rite_binary_footer>();
+    let size = u32::from_be_bytes(header.binary_size) as usize;
+    if size < header_len + footer_len {
+        return Err(BytecodeError::InvalidHeader);
+    } else if size > bytes.len() || bytes[size - footer_len..][..4] != RITE_BINARY_EOF[..4] {
+        return Err(BytecodeError::Truncated);
+    }
+
+    let body = &bytes[CRC_BODY_OFFSET..size];
+    let crc = unsafe { calc_crc_16_ccitt(body.as_ptr(), body.len(), 0) };
+    if crc != u16::from_be_bytes(header.binary_crc) {
+        return Err(BytecodeError::ChecksumMismatch);
+    }
+
+    Ok(())
+}
+
+/// Loads and runs the given RITE binary, which must have been checked with `validate()`, returning
+/// the exception it raised, if any.
+///
+/// mruby copies everything it needs out of the binary, so it can be freed right afterwards. See
+/// `mrb_ro_data_p()` in `mruby-sys`.
+pub(crate) unsafe fn load(state: *mut mrb_state, bytes: &[u8]) -> Result<mrb_value, RubyException> {
+    use mruby_sys::{mrb_gc_protect, mrb_load_irep};
+
+    // NOTE: mruby pads sections relative to the absolute address of the binary, assuming it starts
+    // at a multiple of `MRB_DUMP_ALIGNMENT`, which is the size of a `u32`. The binary is copied
+    // into a buffer of `u32`s for that reason, which is freed again once mruby has copied
+    // everything it needs.
+    let align = mem::size_of::<u32>();
+    let mut aligned = vec![0u32; bytes.len().div_ceil(align)];
+    ptr::copy_nonoverlapping(bytes.as_ptr(), aligned.as_mut_ptr() as *mut u8, bytes.len());
+    let bin = aligned.as_ptr() as *const u8;
+    protect(state, || {
+        let value = mrb_load_irep(state, bin);
+        mrb_gc_protect(state, value);
+        value
+    })
+}
@@ -1,40 +1,155 @@
+use std::any::TypeId;
 use std::ffi::CString;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::os::raw::c_char;
+use std::sync::Arc;
 
-use mruby_sys::{mrb_class_get, mrb_define_class, mrb_intern, mrb_state, RClass};
+use mruby_sys::{mrb_class_get, mrb_define_class, mrb_intern, mrb_state, mrb_value, RClass};
 
+use crate::data::{self, DataType};
+use crate::de::{FromArgs, FromValue};
+use crate::error::RubyException;
+use crate::function::{cast_exception, convert_args, convert_return, new_exception};
+use crate::function::{catch_raised, funcall, protect, raising, Function};
+use crate::root::Roots;
 use crate::ser::{Serializer, ToValue};
+use crate::userdata::UserData;
 
+/// A Rust type which can be registered as a Ruby class with `Mruby::define_class()`.
+///
+/// This is usually implemented with the `#[mruby_class]` attribute macro.
 pub trait Class {
+    /// The name of the Ruby class.
     const NAME: &'static str;
+    /// The name of the parent class, or `None` to inherit from `Object`.
     const PARENT: Option<&'static str>;
 
+    /// Defines the constants and methods of the class.
     fn define(builder: &mut Builder);
 }
 
+/// The Ruby methods of a class, as generated by the `#[methods]` attribute macro.
+///
+/// `#[mruby_class]` expects this to be implemented for the annotated type and calls it from
+/// `Class::define()`.
+pub trait Methods {
+    fn define_methods(builder: &mut Builder);
+}
+
+/// A Ruby class registered from a Rust type, as returned by `Mruby::define_class()`.
+///
+/// Handles are only valid for the interpreter which created them, which is checked at runtime as
+/// with `Root`. Classes are bound to a constant and are therefore never garbage collected, so a
+/// handle stays valid for as long as its interpreter is open. It may be kept for longer, since it
+/// never touches the interpreter by itself.
+#[derive(Clone, Debug)]
+pub struct ClassHandle {
+    class: *mut RClass,
+    name: &'static str,
+    roots: Arc<Roots>,
+}
+
+impl ClassHandle {
+    pub(crate) unsafe fn new(
+        state: *mut mrb_state,
+        class: *mut RClass,
+        name: &'static str,
+    ) -> Self {
+        ClassHandle {
+            class,
+            name,
+            roots: UserData::get(state).roots().clone(),
+        }
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut RClass {
+        self.class
+    }
+
+    /// Returns the class for use with `state`, panicking if it belongs to another interpreter.
+    pub(crate) unsafe fn class_in(&self, state: *mut mrb_state) -> *mut RClass {
+        assert!(
+            Arc::ptr_eq(&self.roots, UserData::get(state).roots()),
+            "class handle belongs to a different interpreter"
+        );
+
+        self.class
+    }
+
+    /// Returns the name of the class.
+    ///
+    /// Classes are defined at the top level, so this is the fully qualified name as well.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl PartialEq for ClassHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.class == other.class && Arc::ptr_eq(&self.roots, &other.roots)
+    }
+}
+
+impl Eq for ClassHandle {}
+
+impl Hash for ClassHandle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.class.hash(state);
+    }
+}
+
+/// Defines the constants and methods of a Ruby class, as passed to `Class::define()`.
+///
+/// Definitions which fail, e.g. because a module to include does not exist, do not panic. The
+/// exception is recorded instead, the remaining definitions are skipped, and
+/// `Mruby::define_class()` returns the error.
 #[derive(Debug)]
 pub struct Builder {
     state: *mut mrb_state,
     class: *mut RClass,
+    error: Option<RubyException>,
 }
 
 impl Builder {
-    pub(crate) unsafe fn new(state: *mut mrb_state, name: &str, parent: Option<&str>) -> Self {
+    /// Defines the class `name`, or reopens it if it already exists.
+    ///
+    /// Fails if `parent` does not name an existing class, or if `name` is already bound to
+    /// something other than a class with that parent.
+    pub(crate) unsafe fn new(
+        state: *mut mrb_state,
+        name: &str,
+        parent: Option<&str>,
+    ) -> Result<Self, RubyException> {
         let name = CString::new(name).expect("String contains null byte");
-        let parent = match parent {
-            None => (*state).object_class,
-            Some("Array") => (*state).array_class,
-            Some("Object") => (*state).object_class,
-            Some("Hash") => (*state).hash_class,
-            Some("Float") => (*state).float_class,
-            Some("Proc") => (*state).proc_class,
-            Some(other) => {
-                let name = CString::new(other).unwrap();
-                mrb_class_get(state, name.as_ptr())
-            }
-        };
+        let parent = parent.map(|name| CString::new(name).expect("String contains null byte"));
+
+        let class = protect(state, || {
+            let parent = match parent {
+                None => (*state).object_class,
+                Some(ref name) => match name.to_bytes() {
+                    b"Array" => (*state).array_class,
+                    b"Object" => (*state).object_class,
+                    b"Hash" => (*state).hash_class,
+                    b"Float" => (*state).float_class,
+                    b"Proc" => (*state).proc_class,
+                    _ => mrb_class_get(state, name.as_ptr()),
+                },
+            };
+
+            mrb_define_class(state, name.as_ptr(), parent)
+        })?;
 
-        let class = mrb_define_class(state, name.as_ptr(), parent);
-        Builder { state, class }
+        Ok(Builder {
+            state,
+            class,
+            error: None,
+        })
+    }
+
+    /// Returns the exception raised by the first definition which failed, if any.
+    pub(crate) fn take_error(&mut self) -> Option<RubyException> {
+        self.error.take()
     }
 
     pub fn def_const<N, V>(&mut self, name: N, value: V) -> &mut Self
@@ -45,26 +160,365 @@ impl Builder {
         use mruby_sys::mrb_define_const;
 
         let name = CString::new(name.as_ref()).expect("String contains null byte");
-        let value = value.to_value(Serializer::new(self.state)).into_inner();
+        self.define(|state, class| unsafe {
+            let value = value.to_value(Serializer::new(state)).into_inner();
+            raising(state, || {
+                mrb_define_const(state, class, name.as_ptr(), value)
+            });
+            Ok(())
+        })
+    }
 
-        unsafe {
-            mrb_define_const(self.state, self.class, name.as_ptr(), value);
-            self
-        }
+    /// Defines an instance method backed by the given Rust closure.
+    ///
+    /// The closure receives the method receiver converted into `S` along with the arguments
+    /// converted into `A`, as with `Mruby::define_global_fn()`.
+    pub fn def_method<'mrb, N, F, S, A, R, E>(&mut self, name: N, func: F) -> &mut Self
+    where
+        N: AsRef<str>,
+        F: Fn(S, A) -> Result<R, E> + Send + 'static,
+        S: FromValue<'mrb>,
+        A: FromArgs<'mrb>,
+        R: ToValue,
+        E: Display,
+    {
+        self.define_function(name.as_ref(), Function::new(func))
+    }
+
+    /// Defines a class method backed by the given Rust closure.
+    ///
+    /// The closure receives the class object itself along with the converted arguments. Class
+    /// methods are the singleton methods of the class object, so this is the same as passing the
+    /// class to `def_singleton_method()`.
+    pub fn def_class_method<'mrb, N, F, S, A, R, E>(&mut self, name: N, func: F) -> &mut Self
+    where
+        N: AsRef<str>,
+        F: Fn(S, A) -> Result<R, E> + Send + 'static,
+        S: FromValue<'mrb>,
+        A: FromArgs<'mrb>,
+        R: ToValue,
+        E: Display,
+    {
+        use mruby_sys::mrb_ext_class_value;
+
+        let func = Function::new(func);
+        self.define(|state, class| unsafe {
+            let receiver = mrb_ext_class_value(class);
+            define_singleton_function(state, receiver, name.as_ref(), func);
+            Ok(())
+        })
+    }
+
+    /// Defines a method backed by the given Rust closure on `object` alone, through its
+    /// singleton class, e.g. on an object assigned to a constant of this class.
+    ///
+    /// The closure receives `object` converted into `S` along with the converted arguments. Fails
+    /// with a `TypeError` if `object` cannot have singleton methods, e.g. if it is an integer or a
+    /// symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `object` is a value or `Root` belonging to a different interpreter.
+    pub fn def_singleton_method<'mrb, N, F, S, A, R, E>(
+        &mut self,
+        object: &(impl ToValue + ?Sized),
+        name: N,
+        func: F,
+    ) -> &mut Self
+    where
+        N: AsRef<str>,
+        F: Fn(S, A) -> Result<R, E> + Send + 'static,
+        S: FromValue<'mrb>,
+        A: FromArgs<'mrb>,
+        R: ToValue,
+        E: Display,
+    {
+        let func = Function::new(func);
+        self.define(|state, _| unsafe {
+            let receiver = object.to_value(Serializer::new(state)).into_inner();
+            define_singleton_function(state, receiver, name.as_ref(), func);
+            Ok(())
+        })
+    }
+
+    /// Defines `initialize` such that instances of this class wrap a Rust value of type `T`.
+    ///
+    /// The closure constructs the value from the arguments passed to `new`. Calling `initialize`
+    /// again on an existing instance replaces the value it holds.
+    pub fn def_initialize<'mrb, F, T, A, E>(&mut self, func: F) -> &mut Self
+    where
+        F: Fn(A) -> Result<T, E> + Send + 'static,
+        T: Send + 'static,
+        A: FromArgs<'mrb>,
+        E: Display,
+    {
+        let func = Function::from_callback(move |state, this, args| unsafe {
+            let data = match func(convert_args(state, args)?) {
+                Ok(data) => data,
+                Err(e) => return Err(new_exception(state, "RuntimeError", e)),
+            };
+
+            data::init(state, this, data).map_err(|e| cast_exception(state, e))?;
+            Ok(this)
+        });
+
+        self.register_data_type::<T>()
+            .define_function("initialize", func)
     }
 
+    /// Defines an instance method which borrows the Rust value wrapped by the receiver.
+    ///
+    /// Raises a `TypeError` if the receiver does not wrap a `T`, or a `RuntimeError` if the
+    /// value is already mutably borrowed by another method further up the stack.
+    pub fn def_method_ref<'mrb, N, F, T, A, R, E>(&mut self, name: N, func: F) -> &mut Self
+    where
+        N: AsRef<str>,
+        F: Fn(&T, A) -> Result<R, E> + Send + 'static,
+        T: Send + 'static,
+        A: FromArgs<'mrb>,
+        R: ToValue,
+        E: Display,
+    {
+        let func = Function::from_callback(move |state, this, args| unsafe {
+            let cell = data::get::<T>(state, this).map_err(|e| cast_exception(state, e))?;
+            let args = convert_args(state, args)?;
+            let result = match cell.try_borrow() {
+                Ok(this) => func(&this, args),
+                Err(e) => return Err(new_exception(state, "RuntimeError", e)),
+            };
+            convert_return(state, result)
+        });
+
+        self.register_data_type::<T>()
+            .define_function(name.as_ref(), func)
+    }
+
+    /// Defines an instance method which mutably borrows the Rust value wrapped by the receiver.
+    ///
+    /// Raises a `TypeError` if the receiver does not wrap a `T`, or a `RuntimeError` if the
+    /// value is already borrowed by another method further up the stack.
+    pub fn def_method_mut<'mrb, N, F, T, A, R, E>(&mut self, name: N, func: F) -> &mut Self
+    where
+        N: AsRef<str>,
+        F: Fn(&mut T, A) -> Result<R, E> + Send + 'static,
+        T: Send + 'static,
+        A: FromArgs<'mrb>,
+        R: ToValue,
+        E: Display,
+    {
+        let func = Function::from_callback(move |state, this, args| unsafe {
+            let cell = data::get::<T>(state, this).map_err(|e| cast_exception(state, e))?;
+            let args = convert_args(state, args)?;
+            let result = match cell.try_borrow_mut() {
+                Ok(mut this) => func(&mut this, args),
+                Err(e) => return Err(new_exception(state, "RuntimeError", e)),
+            };
+            convert_return(state, result)
+        });
+
+        self.register_data_type::<T>()
+            .define_function(name.as_ref(), func)
+    }
+
+    /// Sets the class variable `name`, which may be given with or without the leading `@@`.
     pub fn def_var<N, V>(&mut self, name: N, value: V) -> &mut Self
     where
         N: AsRef<str>,
         V: ToValue,
     {
-        let s = name.as_ref();
-        let name = CString::new(s).expect("String contains null byte");
-        let _value = value.to_value(Serializer::new(self.state)).into_inner();
+        use mruby_sys::{mrb_cv_set, mrb_ext_class_value};
+
+        let name = match name.as_ref() {
+            name if name.starts_with("@@") => name.to_owned(),
+            name => format!("@@{}", name),
+        };
+
+        self.define(|state, class| unsafe {
+            let value = value.to_value(Serializer::new(state)).into_inner();
+            raising(state, || {
+                let sym = mrb_intern(state, name.as_ptr() as *const c_char, name.len());
+                mrb_cv_set(state, mrb_ext_class_value(class), sym, value);
+            });
+            Ok(())
+        })
+    }
+
+    /// Defines `new_name` as an alias of the existing method `old_name`.
+    ///
+    /// Fails with a `NameError` if `old_name` is not defined on this class or any of its
+    /// ancestors.
+    pub fn alias_method<N, O>(&mut self, new_name: N, old_name: O) -> &mut Self
+    where
+        N: AsRef<str>,
+        O: AsRef<str>,
+    {
+        use mruby_sys::mrb_define_alias;
+
+        let new_name = CString::new(new_name.as_ref()).expect("String contains null byte");
+        let old_name = CString::new(old_name.as_ref()).expect("String contains null byte");
+        self.define(|state, class| unsafe {
+            raising(state, || {
+                mrb_define_alias(state, class, new_name.as_ptr(), old_name.as_ptr())
+            });
+            Ok(())
+        })
+    }
+
+    /// Undefines the method `name`, so instances no longer respond to it even if an ancestor
+    /// defines it.
+    pub fn undef_method<N: AsRef<str>>(&mut self, name: N) -> &mut Self {
+        use mruby_sys::mrb_undef_method;
+
+        let name = CString::new(name.as_ref()).expect("String contains null byte");
+        self.define(|state, class| unsafe {
+            raising(state, || mrb_undef_method(state, class, name.as_ptr()));
+            Ok(())
+        })
+    }
+
+    /// Defines a method `name` returning the instance variable `@name`.
+    pub fn attr_reader<N: AsRef<str>>(&mut self, name: N) -> &mut Self {
+        self.call_with_symbol("attr_reader", name.as_ref())
+    }
+
+    /// Defines a method `name=` setting the instance variable `@name`.
+    pub fn attr_writer<N: AsRef<str>>(&mut self, name: N) -> &mut Self {
+        self.call_with_symbol("attr_writer", name.as_ref())
+    }
+
+    /// Defines both `attr_reader()` and `attr_writer()` for `name`.
+    pub fn attr_accessor<N: AsRef<str>>(&mut self, name: N) -> &mut Self {
+        self.attr_reader(name.as_ref()).attr_writer(name.as_ref())
+    }
+
+    /// Includes the module at the given path, e.g. `"Comparable"` or `"Game::Audio"`, into this
+    /// class.
+    ///
+    /// Fails with a `NameError` if no constant exists at `path`, or with a `TypeError` if it is
+    /// not a module.
+    pub fn include_module<P: AsRef<str>>(&mut self, path: P) -> &mut Self {
+        use mruby_sys::mrb_include_module;
+
+        self.define(|state, class| unsafe {
+            let module = get_module(state, path.as_ref())?;
+            raising(state, || mrb_include_module(state, class, module));
+            Ok(())
+        })
+    }
+
+    /// Prepends the module at the given path to this class, so its methods take precedence over
+    /// the ones defined by the class itself.
+    ///
+    /// Fails with a `NameError` if no constant exists at `path`, or with a `TypeError` if it is
+    /// not a module.
+    pub fn prepend_module<P: AsRef<str>>(&mut self, path: P) -> &mut Self {
+        use mruby_sys::mrb_prepend_module;
+
+        self.define(|state, class| unsafe {
+            let module = get_module(state, path.as_ref())?;
+            raising(state, || mrb_prepend_module(state, class, module));
+            Ok(())
+        })
+    }
+
+    fn call_with_symbol(&mut self, method: &str, name: &str) -> &mut Self {
+        use mruby_sys::{mrb_ext_class_value, mrb_ext_symbol_value};
+
+        self.define(|state, class| unsafe {
+            let sym = raising(state, || {
+                mrb_intern(state, name.as_ptr() as *const c_char, name.len())
+            });
+            let args = [mrb_ext_symbol_value(sym)];
+            funcall(state, mrb_ext_class_value(class), method, &args)?;
+            Ok(())
+        })
+    }
+
+    fn register_data_type<T: Send + 'static>(&mut self) -> &mut Self {
+        self.define(|state, class| unsafe {
+            DataType::register::<T>(state, class);
+            Ok(())
+        })
+    }
+
+    fn define_function(&mut self, name: &str, func: Function) -> &mut Self {
+        self.define(|state, class| unsafe {
+            let func = UserData::get(state).add_function(func);
+            raising(state, || func.define(state, class, name));
+            Ok(())
+        })
+    }
+
+    /// Runs `definition` unless an earlier one failed, recording the exception it raises or
+    /// returns, if any.
+    ///
+    /// The definition calls mruby functions which may raise through `raising()`, so the values it
+    /// owns are dropped before the exception is recorded.
+    fn define<F>(&mut self, definition: F) -> &mut Self
+    where
+        F: FnOnce(*mut mrb_state, *mut RClass) -> Result<(), RubyException>,
+    {
+        if self.error.is_none() {
+            let (state, class) = (self.state, self.class);
+            let result = unsafe { catch_raised(state, || definition(state, class)) };
+            self.error = result.and_then(|result| result).err();
+        }
+
+        self
+    }
+}
+
+/// Defines `func` as the method `name` of the singleton class of `receiver`, raising a `TypeError`
+/// if `receiver` cannot have one.
+unsafe fn define_singleton_function(
+    state: *mut mrb_state,
+    receiver: mrb_value,
+    name: &str,
+    func: Function,
+) {
+    use mruby_sys::mrb_singleton_class;
+
+    let func = UserData::get(state).add_function(func);
+    raising(state, || {
+        let singleton = mrb_singleton_class(state, receiver);
+        func.define(state, singleton.value.p as *mut RClass, name);
+    });
+}
+
+/// Defines the Ruby class described by `T` and records it as the class of `T`.
+pub(crate) unsafe fn define<T: Class + 'static>(
+    state: *mut mrb_state,
+) -> Result<ClassHandle, RubyException> {
+    let mut builder = Builder::new(state, T::NAME, T::PARENT)?;
+    T::define(&mut builder);
+    if let Some(exc) = builder.take_error() {
+        return Err(exc);
+    }
+
+    UserData::get(state).add_class(TypeId::of::<T>(), builder.class);
+    Ok(ClassHandle::new(state, builder.class, T::NAME))
+}
+
+/// Looks up the module at `path`, which may contain `::` separators.
+///
+/// Fails with a `NameError` if the constant does not exist, or with a `TypeError` if it is not a
+/// module.
+pub(crate) unsafe fn get_module(
+    state: *mut mrb_state,
+    path: &str,
+) -> Result<*mut RClass, RubyException> {
+    use mruby_sys::{mrb_ext_class_value, mrb_str_new, MRB_TT_MODULE};
 
-        unsafe {
-            let _sym = mrb_intern(self.state, name.as_ptr(), s.len());
-            unimplemented!()
+    let object = mrb_ext_class_value((*state).object_class);
+    let name = protect(state, || {
+        mrb_str_new(state, path.as_ptr() as *const c_char, path.len())
+    })?;
+    match funcall(state, object, "const_get", &[name])? {
+        module if module.tt == MRB_TT_MODULE => Ok(module.value.p as *mut RClass),
+        _ => {
+            let msg = format!("{} is not a module", path);
+            let exc = new_exception(state, "TypeError", msg);
+            Err(RubyException::from_value(state, exc))
         }
     }
 }
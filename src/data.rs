@@ -0,0 +1,189 @@
+use std::any::{self, TypeId};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use mruby_sys::{mrb_data_type, mrb_state, mrb_value, RClass, MRB_TT_DATA};
+
+use crate::class::{self, Class};
+use crate::de::CastError;
+use crate::function::{new_exception, raise, raising};
+use crate::userdata::UserData;
+
+/// Describes how Rust values of one type are stored inside Ruby objects.
+///
+/// Each value is boxed in a `RefCell` and attached to an `RData` object, whose `mrb_data_type`
+/// is unique per Rust type. Comparing that pointer is how a Ruby object is checked to contain a
+/// `T` before it is borrowed.
+pub(crate) struct DataType {
+    name: CString,
+    raw: mrb_data_type,
+}
+
+impl DataType {
+    /// Registers `T` as the type wrapped by instances of `class`, unless it already is.
+    ///
+    /// This also switches `class` to allocate `RData` objects, which start out empty until
+    /// `init()` is called on them. That is done even if `T` is already registered, since `class`
+    /// may be a new class with the same name, e.g. after the constant was removed.
+    pub unsafe fn register<T: Send + 'static>(state: *mut mrb_state, class: *mut RClass) {
+        use mruby_sys::{mrb_class_name, mrb_ext_set_instance_tt};
+
+        mrb_ext_set_instance_tt(class, MRB_TT_DATA);
+        if UserData::get(state).data_type(TypeId::of::<T>()).is_some() {
+            return;
+        }
+
+        let name = raising(state, || mrb_class_name(state, class));
+        let name = CStr::from_ptr(name).to_owned();
+        let data_type = Box::new(DataType {
+            raw: mrb_data_type {
+                struct_name: name.as_ptr(),
+                dfree: Some(free::<T>),
+            },
+            name,
+        });
+
+        UserData::get(state).add_data_type(TypeId::of::<T>(), data_type);
+    }
+
+    /// Returns the name of the Ruby class instances of `T` belong to.
+    pub fn name(&self) -> &str {
+        self.name.to_str().unwrap_or("(unknown)")
+    }
+
+    /// Returns the raw data type, valid for as long as the interpreter is open.
+    pub fn as_ptr(&self) -> *const mrb_data_type {
+        &self.raw
+    }
+}
+
+/// Stores `data` in the Ruby object `this`, dropping any value it held previously.
+pub(crate) unsafe fn init<T: Send + 'static>(
+    state: *mut mrb_state,
+    this: mrb_value,
+    data: T,
+) -> Result<(), CastError> {
+    use mruby_sys::{mrb_ext_data_init, mrb_ext_data_ptr};
+
+    let data_type = lookup::<T>(state)?;
+    if this.tt != MRB_TT_DATA {
+        return Err(not_an_instance(data_type.name()));
+    }
+
+    if !mrb_ext_data_ptr(this).is_null() {
+        let cell = get::<T>(state, this)?;
+        if cell.try_borrow_mut().is_err() {
+            return Err(CastError::unexpected_type(
+                "object is borrowed by a running method",
+            ));
+        }
+
+        free::<T>(state, cell as *const RefCell<T> as *mut c_void);
+    }
+
+    let ptr = Box::into_raw(Box::new(RefCell::new(data)));
+    mrb_ext_data_init(this, ptr as *mut c_void, data_type.as_ptr());
+    Ok(())
+}
+
+/// Moves `data` into a new instance of the class of `T`, defining the class first if needed.
+///
+/// Raises a `TypeError` if the class cannot be defined.
+pub(crate) unsafe fn wrap<T: Class + Send + 'static>(state: *mut mrb_state, data: T) -> mrb_value {
+    use mruby_sys::{mrb_data_object_alloc, mrb_ext_data_init, mrb_ext_obj_value};
+
+    let class = match UserData::get(state).class(TypeId::of::<T>()) {
+        Some(class) => class,
+        None => match class::define::<T>(state) {
+            Ok(class) => class.as_ptr(),
+            Err(err) => {
+                let msg = format!("cannot define class {}: {}", T::NAME, err);
+                raise(new_exception(state, "TypeError", msg))
+            }
+        },
+    };
+
+    DataType::register::<T>(state, class);
+    let data_type = lookup::<T>(state).expect("data type was just registered");
+
+    // NOTE: The object is allocated empty, since allocating may raise, which would leak the value
+    // if it were already boxed.
+    let object = raising(state, || {
+        mrb_data_object_alloc(state, class, ptr::null_mut(), data_type.as_ptr())
+    });
+    let object = mrb_ext_obj_value(object as *mut c_void);
+    let ptr = Box::into_raw(Box::new(RefCell::new(data)));
+    mrb_ext_data_init(object, ptr as *mut c_void, data_type.as_ptr());
+    object
+}
+
+/// Moves the Rust value out of the Ruby object `value`, checking that it is a `T`.
+///
+/// The object is left uninitialized, so methods called on it afterwards raise a `TypeError`.
+pub(crate) unsafe fn take<T: Send + 'static>(
+    state: *mut mrb_state,
+    value: mrb_value,
+) -> Result<T, CastError> {
+    use mruby_sys::{mrb_ext_data_init, mrb_ext_data_type};
+
+    let cell = get::<T>(state, value)?;
+    if cell.try_borrow_mut().is_err() {
+        return Err(CastError::unexpected_type(
+            "object is borrowed by a running method",
+        ));
+    }
+
+    let ptr = cell as *const RefCell<T> as *mut RefCell<T>;
+    mrb_ext_data_init(value, ptr::null_mut(), mrb_ext_data_type(value));
+    Ok(Box::from_raw(ptr).into_inner())
+}
+
+/// Returns the Rust value stored in the Ruby object `value`, checking that it is a `T`.
+///
+/// The returned reference is valid for as long as `value` is reachable from Ruby.
+pub(crate) unsafe fn get<'a, T: Send + 'static>(
+    state: *mut mrb_state,
+    value: mrb_value,
+) -> Result<&'a RefCell<T>, CastError> {
+    use mruby_sys::{mrb_ext_data_ptr, mrb_ext_data_type};
+
+    let data_type = lookup::<T>(state)?;
+    if value.tt != MRB_TT_DATA || mrb_ext_data_type(value) != data_type.as_ptr() {
+        return Err(not_an_instance(data_type.name()));
+    }
+
+    let ptr = mrb_ext_data_ptr(value) as *const RefCell<T>;
+    if ptr.is_null() {
+        let msg = format!("instance of {} is not initialized", data_type.name());
+        return Err(CastError::unexpected_type(msg));
+    }
+
+    Ok(&*ptr)
+}
+
+unsafe fn lookup<'a, T: 'static>(state: *mut mrb_state) -> Result<&'a DataType, CastError> {
+    UserData::get(state)
+        .data_type(TypeId::of::<T>())
+        .ok_or_else(|| {
+            let msg = format!("{} is not bound to a Ruby class", any::type_name::<T>());
+            CastError::unexpected_type(msg)
+        })
+}
+
+fn not_an_instance(class: &str) -> CastError {
+    CastError::unexpected_type(format!("expected an instance of {}", class))
+}
+
+unsafe extern "C" fn free<T>(_: *mut mrb_state, ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+
+    // NOTE: Unwinding into the garbage collector is undefined behavior, so a panicking `Drop`
+    // impl is contained here and the value is considered dropped.
+    let data = Box::from_raw(ptr as *mut RefCell<T>);
+    let _ = panic::catch_unwind(AssertUnwindSafe(move || drop(data)));
+}
@@ -1,4 +1,4 @@
-pub use self::deserializer::{Deserializer, MapIter, SeqDeserializer, SeqIter};
+pub use self::deserializer::{ArgsDeserializer, Deserializer, MapIter, SeqDeserializer, SeqIter};
 pub use self::error::CastError;
 
 use std::borrow::Cow;
@@ -9,56 +9,84 @@ use std::hash::{BuildHasher, Hash};
 use std::rc::Rc;
 use std::sync::Arc;
 
+use crate::object::Object;
+use crate::root::Root;
 use crate::symbol::Symbol;
 use crate::value::Value;
 
 mod deserializer;
 mod error;
 
-pub trait FromValue: Sized {
-    fn from_value(de: Deserializer) -> Result<Self, CastError>;
+pub trait FromValue<'mrb>: Sized {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError>;
 }
 
-impl FromValue for Value {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
-        Ok(de.value)
+/// A list of arguments received from a Ruby method call.
+///
+/// This is the counterpart of `ToArgs`, implemented for `()` and tuples of `FromValue` types,
+/// as well as for `Vec<T>`, which accepts any number of arguments. Tuples accept at most as many
+/// arguments as their arity, and trailing elements which can be deserialized from `nil`, such as
+/// `Option<T>`, may be omitted by the caller.
+pub trait FromArgs<'mrb>: Sized {
+    fn from_args(args: ArgsDeserializer<'_, 'mrb>) -> Result<Self, CastError>;
+}
+
+impl<'mrb> FromValue<'mrb> for Value<'mrb> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
+        Ok(de.into_value())
+    }
+}
+
+impl<'mrb> FromValue<'mrb> for Object<'mrb> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
+        de.deserialize_dynamic_object()
     }
 }
 
-impl FromValue for Symbol {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb> FromValue<'mrb> for Root {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
+        Ok(de.deserialize_root())
+    }
+}
+
+impl<'mrb> FromValue<'mrb> for Symbol {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_symbol()
     }
 }
 
-impl FromValue for () {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb> FromValue<'mrb> for () {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_unit()
     }
 }
 
-impl FromValue for bool {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb> FromValue<'mrb> for bool {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_bool()
     }
 }
 
-impl FromValue for char {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb> FromValue<'mrb> for char {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_char()
     }
 }
 
 #[cfg(not(feature = "disable-floats"))]
-impl FromValue for f32 {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb> FromValue<'mrb> for f32 {
+    // NOTE: This is only a no-op when `mrb_float` is `f32`, i.e. with the `use-f32` feature.
+    #[allow(clippy::unnecessary_cast)]
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_float().map(|f| f as f32)
     }
 }
 
 #[cfg(not(feature = "disable-floats"))]
-impl FromValue for f64 {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb> FromValue<'mrb> for f64 {
+    // NOTE: This is only a no-op when `mrb_float` is `f64`, i.e. without the `use-f32` feature.
+    #[allow(clippy::useless_conversion)]
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_float().map(From::from)
     }
 }
@@ -66,8 +94,8 @@ impl FromValue for f64 {
 macro_rules! impl_value_integer {
     ( $($ty:ident)* ) => {
         $(
-            impl FromValue for $ty {
-                fn from_value(de: Deserializer) -> Result<Self, CastError> {
+            impl<'mrb> FromValue<'mrb> for $ty {
+                fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
                     let num = de.deserialize_integer()?;
                     Self::try_from(num).map_err(|_| CastError::numeric_conversion(num, stringify!($ty)))
                 }
@@ -79,108 +107,108 @@ macro_rules! impl_value_integer {
 impl_value_integer!(i8 i16 i32 i64 isize);
 impl_value_integer!(u8 u16 u32 u64 usize);
 
-impl<'a> FromValue for &'a str {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb> FromValue<'mrb> for &'mrb str {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_str()
     }
 }
 
-impl FromValue for String {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
-        de.deserialize_str().map(ToString::to_string)
+impl<'mrb> FromValue<'mrb> for String {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
+        de.deserialize_string()
     }
 }
 
-impl<'a> FromValue for Cow<'a, str> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb> FromValue<'mrb> for Cow<'mrb, str> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_str().map(Cow::from)
     }
 }
 
-impl<'a, T: FromValue + Clone> FromValue for Cow<'a, [T]> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, 'a, T: FromValue<'mrb> + Clone> FromValue<'mrb> for Cow<'a, [T]> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_seq().collect()
     }
 }
 
-impl<T: FromValue> FromValue for Option<T> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for Option<T> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_option()
     }
 }
 
-impl<T: FromValue> FromValue for Arc<T> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for Arc<T> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         T::from_value(de).map(Arc::from)
     }
 }
 
-impl<T: FromValue> FromValue for Box<T> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for Box<T> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         T::from_value(de).map(Box::new)
     }
 }
 
-impl FromValue for Box<str> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
-        de.deserialize_str().map(Box::from)
+impl<'mrb> FromValue<'mrb> for Box<str> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
+        de.deserialize_string().map(Box::from)
     }
 }
 
-impl<T: FromValue> FromValue for Box<[T]> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for Box<[T]> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_seq().collect()
     }
 }
 
-impl<T: FromValue> FromValue for Rc<T> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for Rc<T> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         T::from_value(de).map(Rc::from)
     }
 }
 
-impl<T> FromValue for BinaryHeap<T>
+impl<'mrb, T> FromValue<'mrb> for BinaryHeap<T>
 where
-    T: FromValue + Ord,
+    T: FromValue<'mrb> + Ord,
 {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_seq().collect()
     }
 }
 
-impl<T> FromValue for BTreeSet<T>
+impl<'mrb, T> FromValue<'mrb> for BTreeSet<T>
 where
-    T: FromValue + Ord,
+    T: FromValue<'mrb> + Ord,
 {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_seq().collect()
     }
 }
 
-impl<T, S> FromValue for HashSet<T, S>
+impl<'mrb, T, S> FromValue<'mrb> for HashSet<T, S>
 where
-    T: FromValue + Eq + Hash,
+    T: FromValue<'mrb> + Eq + Hash,
     S: BuildHasher + Default,
 {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_seq().collect()
     }
 }
 
-impl<T: FromValue> FromValue for LinkedList<T> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for LinkedList<T> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_seq().collect()
     }
 }
 
-impl<T: FromValue> FromValue for Vec<T> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for Vec<T> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_seq().collect()
     }
 }
 
-impl<T: FromValue> FromValue for VecDeque<T> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for VecDeque<T> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_seq().collect()
     }
 }
@@ -188,8 +216,8 @@ impl<T: FromValue> FromValue for VecDeque<T> {
 macro_rules! impl_value_array {
     ( $($arity:tt)+ ) => {
         $(
-            impl<T: FromValue> FromValue for [T; $arity] {
-                fn from_value(de: Deserializer) -> Result<Self, CastError> {
+            impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for [T; $arity] {
+                fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
                     let iter = de.deserialize_seq::<T>();
                     let actual_len = iter.len();
                     let expected_len = $arity;
@@ -198,12 +226,11 @@ macro_rules! impl_value_array {
                         return Err(CastError::length(expected_len, actual_len));
                     }
 
-                    let mut array: [T; $arity] = unsafe { std::mem::uninitialized() };
-                    for (i, elem) in iter.enumerate() {
-                        array[i] = elem?;
+                    let elems = iter.collect::<Result<Vec<T>, _>>()?;
+                    match <[T; $arity] as std::convert::TryFrom<Vec<T>>>::try_from(elems) {
+                        Ok(array) => Ok(array),
+                        Err(_) => unreachable!("length was checked above"),
                     }
-
-                    Ok(array)
                 }
             }
         )+
@@ -214,14 +241,14 @@ impl_value_array! { 0 1 2 3 4 5 6 7 8 9 10 11 12 }
 
 macro_rules! impl_value_tuple {
     ( $($field:ident)+ ) => {
-        impl<$($field,)*> FromValue for ($($field,)*)
+        impl<'mrb, $($field,)*> FromValue<'mrb> for ($($field,)*)
         where
             $(
-                $field: FromValue,
+                $field: FromValue<'mrb>,
             )*
         {
             #[allow(non_snake_case)]
-            fn from_value(de: Deserializer) -> Result<Self, CastError> {
+            fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
                 let mut iter = de.deserialize_tuple();
                 let actual_len = iter.len();
                 let expected_len = count_tokens!($($field)*);
@@ -254,35 +281,107 @@ impl_value_tuple!(A B C D E F G H I J K);
 impl_value_tuple!(A B C D E F G H I J K L);
 impl_value_tuple!(A B C D E F G H I J K L M);
 
-impl<K, V> FromValue for BTreeMap<K, V>
+impl<'mrb, K, V> FromValue<'mrb> for BTreeMap<K, V>
 where
-    K: FromValue + Ord,
-    V: FromValue,
+    K: FromValue<'mrb> + Ord,
+    V: FromValue<'mrb>,
 {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_map().collect()
     }
 }
 
-impl<K, V, S> FromValue for HashMap<K, V, S>
+impl<'mrb, K, V, S> FromValue<'mrb> for HashMap<K, V, S>
 where
-    K: FromValue + Hash + Eq,
-    V: FromValue,
+    K: FromValue<'mrb> + Hash + Eq,
+    V: FromValue<'mrb>,
     S: BuildHasher + Default,
 {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         de.deserialize_map().collect()
     }
 }
 
-impl<T: FromValue> FromValue for Cell<T> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for Cell<T> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         T::from_value(de).map(Cell::from)
     }
 }
 
-impl<T: FromValue> FromValue for RefCell<T> {
-    fn from_value(de: Deserializer) -> Result<Self, CastError> {
+impl<'mrb, T: FromValue<'mrb>> FromValue<'mrb> for RefCell<T> {
+    fn from_value(de: Deserializer<'mrb>) -> Result<Self, CastError> {
         T::from_value(de).map(RefCell::from)
     }
 }
+
+impl<'mrb> FromArgs<'mrb> for () {
+    fn from_args(args: ArgsDeserializer<'_, 'mrb>) -> Result<Self, CastError> {
+        if args.is_empty() {
+            Ok(())
+        } else {
+            Err(CastError::length(0, args.len()))
+        }
+    }
+}
+
+impl<'mrb, T: FromValue<'mrb>> FromArgs<'mrb> for Vec<T> {
+    fn from_args(mut args: ArgsDeserializer<'_, 'mrb>) -> Result<Self, CastError> {
+        let mut values = Vec::with_capacity(args.len());
+        while let Some(value) = args.next_arg() {
+            values.push(value?);
+        }
+
+        Ok(values)
+    }
+}
+
+macro_rules! impl_args_tuple {
+    ( $($field:ident)+ ) => {
+        impl<'mrb, $($field,)*> FromArgs<'mrb> for ($($field,)*)
+        where
+            $(
+                $field: FromValue<'mrb>,
+            )*
+        {
+            #[allow(non_snake_case)]
+            fn from_args(mut args: ArgsDeserializer<'_, 'mrb>) -> Result<Self, CastError> {
+                let actual_len = args.len();
+                let expected_len = count_tokens!($($field)*);
+
+                if actual_len > expected_len {
+                    return Err(CastError::length(expected_len, actual_len));
+                }
+
+                let mut missing = false;
+                $(
+                    let $field = args.next_arg().unwrap_or_else(|| {
+                        let arg = args.missing_arg();
+                        missing |= arg.is_err();
+                        arg
+                    });
+                )*
+
+                // NOTE: Missing arguments take precedence over type mismatches, as in Ruby.
+                if missing {
+                    return Err(CastError::length(expected_len, actual_len));
+                }
+
+                Ok(($($field?,)*))
+            }
+        }
+    };
+}
+
+impl_args_tuple!(A);
+impl_args_tuple!(A B);
+impl_args_tuple!(A B C);
+impl_args_tuple!(A B C D);
+impl_args_tuple!(A B C D E);
+impl_args_tuple!(A B C D E F);
+impl_args_tuple!(A B C D E F G);
+impl_args_tuple!(A B C D E F G H);
+impl_args_tuple!(A B C D E F G H I);
+impl_args_tuple!(A B C D E F G H I J);
+impl_args_tuple!(A B C D E F G H I J K);
+impl_args_tuple!(A B C D E F G H I J K L);
+impl_args_tuple!(A B C D E F G H I J K L M);
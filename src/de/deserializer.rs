@@ -1,23 +1,39 @@
-use std::ffi::CStr;
+use std::cell::{Ref, RefMut};
 use std::iter::FusedIterator;
 use std::marker::PhantomData;
+use std::{slice, str};
 
 use mruby_sys::{mrb_float, mrb_int, mrb_state, mrb_value};
 
 use super::{CastError, FromValue};
+use crate::arena::{self, ArenaIndex};
 use crate::class::Class;
+use crate::data;
+use crate::function::raising;
+use crate::object::Object;
+use crate::root::Root;
 use crate::symbol::FromSymbol;
 use crate::value::Value;
+use crate::Mruby;
 
 #[derive(Debug)]
-pub struct Deserializer {
+pub struct Deserializer<'mrb> {
     state: *mut mrb_state,
-    pub(super) value: Value,
+    value: Value<'mrb>,
 }
 
-impl Deserializer {
-    pub(crate) const fn new(state: *mut mrb_state, value: Value) -> Self {
-        Deserializer { state, value }
+impl<'mrb> Deserializer<'mrb> {
+    pub(crate) const fn new(state: *mut mrb_state, value: mrb_value) -> Self {
+        Deserializer {
+            state,
+            value: Value::new(state, value),
+        }
+    }
+
+    /// Returns the value, keeping it in the GC arena for as long as the interpreter is borrowed.
+    pub(super) fn into_value(self) -> Value<'mrb> {
+        unsafe { arena::keep(self.state, *self.value.get_ref()) };
+        self.value
     }
 
     // TODO: Switch to `const fn` once `match` statements are stabilized. See:
@@ -34,7 +50,7 @@ impl Deserializer {
     }
 
     pub fn deserialize_char(self) -> Result<char, CastError> {
-        let text = self.deserialize_str()?;
+        let text = self.deserialize_string()?;
         let mut chars = text.chars();
         let first_char = chars
             .next()
@@ -72,49 +88,139 @@ impl Deserializer {
     }
 
     #[inline]
-    pub fn deserialize_map<K: FromValue, V: FromValue>(self) -> MapIter<K, V> {
+    pub fn deserialize_map<K, V>(self) -> MapIter<'mrb, K, V>
+    where
+        K: FromValue<'mrb>,
+        V: FromValue<'mrb>,
+    {
         MapIter::new(self.state, self.value)
     }
 
-    pub fn deserialize_object<T: Class>(self) -> Result<T, CastError> {
-        unimplemented!()
+    /// Wraps any value other than `undef` in an `Object` for runtime reflection.
+    pub fn deserialize_dynamic_object(self) -> Result<Object<'mrb>, CastError> {
+        if self.value.is_undef() {
+            return Err(CastError::unexpected_type("value is undefined"));
+        }
+
+        let state = self.state;
+        Ok(Object::new(state, self.into_value()))
+    }
+
+    /// Keeps the value alive until the returned handle is dropped. This is what converting a
+    /// value into a `Root` does.
+    pub fn deserialize_root(self) -> Root {
+        unsafe { Root::new(self.state, self.value.into_inner()) }
+    }
+
+    /// Moves the Rust value out of an object created by `Serializer::serialize_object()` or by
+    /// a `Class::define()` initializer.
+    ///
+    /// The Ruby object is left uninitialized, so any method called on it afterwards raises a
+    /// `TypeError`.
+    pub fn deserialize_object<T: Class + Send + 'static>(self) -> Result<T, CastError> {
+        unsafe { data::take(self.state, self.value.into_inner()) }
+    }
+
+    /// Borrows the Rust value stored in the object, which stays alive as long as the object is
+    /// reachable from Ruby.
+    pub fn deserialize_object_ref<T>(self) -> Result<Ref<'mrb, T>, CastError>
+    where
+        T: Class + Send + 'static,
+    {
+        let cell = unsafe { data::get::<T>(self.state, *self.value.get_ref())? };
+        // NOTE: The object may not be referenced from anywhere else, e.g. when it was just created
+        // with `new`, so it is kept alive like a `Value` for as long as it is borrowed.
+        self.into_value();
+        cell.try_borrow()
+            .map_err(|_| CastError::unexpected_type("object is mutably borrowed"))
+    }
+
+    /// Mutably borrows the Rust value stored in the object, which stays alive as long as the
+    /// object is reachable from Ruby.
+    pub fn deserialize_object_mut<T>(self) -> Result<RefMut<'mrb, T>, CastError>
+    where
+        T: Class + Send + 'static,
+    {
+        let cell = unsafe { data::get::<T>(self.state, *self.value.get_ref())? };
+        self.into_value();
+        cell.try_borrow_mut()
+            .map_err(|_| CastError::unexpected_type("object is already borrowed"))
     }
 
-    pub fn deserialize_option<T: FromValue>(self) -> Result<Option<T>, CastError> {
+    pub fn deserialize_option<T: FromValue<'mrb>>(self) -> Result<Option<T>, CastError> {
         use mruby_sys::mrb_ext_is_value_nil;
 
         unsafe {
             if mrb_ext_is_value_nil(*self.value.get_ref()) == 1 {
                 Ok(None)
             } else {
-                let de = Deserializer::new(self.state, self.value);
-                T::from_value(de).map(Some)
+                T::from_value(self).map(Some)
             }
         }
     }
 
-    pub fn deserialize_str<'a>(self) -> Result<&'a str, CastError> {
-        use mruby_sys::{mrb_str_to_cstr, MRB_TT_STRING, MRB_TT_SYMBOL};
+    /// Borrows the contents of a Ruby string, or the name of a symbol.
+    ///
+    /// A frozen string is borrowed directly and kept in the GC arena for as long as the interpreter
+    /// is borrowed. Any other string may still be modified by Ruby code, so its contents are copied
+    /// into a new string first, which Ruby code never sees.
+    pub fn deserialize_str(self) -> Result<&'mrb str, CastError> {
+        use mruby_sys::{
+            mrb_string_value_len, mrb_string_value_ptr, RBasic, MRB_FL_OBJ_IS_FROZEN,
+            MRB_TT_STRING, MRB_TT_SYMBOL,
+        };
+
+        unsafe {
+            let value = *self.value.get_ref();
+            match value.tt {
+                MRB_TT_STRING => {
+                    let ptr = mrb_string_value_ptr(self.state, value);
+                    let len = mrb_string_value_len(self.state, value);
+                    let s = str::from_utf8(raw_bytes(ptr, len))?;
+
+                    let flags = (*(value.value.p as *mut RBasic)).flags();
+                    if flags & MRB_FL_OBJ_IS_FROZEN != 0 {
+                        arena::keep(self.state, value);
+                        Ok(s)
+                    } else {
+                        Ok(keep_copy(self.state, s))
+                    }
+                }
+                MRB_TT_SYMBOL => self.deserialize_symbol(),
+                _ => Err(CastError::unexpected_type("value is not a string")),
+            }
+        }
+    }
+
+    /// Copies the contents of a Ruby string, or the name of a symbol.
+    pub fn deserialize_string(self) -> Result<String, CastError> {
+        use mruby_sys::{mrb_string_value_len, mrb_string_value_ptr, MRB_TT_STRING, MRB_TT_SYMBOL};
 
         unsafe {
             let value = self.value.get_ref();
-            let s = match value.tt {
-                MRB_TT_STRING => mrb_str_to_cstr(self.state, self.value.into_inner()),
+            let bytes = match value.tt {
+                MRB_TT_STRING => {
+                    let ptr = mrb_string_value_ptr(self.state, *value);
+                    let len = mrb_string_value_len(self.state, *value);
+                    raw_bytes(ptr, len)
+                }
                 MRB_TT_SYMBOL => return self.deserialize_symbol(),
                 _ => return Err(CastError::unexpected_type("value is not a string")),
             };
 
-            CStr::from_ptr(s).to_str().map_err(CastError::from)
+            str::from_utf8(bytes)
+                .map(ToOwned::to_owned)
+                .map_err(CastError::from)
         }
     }
 
     #[inline]
-    pub fn deserialize_seq<T: FromValue>(self) -> SeqIter<T> {
+    pub fn deserialize_seq<T: FromValue<'mrb>>(self) -> SeqIter<'mrb, T> {
         SeqIter::new(self.state, self.value)
     }
 
-    pub fn deserialize_symbol<'a, T: FromSymbol<'a> + 'a>(self) -> Result<T, CastError> {
-        use mruby_sys::{mrb_ext_symbol_to_sym, mrb_str_to_cstr, mrb_sym2str, MRB_TT_SYMBOL};
+    pub fn deserialize_symbol<T: FromSymbol<'mrb>>(self) -> Result<T, CastError> {
+        use mruby_sys::{mrb_ext_symbol_to_sym, mrb_sym2name_len, MRB_TT_SYMBOL};
 
         let value = self.value.into_inner();
         if value.tt != MRB_TT_SYMBOL {
@@ -123,15 +229,23 @@ impl Deserializer {
 
         unsafe {
             let sym = mrb_ext_symbol_to_sym(value);
-            let name = mrb_sym2str(self.state, sym);
-            let cstr = mrb_str_to_cstr(self.state, name);
-            let s = CStr::from_ptr(cstr).to_str()?;
+            let mut len = 0;
+            let ptr = mrb_sym2name_len(self.state, sym, &mut len);
+            let mut s = str::from_utf8(raw_bytes(ptr, len))?;
+
+            // NOTE: The names of symbols short enough to be packed into the symbol itself are
+            // unpacked into a buffer shared by all of them, which the next lookup overwrites. Other
+            // names are stored in the symbol table until the interpreter is closed.
+            if ptr == (*self.state).symbuf.as_ptr() {
+                s = keep_copy(self.state, s);
+            }
+
             T::from_name(s).map_err(CastError::from)
         }
     }
 
     #[inline]
-    pub fn deserialize_tuple(self) -> SeqDeserializer {
+    pub fn deserialize_tuple(self) -> SeqDeserializer<'mrb> {
         SeqDeserializer::new(self.state, self.value)
     }
 
@@ -150,15 +264,57 @@ impl Deserializer {
 }
 
 #[derive(Debug)]
-pub struct SeqDeserializer {
+pub struct ArgsDeserializer<'a, 'mrb> {
+    state: *mut mrb_state,
+    args: &'a [mrb_value],
+    index: usize,
+    _marker: PhantomData<&'mrb Mruby>,
+}
+
+impl<'a, 'mrb> ArgsDeserializer<'a, 'mrb> {
+    pub(crate) const fn new(state: *mut mrb_state, args: &'a [mrb_value]) -> Self {
+        ArgsDeserializer {
+            state,
+            args,
+            index: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn next_arg<T: FromValue<'mrb>>(&mut self) -> Option<Result<T, CastError>> {
+        let arg = *self.args.get(self.index)?;
+        self.index += 1;
+
+        let de = Deserializer::new(self.state, arg);
+        Some(T::from_value(de))
+    }
+
+    /// Deserializes `nil` in place of an argument which the caller did not pass.
+    pub fn missing_arg<T: FromValue<'mrb>>(&self) -> Result<T, CastError> {
+        let nil = unsafe { mruby_sys::mrb_ext_nil_value() };
+        T::from_value(Deserializer::new(self.state, nil))
+    }
+
+    /// Returns the number of arguments which have not been deserialized yet.
+    pub fn len(&self) -> usize {
+        self.args.len() - self.index
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug)]
+pub struct SeqDeserializer<'mrb> {
     state: *mut mrb_state,
-    value: Option<Value>,
+    value: Option<Value<'mrb>>,
     index: usize,
     len: usize,
 }
 
-impl SeqDeserializer {
-    fn new(state: *mut mrb_state, value: Value) -> Self {
+impl<'mrb> SeqDeserializer<'mrb> {
+    fn new(state: *mut mrb_state, value: Value<'mrb>) -> Self {
         use mruby_sys::{mrb_ext_ary_len, MRB_TT_ARRAY};
 
         let (value, len) = if value.get_ref().tt == MRB_TT_ARRAY {
@@ -176,7 +332,7 @@ impl SeqDeserializer {
         }
     }
 
-    pub fn next_element<T: FromValue>(&mut self) -> Option<Result<T, CastError>> {
+    pub fn next_element<T: FromValue<'mrb>>(&mut self) -> Option<Result<T, CastError>> {
         use mruby_sys::mrb_ary_ref;
 
         if self.index >= self.len {
@@ -184,9 +340,13 @@ impl SeqDeserializer {
         }
 
         let value = self.value.take()?;
-        let elem = unsafe { mrb_ary_ref(self.state, *value.get_ref(), self.index as mrb_int) };
-        let de = Deserializer::new(self.state, Value(elem));
-        let result = T::from_value(de);
+        let result = unsafe {
+            let arena = ArenaIndex::save(self.state);
+            let elem = mrb_ary_ref(self.state, *value.get_ref(), self.index as mrb_int);
+            let result = T::from_value(Deserializer::new(self.state, elem));
+            arena.restore();
+            result
+        };
 
         if result.is_err() {
             return Some(result);
@@ -201,26 +361,30 @@ impl SeqDeserializer {
     pub const fn len(&self) -> usize {
         self.len
     }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
 }
 
 #[derive(Debug)]
-pub struct MapIter<K, V> {
+pub struct MapIter<'mrb, K, V> {
     state: *mut mrb_state,
     entries: Option<(mrb_value, mrb_value)>,
     index: usize,
     len: usize,
-    _marker: PhantomData<(K, V)>,
+    _marker: PhantomData<(&'mrb Mruby, K, V)>,
 }
 
-impl<K: FromValue, V: FromValue> MapIter<K, V> {
-    fn new(state: *mut mrb_state, value: Value) -> Self {
+impl<'mrb, K: FromValue<'mrb>, V: FromValue<'mrb>> MapIter<'mrb, K, V> {
+    fn new(state: *mut mrb_state, value: Value<'mrb>) -> Self {
         use mruby_sys::{mrb_ext_ary_len, mrb_hash_keys, mrb_hash_values, MRB_TT_HASH};
 
         let (entries, len) = unsafe {
             let inner = value.into_inner();
             if inner.tt == MRB_TT_HASH {
-                let keys = mrb_hash_keys(state, inner);
-                let values = mrb_hash_values(state, inner);
+                let keys = raising(state, || mrb_hash_keys(state, inner));
+                let values = raising(state, || mrb_hash_values(state, inner));
                 let entries = (keys, values);
                 let len = mrb_ext_ary_len(keys) as usize;
                 (Some(entries), len)
@@ -239,7 +403,7 @@ impl<K: FromValue, V: FromValue> MapIter<K, V> {
     }
 }
 
-impl<K: FromValue, V: FromValue> Iterator for MapIter<K, V> {
+impl<'mrb, K: FromValue<'mrb>, V: FromValue<'mrb>> Iterator for MapIter<'mrb, K, V> {
     type Item = Result<(K, V), CastError>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -250,18 +414,21 @@ impl<K: FromValue, V: FromValue> Iterator for MapIter<K, V> {
         }
 
         let (keys, values) = self.entries.take()?;
+        let arena = unsafe { ArenaIndex::save(self.state) };
 
         let key = unsafe { mrb_ary_ref(self.state, keys, self.index as mrb_int) };
-        let key_de = Deserializer::new(self.state, Value(key));
+        let key_de = Deserializer::new(self.state, key);
         let key_result = K::from_value(key_de);
 
         if let Err(e) = key_result {
+            unsafe { arena.restore() };
             return Some(Err(e));
         }
 
         let val = unsafe { mrb_ary_ref(self.state, values, self.index as mrb_int) };
-        let val_de = Deserializer::new(self.state, Value(val));
+        let val_de = Deserializer::new(self.state, val);
         let val_result = V::from_value(val_de);
+        unsafe { arena.restore() };
 
         if let Err(e) = val_result {
             return Some(Err(e));
@@ -278,23 +445,27 @@ impl<K: FromValue, V: FromValue> Iterator for MapIter<K, V> {
     }
 }
 
-impl<K: FromValue, V: FromValue> ExactSizeIterator for MapIter<K, V> {
+impl<'mrb, K, V> ExactSizeIterator for MapIter<'mrb, K, V>
+where
+    K: FromValue<'mrb>,
+    V: FromValue<'mrb>,
+{
     #[inline]
     fn len(&self) -> usize {
         self.len
     }
 }
 
-impl<K: FromValue, V: FromValue> FusedIterator for MapIter<K, V> {}
+impl<'mrb, K: FromValue<'mrb>, V: FromValue<'mrb>> FusedIterator for MapIter<'mrb, K, V> {}
 
 #[derive(Debug)]
-pub struct SeqIter<T> {
-    sequence: SeqDeserializer,
+pub struct SeqIter<'mrb, T> {
+    sequence: SeqDeserializer<'mrb>,
     _marker: PhantomData<T>,
 }
 
-impl<T: FromValue> SeqIter<T> {
-    fn new(state: *mut mrb_state, value: Value) -> Self {
+impl<'mrb, T: FromValue<'mrb>> SeqIter<'mrb, T> {
+    fn new(state: *mut mrb_state, value: Value<'mrb>) -> Self {
         SeqIter {
             sequence: SeqDeserializer::new(state, value),
             _marker: PhantomData,
@@ -302,7 +473,7 @@ impl<T: FromValue> SeqIter<T> {
     }
 }
 
-impl<T: FromValue> Iterator for SeqIter<T> {
+impl<'mrb, T: FromValue<'mrb>> Iterator for SeqIter<'mrb, T> {
     type Item = Result<T, CastError>;
 
     #[inline]
@@ -316,11 +487,31 @@ impl<T: FromValue> Iterator for SeqIter<T> {
     }
 }
 
-impl<T: FromValue> ExactSizeIterator for SeqIter<T> {
+impl<'mrb, T: FromValue<'mrb>> ExactSizeIterator for SeqIter<'mrb, T> {
     #[inline]
     fn len(&self) -> usize {
         self.sequence.len()
     }
 }
 
-impl<T: FromValue> FusedIterator for SeqIter<T> {}
+impl<'mrb, T: FromValue<'mrb>> FusedIterator for SeqIter<'mrb, T> {}
+
+/// Copies `s` into a new Ruby string, which is kept in the GC arena for as long as the interpreter
+/// is borrowed for `'mrb`, and borrows its contents.
+unsafe fn keep_copy<'mrb>(state: *mut mrb_state, s: &str) -> &'mrb str {
+    use mruby_sys::{mrb_str_new, mrb_string_value_ptr};
+
+    let (ptr, len) = (s.as_ptr() as *const _, s.len());
+    let copy = raising(state, || mrb_str_new(state, ptr, len));
+    arena::keep(state, copy);
+    str::from_utf8_unchecked(raw_bytes(mrb_string_value_ptr(state, copy), len as mrb_int))
+}
+
+/// Returns `len` bytes starting at `ptr`, which must stay valid for `'a`.
+unsafe fn raw_bytes<'a>(ptr: *const std::os::raw::c_char, len: mrb_int) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr as *const u8, len as usize)
+    }
+}
@@ -0,0 +1,285 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use mruby_sys::{mrb_state, mrb_value};
+
+use crate::de::{CastError, Deserializer, FromValue};
+use crate::function::{funcall, protect_raw};
+use crate::userdata::UserData;
+
+/// Name of the exception class raised once the limit set with `Mruby::set_execution_limit()` is
+/// exceeded.
+pub(crate) const LIMIT_EXCEEDED_CLASS: &str = "ExecutionLimitExceeded";
+
+/// Name of the exception class raised by `Kernel#exit`.
+pub(crate) const EXIT_CLASS: &str = "SystemExit";
+
+/// Name of the exception class raised when interrupted with `InterruptHandle::interrupt()`.
+pub(crate) const INTERRUPTED_CLASS: &str = "ExecutionInterrupted";
+
+/// Reason for aborting running code, which is told apart by the class of the exception raised.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum Abort {
+    /// The script called `exit` or `exit!`, raising `SystemExit`.
+    Exit,
+    /// The Rust side aborted execution.
+    Execution(ExecutionError),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EvalError {
+    Bytecode(BytecodeError),
+    Cast(CastError),
+    Exception(RubyException),
+    Execution(ExecutionError),
+    Exit(i32),
+}
+
+impl Display for EvalError {
+    fn fmt(&self, fmt: &mut Formatter) -> FmtResult {
+        match *self {
+            EvalError::Bytecode(ref err) => write!(fmt, "failed to load bytecode: {}", err),
+            EvalError::Cast(ref err) => write!(fmt, "failed to convert result: {}", err),
+            EvalError::Exception(ref exc) => write!(fmt, "uncaught exception: {}", exc),
+            EvalError::Execution(ref err) => write!(fmt, "execution aborted: {}", err),
+            EvalError::Exit(status) => write!(fmt, "script exited with status {}", status),
+        }
+    }
+}
+
+impl Error for EvalError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            EvalError::Bytecode(ref err) => Some(err),
+            EvalError::Cast(ref err) => Some(err),
+            EvalError::Exception(ref exc) => Some(exc),
+            EvalError::Execution(ref err) => Some(err),
+            EvalError::Exit(_) => None,
+        }
+    }
+}
+
+impl From<BytecodeError> for EvalError {
+    fn from(err: BytecodeError) -> Self {
+        EvalError::Bytecode(err)
+    }
+}
+
+impl From<CastError> for EvalError {
+    fn from(err: CastError) -> Self {
+        EvalError::Cast(err)
+    }
+}
+
+impl From<ExecutionError> for EvalError {
+    fn from(err: ExecutionError) -> Self {
+        EvalError::Execution(err)
+    }
+}
+
+impl From<RubyException> for EvalError {
+    fn from(exc: RubyException) -> Self {
+        match exc.abort {
+            Some(Abort::Exit) => EvalError::Exit(exc.exit_status.unwrap_or(0)),
+            Some(Abort::Execution(err)) => EvalError::Execution(err),
+            None => EvalError::Exception(exc),
+        }
+    }
+}
+
+/// An error raised from the Rust side to abort running Ruby code.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ExecutionError {
+    /// The limit set with `Mruby::set_execution_limit()` was exceeded.
+    LimitExceeded,
+    /// The code was interrupted with `InterruptHandle::interrupt()`.
+    Interrupted,
+}
+
+impl Display for ExecutionError {
+    fn fmt(&self, fmt: &mut Formatter) -> FmtResult {
+        let description = match *self {
+            ExecutionError::LimitExceeded => "execution limit exceeded",
+            ExecutionError::Interrupted => "execution interrupted",
+        };
+
+        fmt.write_str(description)
+    }
+}
+
+impl Error for ExecutionError {}
+
+/// An exception raised by Ruby code and captured on the Rust side.
+///
+/// The exception object itself is not retained; its class path, message and backtrace are copied
+/// out of the interpreter when the exception is caught, so this type can outlive the `Mruby`
+/// instance that produced it.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RubyException {
+    class: String,
+    message: String,
+    backtrace: Vec<String>,
+    abort: Option<Abort>,
+    exit_status: Option<i32>,
+}
+
+impl RubyException {
+    /// Copies the class path, message and backtrace out of the given exception object.
+    pub(crate) unsafe fn from_value(state: *mut mrb_state, exc: mrb_value) -> Self {
+        use mruby_sys::mrb_obj_class;
+        use mruby_sys::{mrb_class_path, mrb_exc_backtrace};
+
+        // NOTE: Building these values allocates, which may raise when the memory limit is
+        // reached. Anything raised while copying the exception out is discarded.
+        let class = protect_raw(state, || mrb_class_path(state, mrb_obj_class(state, exc)));
+        let class = class
+            .ok()
+            .and_then(|class| String::from_value(Deserializer::new(state, class)).ok())
+            .unwrap_or_else(|| String::from("Exception"));
+
+        // NOTE: `message` may be overridden, so anything it raises is discarded.
+        let message = funcall(state, exc, "message", &[]).ok();
+        let message = message
+            .and_then(|message| String::from_value(Deserializer::new(state, message)).ok())
+            .unwrap_or_default();
+
+        // NOTE: `mrb_exc_backtrace()` returns `nil` when no backtrace was recorded, which
+        // deserializes into an empty sequence.
+        let backtrace = protect_raw(state, || mrb_exc_backtrace(state, exc));
+        let backtrace = backtrace
+            .ok()
+            .and_then(|backtrace| Vec::from_value(Deserializer::new(state, backtrace)).ok())
+            .unwrap_or_default();
+
+        let abort = UserData::get(state).abort_kind(mrb_obj_class(state, exc));
+        let exit_status = if abort == Some(Abort::Exit) {
+            Some(protect_raw(state, || crate::exit::status(state, exc)).unwrap_or(0))
+        } else {
+            None
+        };
+
+        RubyException {
+            class,
+            message,
+            backtrace,
+            abort,
+            exit_status,
+        }
+    }
+
+    /// Returns what raising the exception aborted running code for, if anything.
+    pub(crate) fn abort(&self) -> Option<Abort> {
+        self.abort
+    }
+
+    /// Returns the fully qualified name of the exception class, e.g. `"ArgumentError"`.
+    pub fn class_name(&self) -> &str {
+        &self.class
+    }
+
+    /// Returns the exception message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the backtrace lines, innermost frame first, formatted as `file:line:in method`.
+    ///
+    /// This is empty if the code that raised the exception was loaded without a filename.
+    pub fn backtrace(&self) -> &[String] {
+        &self.backtrace
+    }
+}
+
+impl Display for RubyException {
+    fn fmt(&self, fmt: &mut Formatter) -> FmtResult {
+        if let Some(location) = self.backtrace.first() {
+            write!(fmt, "{}: ", location)?;
+        }
+
+        write!(fmt, "{} ({})", self.message, self.class)
+    }
+}
+
+impl Error for RubyException {}
+
+/// An error raised while compiling Ruby source code into bytecode.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CompileError {
+    filename: String,
+    location: Option<(usize, usize)>,
+    message: String,
+}
+
+impl CompileError {
+    pub(crate) fn new<M>(filename: &str, location: Option<(usize, usize)>, message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        CompileError {
+            filename: filename.to_owned(),
+            location,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the filename the source code was compiled with.
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Returns the line number of the syntax error, or `None` if the error has no location.
+    pub fn line(&self) -> Option<usize> {
+        self.location.map(|(line, _)| line)
+    }
+
+    /// Returns the column of the syntax error, or `None` if the error has no location.
+    pub fn column(&self) -> Option<usize> {
+        self.location.map(|(_, column)| column)
+    }
+
+    /// Returns the error message, e.g. `"syntax error, unexpected $end"`.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for CompileError {
+    fn fmt(&self, fmt: &mut Formatter) -> FmtResult {
+        match self.location {
+            Some((line, column)) => write!(fmt, "{}:{}:{}: ", self.filename, line, column)?,
+            None => write!(fmt, "{}: ", self.filename)?,
+        }
+
+        write!(fmt, "{}", self.message)
+    }
+}
+
+impl Error for CompileError {}
+
+/// An error returned when loading bytes which are not a valid mruby RITE binary.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BytecodeError {
+    /// The binary does not start with a RITE header.
+    InvalidHeader,
+    /// The binary was written for an incompatible version of the RITE format.
+    UnsupportedVersion,
+    /// The binary is shorter than the size recorded in its header.
+    Truncated,
+    /// The checksum in the header does not match the contents of the binary.
+    ChecksumMismatch,
+}
+
+impl Display for BytecodeError {
+    fn fmt(&self, fmt: &mut Formatter) -> FmtResult {
+        let description = match *self {
+            BytecodeError::InvalidHeader => "invalid RITE header",
+            BytecodeError::UnsupportedVersion => "unsupported RITE format version",
+            BytecodeError::Truncated => "binary is truncated",
+            BytecodeError::ChecksumMismatch => "checksum mismatch",
+        };
+
+        fmt.write_str(description)
+    }
+}
+
+impl Error for BytecodeError {}
@@ -0,0 +1,113 @@
+use mruby_sys::{mrb_aspec, mrb_state, mrb_sym, mrb_value};
+
+use crate::error::{Abort, EXIT_CLASS};
+use crate::function::{new_exception, raising};
+use crate::userdata::UserData;
+
+/// Equivalent of `MRB_ARGS_OPT(1)`, accepting one optional argument.
+const ARGS_OPT_1: mrb_aspec = 1 << 13;
+
+/// Equivalent of `MRB_ARGS_NONE()`.
+const ARGS_NONE: mrb_aspec = 0;
+
+/// Defines `SystemExit` and replaces `Kernel#exit` and `Kernel#exit!` with methods raising it.
+///
+/// The `mruby-exit` gem terminates the whole process instead, which is never what the host
+/// application wants. Raising an exception unwinds the script and runs its `ensure` blocks, after
+/// which the Rust call that ran it returns `EvalError::Exit`.
+pub(crate) unsafe fn init(state: *mut mrb_state) {
+    use mruby_sys::{mrb_define_class, mrb_define_method, mrb_define_module_function};
+    use mruby_sys::{mrb_ext_class_value, mrb_gc_register};
+
+    let name = format!("{}\0", EXIT_CLASS);
+    let class = raising(state, || {
+        let class = mrb_define_class(state, name.as_ptr() as *const _, (*state).eException_class);
+        // NOTE: Ruby code may remove the constant, so the class is kept alive independently of it.
+        mrb_gc_register(state, mrb_ext_class_value(class));
+        class
+    });
+
+    UserData::get(state).add_abort_class(class, Abort::Exit);
+    raising(state, || {
+        let status = b"status\0".as_ptr() as *const _;
+        let success = b"success?\0".as_ptr() as *const _;
+        mrb_define_method(state, class, status, Some(system_exit_status), ARGS_NONE);
+        mrb_define_method(state, class, success, Some(system_exit_success), ARGS_NONE);
+
+        // NOTE: Unlike in CRuby, `exit!` runs `ensure` blocks as well, since Rust code may rely on
+        // them to restore state shared with the script.
+        let kernel = (*state).kernel_module;
+        for name in &[&b"exit\0"[..], &b"exit!\0"[..]] {
+            let name = name.as_ptr() as *const _;
+            mrb_define_module_function(state, kernel, name, Some(exit), ARGS_OPT_1);
+        }
+    });
+}
+
+/// Reads the exit status stored in a `SystemExit` exception, which defaults to success.
+pub(crate) unsafe fn status(state: *mut mrb_state, exc: mrb_value) -> i32 {
+    use mruby_sys::{mrb_ext_fixnum_to_cint, mrb_iv_get, MRB_TT_FIXNUM};
+
+    let status = mrb_iv_get(state, exc, status_sym(state));
+    if status.tt == MRB_TT_FIXNUM {
+        mrb_ext_fixnum_to_cint(status) as i32
+    } else {
+        0
+    }
+}
+
+unsafe fn status_sym(state: *mut mrb_state) -> mrb_sym {
+    use mruby_sys::mrb_intern;
+
+    let name = "@status";
+    mrb_intern(state, name.as_ptr() as *const _, name.len())
+}
+
+/// Implementation of `Kernel#exit(status = true)`.
+///
+/// NOTE: Raising unwinds the stack with `longjmp()`, so this must not hold any Rust values which
+/// need to be dropped.
+unsafe extern "C" fn exit(state: *mut mrb_state, _this: mrb_value) -> mrb_value {
+    use mruby_sys::{mrb_exc_new_str, mrb_iv_set, mrb_str_new};
+    use mruby_sys::{mrb_exc_raise, mrb_ext_fixnum_value, mrb_ext_undef_value, mrb_get_args};
+    use mruby_sys::{MRB_TT_FALSE, MRB_TT_FIXNUM, MRB_TT_TRUE, MRB_TT_UNDEF};
+
+    let mut arg = mrb_ext_undef_value();
+    mrb_get_args(state, b"|o\0".as_ptr() as *const _, &mut arg);
+
+    let status = match arg.tt {
+        MRB_TT_UNDEF | MRB_TT_TRUE => mrb_ext_fixnum_value(0),
+        // NOTE: `nil` shares its type tag with `false`, which is told apart by a non-zero value.
+        MRB_TT_FALSE if arg.value.i != 0 => mrb_ext_fixnum_value(1),
+        MRB_TT_FIXNUM => arg,
+        _ => {
+            let exc = new_exception(state, "TypeError", "exit status must be an Integer");
+            mrb_exc_raise(state, exc);
+            unreachable!("mrb_exc_raise() returned")
+        }
+    };
+
+    // NOTE: The class is not looked up by name, since Ruby code may have replaced the constant.
+    let class = UserData::get(state)
+        .abort_class(Abort::Exit)
+        .expect("SystemExit is defined on startup");
+    let msg = mrb_str_new(state, b"exit".as_ptr() as *const _, 4);
+    let exc = mrb_exc_new_str(state, class, msg);
+    mrb_iv_set(state, exc, status_sym(state), status);
+    mrb_exc_raise(state, exc);
+    unreachable!("mrb_exc_raise() returned")
+}
+
+/// Implementation of `SystemExit#status`.
+unsafe extern "C" fn system_exit_status(state: *mut mrb_state, this: mrb_value) -> mrb_value {
+    use mruby_sys::mrb_ext_fixnum_value;
+
+    mrb_ext_fixnum_value(status(state, this).into())
+}
+
+/// Implementation of `SystemExit#success?`.
+unsafe extern "C" fn system_exit_success(state: *mut mrb_state, this: mrb_value) -> mrb_value {
+    use mruby_sys::mrb_ext_bool_value;
+
+    mrb_ext_bool_value((status(state, this) == 0) as _)
+}
@@ -0,0 +1,461 @@
+use std::any::Any;
+use std::ffi::CString;
+use std::fmt::Display;
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::thread;
+
+use mruby_sys::{mrb_int, mrb_state, mrb_value, RClass};
+
+use crate::de::{ArgsDeserializer, CastError, Deserializer, FromArgs, FromValue};
+use crate::error::{EvalError, RubyException};
+use crate::gc;
+use crate::root;
+use crate::ser::{Serializer, ToArgs, ToValue};
+use crate::userdata::UserData;
+use crate::value::Value;
+
+type Callback =
+    dyn Fn(*mut mrb_state, mrb_value, &[mrb_value]) -> Result<mrb_value, mrb_value> + Send;
+
+/// A type-erased Rust function which can be bound to a Ruby method.
+///
+/// Functions are owned by the interpreter they are defined on and are dropped along with it. On
+/// failure, the callback returns the Ruby exception to raise instead of raising it directly, so
+/// that no Rust values are left on the stack when mruby unwinds past the trampoline.
+pub(crate) struct Function(Box<Callback>);
+
+impl Function {
+    pub fn new<'mrb, F, S, A, R, E>(func: F) -> Self
+    where
+        F: Fn(S, A) -> Result<R, E> + Send + 'static,
+        S: FromValue<'mrb>,
+        A: FromArgs<'mrb>,
+        R: ToValue,
+        E: Display,
+    {
+        Function::from_callback(move |state, this, args| unsafe {
+            let this = S::from_value(Deserializer::new(state, this))
+                .map_err(|e| cast_exception(state, e))?;
+            let args = convert_args(state, args)?;
+            convert_return(state, func(this, args))
+        })
+    }
+
+    /// Wraps a callback operating on raw values, for receivers which are not `FromValue`.
+    pub fn from_callback<F>(callback: F) -> Self
+    where
+        F: Fn(*mut mrb_state, mrb_value, &[mrb_value]) -> Result<mrb_value, mrb_value>
+            + Send
+            + 'static,
+    {
+        Function(Box::new(callback))
+    }
+
+    /// Binds this function to the method `name` on `class`.
+    ///
+    /// The function is referenced by pointer from the method's environment, so the caller must
+    /// ensure it outlives every call made through the method. Defining the method allocates, so
+    /// this may raise and must be called through `raising()` or in a protected call.
+    pub unsafe fn define(&self, state: *mut mrb_state, class: *mut RClass, name: &str) {
+        use mruby_sys::{mrb_define_method_raw, mrb_ext_cptr_value, mrb_intern, mrb_method_t};
+        use mruby_sys::{mrb_method_t__bindgen_ty_1, mrb_proc_new_cfunc_with_env};
+
+        let ptr = self as *const Function as *mut _;
+        let env = [mrb_ext_cptr_value(state, ptr)];
+        let proc_ = mrb_proc_new_cfunc_with_env(state, Some(trampoline), 1, env.as_ptr());
+
+        let method = mrb_method_t {
+            func_p: 0,
+            __bindgen_anon_1: mrb_method_t__bindgen_ty_1 { proc_ },
+        };
+
+        let sym = mrb_intern(state, name.as_ptr() as *const c_char, name.len());
+        mrb_define_method_raw(state, class, sym, method);
+    }
+}
+
+unsafe extern "C" fn trampoline(state: *mut mrb_state, this: mrb_value) -> mrb_value {
+    use mruby_sys::{mrb_exc_raise, mrb_ext_cptr_to_ptr, mrb_get_args, mrb_proc_cfunc_env_get};
+
+    let env = mrb_proc_cfunc_env_get(state, 0);
+    let func = &*(mrb_ext_cptr_to_ptr(env) as *const Function);
+
+    // NOTE: Without the `!` modifier, `mrb_get_args()` copies the arguments out of the VM stack,
+    // which may be reallocated if the function calls back into Ruby.
+    let mut argv: *mut mrb_value = ptr::null_mut();
+    let mut argc: mrb_int = 0;
+    mrb_get_args(
+        state,
+        b"*\0".as_ptr() as *const c_char,
+        &mut argv,
+        &mut argc,
+    );
+
+    let args = if argv.is_null() {
+        &[]
+    } else {
+        slice::from_raw_parts(argv, argc as usize)
+    };
+
+    // NOTE: The callback holds Rust values, such as `RefCell` guards, which must be dropped before
+    // raising. Exceptions raised while it runs are turned into panics by `raising()` for that
+    // reason, and are raised again here once the stack is unwound.
+    let kept = UserData::get(state).kept().len();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| (func.0)(state, this, args)));
+    // NOTE: mruby restores the GC arena once the function returns, releasing the values handed
+    // out to it.
+    UserData::get(state).kept().truncate(kept);
+    let exc = match result {
+        Ok(Ok(value)) => return value,
+        Ok(Err(exc)) => exc,
+        Err(payload) => match payload.downcast::<Raised>() {
+            Ok(raised) => raised.0,
+            Err(payload) => new_exception(state, "RuntimeError", panic_message(payload)),
+        },
+    };
+
+    // NOTE: When a call back into Ruby was aborted, the function fails with an error of its own,
+    // which Ruby code could rescue. The abort is raised instead, so that code is aborted as well.
+    let exc = crate::hook::pending_abort(state).unwrap_or(exc);
+
+    mrb_exc_raise(state, exc);
+    unreachable!("mrb_exc_raise() returned")
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    let msg = if let Some(msg) = payload.downcast_ref::<&str>() {
+        *msg
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.as_str()
+    } else {
+        "unknown cause"
+    };
+
+    format!("Rust function panicked: {}", msg)
+}
+
+/// Converts the raw method arguments into `A`, or into the exception to raise on failure.
+pub(crate) unsafe fn convert_args<'mrb, A: FromArgs<'mrb>>(
+    state: *mut mrb_state,
+    args: &[mrb_value],
+) -> Result<A, mrb_value> {
+    A::from_args(ArgsDeserializer::new(state, args)).map_err(|e| cast_exception(state, e))
+}
+
+/// Converts the result of a Rust function into its Ruby return value, or into a `RuntimeError`.
+pub(crate) unsafe fn convert_return<R: ToValue, E: Display>(
+    state: *mut mrb_state,
+    result: Result<R, E>,
+) -> Result<mrb_value, mrb_value> {
+    use mruby_sys::{mrb_ext_nil_value, MRB_TT_UNDEF};
+
+    match result {
+        // NOTE: `()` serializes into `undef`, which must never be handed to Ruby code.
+        Ok(ret) => match ret.to_value(Serializer::new(state)).into_inner() {
+            value if value.tt == MRB_TT_UNDEF => Ok(mrb_ext_nil_value()),
+            value => Ok(value),
+        },
+        Err(e) => Err(new_exception(state, "RuntimeError", e)),
+    }
+}
+
+pub(crate) unsafe fn cast_exception(state: *mut mrb_state, err: CastError) -> mrb_value {
+    match err {
+        CastError::Length(expected, actual) => {
+            let msg = format!("wrong number of arguments ({} for {})", actual, expected);
+            new_exception(state, "ArgumentError", msg)
+        }
+        other => new_exception(state, "TypeError", other),
+    }
+}
+
+/// Runs `body` with a handler installed for Ruby exceptions, returning the exception raised from
+/// it, or left pending by it, instead of unwinding past the caller.
+///
+/// This is the equivalent of `mrb_protect()` from the `mruby-error` gem, which is not always built.
+/// Raising unwinds the stack with `longjmp()`, so `body` must only call mruby functions and must
+/// not own any Rust values. Rust code which may raise is run with `catch_raised()` instead. A panic
+/// in `body` is resumed once the handler is removed again.
+pub(crate) unsafe fn protect<T, F>(state: *mut mrb_state, body: F) -> Result<T, RubyException>
+where
+    F: FnOnce() -> T,
+{
+    protect_raw(state, body).map_err(|exc| RubyException::from_value(state, exc))
+}
+
+/// Same as `protect()`, but returns the exception object itself so it can be raised again.
+///
+/// The exception is kept in the GC arena by `mrb_ext_protect()`, which protects it from the garbage
+/// collector until the arena is restored.
+pub(crate) unsafe fn protect_raw<T, F>(state: *mut mrb_state, body: F) -> Result<T, mrb_value>
+where
+    F: FnOnce() -> T,
+{
+    use mruby_sys::{mrb_ext_obj_value, mrb_ext_protect};
+
+    struct Protected<F, T> {
+        body: Option<F>,
+        result: Option<thread::Result<T>>,
+    }
+
+    unsafe extern "C" fn run<F: FnOnce() -> T, T>(_: *mut mrb_state, data: *mut c_void) {
+        let protected = &mut *(data as *mut Protected<F, T>);
+        let body = protected.body.take().expect("body is only run once");
+        protected.result = Some(panic::catch_unwind(AssertUnwindSafe(body)));
+    }
+
+    let mut protected = Protected {
+        body: Some(body),
+        result: None,
+    };
+
+    let data = &mut protected as *mut Protected<F, T> as *mut c_void;
+    let index = (*state).gc.arena_idx;
+    let raised = mrb_ext_protect(state, Some(run::<F, T>), data) != 0;
+    let exc = mem::replace(&mut (*state).exc, ptr::null_mut());
+    if !exc.is_null() {
+        return Err(mrb_ext_obj_value(exc as *mut _));
+    }
+
+    debug_assert!(!raised, "exception raised without setting `mrb_state.exc`");
+    release_reserved_slot(state, index);
+    match protected.result {
+        Some(Ok(result)) => Ok(result),
+        Some(Err(payload)) => panic::resume_unwind(payload),
+        None => unreachable!("body returned without a result"),
+    }
+}
+
+/// Releases the arena slot `mrb_ext_protect()` reserves at `index` for the exception, which is
+/// left unused when nothing was raised, moving the objects created by the body down into it.
+///
+/// Without this, every protected call would leave one more object in the arena.
+unsafe fn release_reserved_slot(state: *mut mrb_state, index: c_int) {
+    let gc = &mut (*state).gc;
+    let object_class = (*state).object_class as *mut _;
+    if gc.arena_idx <= index || *gc.arena.offset(index as isize) != object_class {
+        return;
+    }
+
+    let start = gc.arena.offset(index as isize);
+    let count = (gc.arena_idx - index - 1) as usize;
+    ptr::copy(start.add(1), start, count);
+    gc.arena_idx -= 1;
+}
+
+/// The payload of the panic unwinding the Rust stack after a mruby function called through
+/// `raising()` raised `exc`.
+struct Raised(mrb_value);
+
+// NOTE: The payload never leaves the thread of the interpreter, but `resume_unwind()` requires it.
+unsafe impl Send for Raised {}
+
+/// Raises the Ruby exception `exc` from Rust code.
+///
+/// The Rust stack is unwound like for a panic, dropping the values on it, up to the closest
+/// `catch_raised()` or the trampoline of the Rust function called from Ruby, which raises the
+/// exception again once nothing is left to drop.
+pub(crate) fn raise(exc: mrb_value) -> ! {
+    panic::resume_unwind(Box::new(Raised(exc)))
+}
+
+/// Calls `body`, which must only call mruby functions, and `raise()`s the exception it raised, if
+/// any.
+///
+/// This is how Rust code calls mruby functions which may raise, e.g. because they allocate.
+pub(crate) unsafe fn raising<T, F>(state: *mut mrb_state, body: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    protect_raw(state, body).unwrap_or_else(|exc| raise(exc))
+}
+
+/// Runs the Rust code `body`, returning the exception it raised with `raise()`, if any.
+///
+/// Other panics are resumed.
+pub(crate) unsafe fn catch_raised<T, F>(state: *mut mrb_state, body: F) -> Result<T, RubyException>
+where
+    F: FnOnce() -> T,
+{
+    catch_raised_raw(state, body).map_err(|exc| RubyException::from_value(state, exc))
+}
+
+/// Same as `catch_raised()`, but returns the exception object itself so it can be raised again.
+pub(crate) unsafe fn catch_raised_raw<T, F>(state: *mut mrb_state, body: F) -> Result<T, mrb_value>
+where
+    F: FnOnce() -> T,
+{
+    use mruby_sys::mrb_gc_protect;
+
+    let payload = match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(result) => return Ok(result),
+        Err(payload) => payload,
+    };
+
+    match payload.downcast::<Raised>() {
+        // NOTE: The arena may have been restored below the exception while unwinding.
+        Ok(raised) => {
+            let exc = raised.0;
+            match protect_raw(state, || mrb_gc_protect(state, exc)) {
+                Ok(()) => Err(exc),
+                Err(exc) => Err(exc),
+            }
+        }
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// Calls `method` on `receiver`, returning the exception it raised instead of unwinding.
+pub(crate) unsafe fn funcall(
+    state: *mut mrb_state,
+    receiver: mrb_value,
+    method: &str,
+    args: &[mrb_value],
+) -> Result<mrb_value, RubyException> {
+    use mruby_sys::{mrb_funcall_argv, mrb_intern};
+
+    let name = method.as_ptr() as *const c_char;
+    let len = args.len() as mrb_int;
+    protect(state, || {
+        let sym = mrb_intern(state, name, method.len());
+        mrb_funcall_argv(state, receiver, sym, len, args.as_ptr())
+    })
+}
+
+/// Calls `method` on `receiver` with the given arguments, converting the result into `R`.
+///
+/// Returns the exception raised by the call, or the error converting its result.
+pub(crate) unsafe fn call_method<'mrb, R, A>(
+    state: *mut mrb_state,
+    receiver: mrb_value,
+    method: &str,
+    args: A,
+) -> Result<Result<R, CastError>, RubyException>
+where
+    R: FromValue<'mrb>,
+    A: ToArgs,
+{
+    // NOTE: Converting the arguments and the result may raise, e.g. when the memory limit is
+    // reached.
+    catch_raised(state, || {
+        let args: Vec<mrb_value> = args
+            .to_args(Serializer::new(state))
+            .into_iter()
+            .map(Value::into_inner)
+            .collect();
+
+        let value = funcall(state, receiver, method, &args)?;
+        Ok(R::from_value(Deserializer::new(state, value)))
+    })?
+}
+
+/// Calls `method` on `receiver` from the Rust side, as `Mruby::call()` and `Object::call()` do.
+///
+/// Like any call into Ruby, this first releases the roots dropped since the last call and runs
+/// a deferred garbage collection, and clears a pending interrupt request once it has aborted the
+/// outermost call.
+pub(crate) unsafe fn call<'mrb, R, A>(
+    state: *mut mrb_state,
+    receiver: mrb_value,
+    method: &str,
+    args: A,
+) -> Result<Result<R, CastError>, RubyException>
+where
+    R: FromValue<'mrb>,
+    A: ToArgs,
+{
+    let call = Call::enter(state);
+    root::release_dropped(state);
+    gc::defer_automatic(state);
+    let result = call_method(state, receiver, method, args);
+    call.finish(result)
+}
+
+/// A call into Ruby from the Rust side, which is counted while it runs so that the calls made by
+/// Rust functions called from Ruby can be told apart from the outermost one.
+pub(crate) struct Call(*mut mrb_state);
+
+impl Call {
+    pub unsafe fn enter(state: *mut mrb_state) -> Self {
+        let user_data = UserData::get(state);
+        user_data.set_call_depth(user_data.call_depth() + 1);
+        Call(state)
+    }
+
+    /// Clears a pending interrupt request once it has aborted the outermost call, so the next
+    /// call runs normally again.
+    ///
+    /// A nested call leaves the request pending, so the Ruby code which called the Rust function
+    /// making it is aborted as well.
+    pub fn finish<T>(self, result: Result<T, RubyException>) -> Result<T, RubyException> {
+        unsafe {
+            use crate::error::{Abort, ExecutionError};
+
+            let interrupted = Some(Abort::Execution(ExecutionError::Interrupted));
+            if matches!(result, Err(ref exc) if exc.abort() == interrupted) {
+                let user_data = UserData::get(self.0);
+                if user_data.call_depth() == 1 {
+                    if let Some(interrupt) = user_data.interrupt() {
+                        interrupt.reset();
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl Drop for Call {
+    fn drop(&mut self) {
+        unsafe {
+            let user_data = UserData::get(self.0);
+            user_data.set_call_depth(user_data.call_depth() - 1);
+        }
+    }
+}
+
+/// Returns the exception a Rust function called from Ruby raises for `err`, for calls into Ruby
+/// which report a result that cannot be converted like any other exception.
+pub(crate) unsafe fn cast_error(state: *mut mrb_state, err: CastError) -> RubyException {
+    RubyException::from_value(state, cast_exception(state, err))
+}
+
+/// Converts the result of running Ruby code into `R`, returning the exception raised by the
+/// conversion, e.g. when the memory limit is reached.
+pub(crate) unsafe fn convert_result<'mrb, R>(
+    state: *mut mrb_state,
+    value: mrb_value,
+) -> Result<R, EvalError>
+where
+    R: FromValue<'mrb>,
+{
+    catch_raised(state, || R::from_value(Deserializer::new(state, value)))?.map_err(EvalError::from)
+}
+
+/// Creates a new exception object of the built-in class `class` with the given message.
+///
+/// If creating it raises, e.g. when the memory limit is reached, that exception is returned
+/// instead.
+pub(crate) unsafe fn new_exception<M: Display>(
+    state: *mut mrb_state,
+    class: &str,
+    msg: M,
+) -> mrb_value {
+    use mruby_sys::{mrb_exc_get, mrb_exc_new_str, mrb_str_new};
+
+    let class = CString::new(class).expect("String contains null byte");
+    let msg = msg.to_string();
+    let result = protect_raw(state, || {
+        let msg = mrb_str_new(state, msg.as_ptr() as *const c_char, msg.len());
+        mrb_exc_new_str(state, mrb_exc_get(state, class.as_ptr()), msg)
+    });
+
+    match result {
+        Ok(exc) | Err(exc) => exc,
+    }
+}
@@ -0,0 +1,190 @@
+use std::time::{Duration, Instant};
+
+use mruby_sys::{mrb_gc, mrb_heap_page, mrb_state};
+
+use crate::error::RubyException;
+use crate::function::{funcall, new_exception};
+use crate::root;
+use crate::userdata::UserData;
+
+/// Phase of the incremental garbage collector.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GcPhase {
+    /// No collection cycle is in progress. The next step marks the root objects.
+    Root,
+    /// Reachable objects are being marked.
+    Mark,
+    /// Unreachable objects are being freed.
+    Sweep,
+}
+
+impl GcPhase {
+    fn of(gc: &mrb_gc) -> Self {
+        use mruby_sys::{MRB_GC_STATE_MARK, MRB_GC_STATE_ROOT};
+
+        match gc.state {
+            MRB_GC_STATE_ROOT => GcPhase::Root,
+            MRB_GC_STATE_MARK => GcPhase::Mark,
+            _ => GcPhase::Sweep,
+        }
+    }
+}
+
+/// A snapshot of the state of an interpreter's garbage collector, returned by `Mruby::gc_stats()`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GcStats {
+    /// Number of objects currently allocated, including unreachable ones not yet swept.
+    pub live_objects: usize,
+    /// Number of objects which survived the last marking phase.
+    pub live_after_mark: usize,
+    /// Number of object heap pages, each holding a fixed number of objects.
+    pub heap_pages: usize,
+    /// Current phase of the incremental collector.
+    pub phase: GcPhase,
+    /// Number of live objects at which the next automatic collection step runs.
+    pub threshold: usize,
+    /// Whether the collector runs in generational mode.
+    pub generational: bool,
+    /// Whether automatic and explicit collection is enabled.
+    pub enabled: bool,
+}
+
+impl GcStats {
+    pub(crate) unsafe fn new(gc: &mrb_gc) -> Self {
+        GcStats {
+            live_objects: gc.live,
+            live_after_mark: gc.live_after_mark,
+            heap_pages: count_pages(gc.heaps),
+            phase: GcPhase::of(gc),
+            threshold: gc.threshold,
+            generational: gc.generational() != 0,
+            enabled: gc.disabled() == 0,
+        }
+    }
+}
+
+/// Outcome of `Mruby::gc_step_for()`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GcProgress {
+    /// Number of incremental steps run.
+    pub steps: usize,
+    /// Time spent collecting, which may exceed the budget by up to one step.
+    pub elapsed: Duration,
+    /// Whether the collection cycle was completed. Otherwise, the next call continues it.
+    pub complete: bool,
+    /// Phase of the collector after the last step.
+    pub phase: GcPhase,
+    /// Number of heap pages the current cycle has yet to sweep, which bounds the remaining work
+    /// once marking is done. While marking, this is every page.
+    pub pages_to_sweep: usize,
+}
+
+/// Runs a collection step of `state`, releasing dropped roots first and deferring automatic
+/// collection again afterwards if requested.
+pub(crate) unsafe fn collect(state: *mut mrb_state, step: unsafe extern "C" fn(*mut mrb_state)) {
+    root::release_dropped(state);
+    step(state);
+    defer_automatic(state);
+}
+
+/// Runs incremental collection steps on `state` until the current cycle is complete or `budget`
+/// is used up. At least one step is run unless the collector is disabled.
+pub(crate) unsafe fn step_for(state: *mut mrb_state, budget: Duration) -> GcProgress {
+    use mruby_sys::{mrb_incremental_gc, MRB_GC_STATE_ROOT};
+
+    let start = Instant::now();
+    let mut steps = 0;
+    let mut complete = false;
+
+    root::release_dropped(state);
+    if (*state).gc.disabled() == 0 && (*state).gc.iterating() == 0 {
+        loop {
+            mrb_incremental_gc(state);
+            steps += 1;
+
+            complete = (*state).gc.state == MRB_GC_STATE_ROOT;
+            if complete || start.elapsed() >= budget {
+                break;
+            }
+        }
+    }
+    defer_automatic(state);
+
+    let gc = &(*state).gc;
+    let phase = GcPhase::of(gc);
+    let pages_to_sweep = match phase {
+        GcPhase::Root => 0,
+        GcPhase::Mark => count_pages(gc.heaps),
+        GcPhase::Sweep => count_pages(gc.sweeps),
+    };
+
+    GcProgress {
+        steps,
+        elapsed: start.elapsed(),
+        complete,
+        phase,
+        pages_to_sweep,
+    }
+}
+
+/// Defers or resumes automatic collection in `state`.
+pub(crate) unsafe fn set_deferred(state: *mut mrb_state, deferred: bool) {
+    UserData::get(state).set_gc_deferred(deferred);
+    if deferred {
+        defer_automatic(state);
+    } else {
+        // NOTE: The next allocation runs a step, which computes the usual threshold again.
+        (*state).gc.threshold = (*state).gc.live;
+    }
+}
+
+/// Keeps allocations from triggering collection steps if automatic collection is deferred.
+///
+/// mruby runs a step whenever the number of live objects exceeds the threshold, which every
+/// collection resets. This must be called again after each one, including before running Ruby
+/// code, which may have called `GC.start`.
+pub(crate) unsafe fn defer_automatic(state: *mut mrb_state) {
+    if UserData::get(state).gc_deferred() {
+        (*state).gc.threshold = usize::MAX;
+    }
+}
+
+/// Switches the collector of `state` into or out of generational mode.
+///
+/// Switching modes requires finishing or resetting the current cycle, which mruby only exposes as
+/// `GC.generational_mode=`. It raises `RuntimeError` while the collector is disabled.
+pub(crate) unsafe fn set_generational_mode(
+    state: *mut mrb_state,
+    enabled: bool,
+) -> Result<(), RubyException> {
+    use mruby_sys::{mrb_bool, mrb_intern};
+    use mruby_sys::{mrb_const_defined, mrb_const_get, mrb_ext_bool_value, mrb_ext_class_value};
+
+    if ((*state).gc.generational() != 0) == enabled {
+        return Ok(());
+    }
+
+    let object = mrb_ext_class_value((*state).object_class);
+    let sym = mrb_intern(state, "GC".as_ptr() as *const _, 2);
+    if mrb_const_defined(state, object, sym) == 0 {
+        let exc = new_exception(state, "NameError", "uninitialized constant GC");
+        return Err(RubyException::from_value(state, exc));
+    }
+
+    let module = mrb_const_get(state, object, sym);
+    let arg = mrb_ext_bool_value(enabled as mrb_bool);
+    let result = funcall(state, module, "generational_mode=", &[arg]);
+    defer_automatic(state);
+    result.map(|_| ())
+}
+
+/// Returns the number of heap pages in the list starting at `page`.
+unsafe fn count_pages(mut page: *mut mrb_heap_page) -> usize {
+    let mut count = 0;
+    while !page.is_null() {
+        count += 1;
+        page = (*page).next;
+    }
+
+    count
+}
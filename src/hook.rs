@@ -0,0 +1,100 @@
+use mruby_sys::{mrb_code, mrb_irep, mrb_state, mrb_value};
+
+use crate::error::{Abort, RubyException};
+use crate::function::protect;
+use crate::userdata::UserData;
+
+/// Creates an exception of a new class named `class`, which stays registered with the garbage
+/// collector until the interpreter is closed.
+///
+/// These exceptions are raised by the hook to abort running code. They are allocated up front and
+/// raised again each time, since creating a new one could run Ruby code, e.g. an overridden
+/// `initialize`, from within the hook.
+pub(crate) unsafe fn new_abort_exception(
+    state: *mut mrb_state,
+    class: &str,
+    msg: &str,
+    abort: Abort,
+) -> Result<mrb_value, RubyException> {
+    use mruby_sys::{mrb_class_name_class, mrb_class_new, mrb_intern};
+    use mruby_sys::{mrb_exc_new_str, mrb_gc_register, mrb_str_new};
+
+    // NOTE: The class inherits from `Exception` rather than `StandardError`, so a bare `rescue`
+    // does not catch it. It is named without being assigned to a constant, so Ruby code cannot
+    // redefine it beforehand.
+    let class = protect(state, || {
+        let sym = mrb_intern(state, class.as_ptr() as *const _, class.len());
+        let class = mrb_class_new(state, (*state).eException_class);
+        mrb_class_name_class(state, (*state).object_class, class, sym);
+        class
+    })?;
+    UserData::get(state).add_abort_class(class, abort);
+
+    protect(state, || {
+        let msg = mrb_str_new(state, msg.as_ptr() as *const _, msg.len());
+        let exception = mrb_exc_new_str(state, class, msg);
+        mrb_gc_register(state, exception);
+        exception
+    })
+}
+
+/// Installs the hook if an execution limit or interrupt handle needs it, or removes it otherwise.
+///
+/// The hook is called before every instruction, so it is left out whenever possible.
+pub(crate) unsafe fn update(state: *mut mrb_state) {
+    let user_data = UserData::get(state);
+    let limited = match user_data.execution_limit() {
+        Some(limit) => limit.is_set(),
+        None => false,
+    };
+    let interruptible = user_data.interrupt().is_some();
+
+    (*state).code_fetch_hook = if limited || interruptible {
+        Some(code_fetch_hook)
+    } else {
+        None
+    };
+}
+
+/// Implementation of `mrb_state.code_fetch_hook`, which is called before executing each VM
+/// instruction.
+///
+/// Once interrupted or past the execution limit, this raises the corresponding exception before
+/// every instruction. Ruby code can rescue it, but cannot run any further code by doing so.
+unsafe extern "C" fn code_fetch_hook(
+    state: *mut mrb_state,
+    _irep: *mut mrb_irep,
+    _pc: *mut mrb_code,
+    _regs: *mut mrb_value,
+) {
+    use mruby_sys::mrb_exc_raise;
+
+    let user_data = UserData::get(state);
+    if let Some(interrupt) = user_data.interrupt() {
+        if interrupt.is_requested() {
+            mrb_exc_raise(state, interrupt.exception());
+        }
+    }
+
+    if let Some(limit) = user_data.execution_limit() {
+        if limit.tick() {
+            mrb_exc_raise(state, limit.exception());
+        }
+    }
+}
+
+/// Returns the exception the hook raises before the next instruction, if running code is being
+/// aborted.
+pub(crate) unsafe fn pending_abort(state: *mut mrb_state) -> Option<mrb_value> {
+    let user_data = UserData::get(state);
+    if let Some(interrupt) = user_data.interrupt() {
+        if interrupt.is_requested() {
+            return Some(interrupt.exception());
+        }
+    }
+
+    match user_data.execution_limit() {
+        Some(limit) if limit.is_exceeded() => Some(limit.exception()),
+        _ => None,
+    }
+}
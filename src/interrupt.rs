@@ -0,0 +1,71 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use mruby_sys::{mrb_state, mrb_value};
+
+use crate::error::{Abort, ExecutionError, RubyException, INTERRUPTED_CLASS};
+use crate::hook::new_abort_exception;
+
+/// A handle for interrupting the Ruby code run by an interpreter from another thread.
+///
+/// Created with `Mruby::interrupt_handle()`. Cloning the handle is cheap, and all clones refer to
+/// the same interpreter. The handle may outlive the interpreter, in which case interrupting it has
+/// no effect.
+#[derive(Clone, Debug)]
+pub struct InterruptHandle {
+    requested: Arc<AtomicBool>,
+}
+
+impl InterruptHandle {
+    /// Requests that the interpreter abort the code it is running.
+    ///
+    /// The code is aborted before executing its next instruction, and the call into Ruby which
+    /// ran it returns `EvalError::Execution(ExecutionError::Interrupted)`. If no code is running,
+    /// the next call into Ruby is aborted instead.
+    pub fn interrupt(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Interrupt state of an interpreter, shared with its `InterruptHandle`s.
+#[derive(Debug)]
+pub(crate) struct Interrupt {
+    requested: Arc<AtomicBool>,
+    exception: mrb_value,
+}
+
+impl Interrupt {
+    pub unsafe fn new(state: *mut mrb_state) -> Result<Self, RubyException> {
+        Ok(Interrupt {
+            requested: Arc::new(AtomicBool::new(false)),
+            exception: new_abort_exception(
+                state,
+                INTERRUPTED_CLASS,
+                "execution interrupted",
+                Abort::Execution(ExecutionError::Interrupted),
+            )?,
+        })
+    }
+
+    /// Returns a new handle for requesting an interrupt.
+    pub fn handle(&self) -> InterruptHandle {
+        InterruptHandle {
+            requested: self.requested.clone(),
+        }
+    }
+
+    /// Returns whether an interrupt was requested and not yet reset.
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    /// Clears the request once it has aborted the running code.
+    pub fn reset(&self) {
+        self.requested.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns the exception raised when interrupted.
+    pub fn exception(&self) -> mrb_value {
+        self.exception
+    }
+}
@@ -1,76 +1,822 @@
+//! Safe, high-level bindings to [mruby](https://mruby.org/), an embeddable Ruby interpreter.
+//!
+//! Ruby exceptions raised while Rust code is on the stack, e.g. by a Rust function called from
+//! Ruby or by a conversion which runs out of memory, are carried past the Rust frames by
+//! unwinding, so their values are dropped before mruby sees the exception. This requires the
+//! `unwind` panic strategy, and the crate fails to compile with `panic = "abort"`, which would
+//! abort the process instead.
+
+pub use crate::builder::MrubyBuilder;
+pub use crate::bytecode::Bytecode;
+pub use crate::error::{BytecodeError, CompileError, EvalError, ExecutionError, RubyException};
+pub use crate::gc::{GcPhase, GcProgress, GcStats};
+pub use crate::interrupt::InterruptHandle;
+pub use crate::limit::Limit;
+pub use crate::object::Object;
+pub use crate::output::Capture;
+pub use crate::root::Root;
+pub use crate::sandbox::Capabilities;
 pub use crate::value::Value;
-pub use mruby_macros::Symbol;
+pub use mruby_macros::{include_ruby, methods, mruby_class, Symbol};
+
+#[doc(hidden)]
+pub use crate::bytecode::config_matches as __bytecode_config_matches;
 
+use std::any::TypeId;
 use std::ffi::CString;
+use std::fmt::Display;
+use std::io::Write;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::time::Duration;
 
 use mruby_sys::{self, mrb_state};
 
-use crate::de::{CastError, Deserializer, FromValue};
-use crate::ser::{Serializer, ToValue};
+use crate::arena::{ArenaIndex, ArenaScope};
+use crate::class::{Class, ClassHandle};
+use crate::de::{FromArgs, FromValue};
+use crate::function::Function;
+use crate::function::{call, cast_error, catch_raised, convert_result, protect};
+use crate::function::{raising, Call};
+use crate::module::{Module, ModuleBuilder};
+use crate::ser::{Serializer, ToArgs, ToValue};
+use crate::userdata::UserData;
+
+// NOTE: Code generated by `mruby-macros` refers to this crate as `::mruby`, which also needs to
+// resolve within the crate itself.
+extern crate self as mruby;
+
+// NOTE: See `function::raise()`. With `panic = "abort"`, every exception raised from Rust code
+// would terminate the host instead of being returned from `eval()` or `call()`.
+#[cfg(panic = "abort")]
+compile_error!("mruby requires the `unwind` panic strategy, but `panic = \"abort\"` is set");
 
 #[macro_use]
 mod macros;
 
+pub mod class;
 pub mod de;
+pub mod module;
 pub mod ser;
 pub mod symbol;
 
-mod class;
-mod module;
+mod arena;
+mod builder;
+mod bytecode;
+mod data;
+mod error;
+mod exit;
+mod function;
+mod gc;
+mod hook;
+mod interrupt;
+mod limit;
+mod memory;
 mod object;
+mod output;
+mod root;
+mod sandbox;
+mod userdata;
 mod value;
 
 #[derive(Debug)]
 pub enum Error {
     Init,
+    /// Starting the interpreter took more memory than `MrubyBuilder::memory_limit()` allows.
+    MemoryLimit,
 }
 
+/// A mruby interpreter, which is closed when it is dropped.
+///
+/// The values obtained from an interpreter borrow it, so they cannot outlive it. Convert them into
+/// Rust types or `Root`s to keep them around.
+///
+/// Every method which runs Ruby code, allocates objects or runs the garbage collector takes
+/// `&mut self`, since the collector may free objects which values still borrowing the
+/// interpreter refer to. The methods taking `&self` only read or store settings, such as
+/// `gc_stats()` or `set_gc_enabled()`, and never enter the VM.
+///
+/// # Example
+///
+/// ```rust
+/// # use mruby::Mruby;
+/// let mut ruby = Mruby::new().unwrap();
+/// let sum: i64 = ruby.eval("[1, 2, 3].inject(:+)").unwrap();
+/// assert_eq!(sum, 6);
+/// ```
 #[derive(Debug, Eq, PartialEq)]
 pub struct Mruby {
     state: *mut mrb_state,
 }
 
 impl Mruby {
+    /// Creates an interpreter with the default configuration.
     pub fn new() -> Result<Self, Error> {
-        let state = unsafe { mruby_sys::mrb_open() };
-        if !state.is_null() {
-            Ok(Mruby { state })
-        } else {
-            Err(Error::Init)
+        Mruby::builder().build()
+    }
+
+    /// Returns a builder for configuring a new interpreter, e.g. with a memory limit.
+    pub fn builder() -> MrubyBuilder {
+        MrubyBuilder::default()
+    }
+
+    /// Creates an interpreter which only allows Ruby code to use the given capabilities.
+    ///
+    /// Methods giving access to anything else, such as `File`, `Socket`, `eval` or `exit`, are
+    /// replaced with methods raising `SecurityError`, which inherits from `Exception`. Rust
+    /// functions defined on the interpreter are not restricted, so they can expose exactly what
+    /// untrusted scripts are allowed to do.
+    ///
+    /// For gems which can be left out entirely, disabling their `gem-*` features is the more
+    /// thorough option, since it removes their code from the binary.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use mruby::{Capabilities, Mruby};
+    /// let mut ruby = Mruby::sandboxed(Capabilities::none()).unwrap();
+    /// let result = ruby.eval::<String>("File.read('/etc/passwd')");
+    /// assert!(result.is_err());
+    /// ```
+    pub fn sandboxed(capabilities: Capabilities) -> Result<Self, Error> {
+        Mruby::builder().capabilities(capabilities).build()
+    }
+
+    /// Returns the capabilities allowed to Ruby code, which are all of them unless the
+    /// interpreter was created with `sandboxed()`.
+    pub fn capabilities(&self) -> Capabilities {
+        match unsafe { UserData::get(self.state).sandbox() } {
+            Some(sandbox) => sandbox.capabilities(),
+            None => Capabilities::all(),
+        }
+    }
+
+    /// Returns the number of bytes currently allocated by the interpreter.
+    pub fn memory_usage(&self) -> usize {
+        unsafe { UserData::get(self.state).allocator().current() }
+    }
+
+    /// Returns the largest number of bytes allocated by the interpreter at any one time.
+    pub fn peak_memory_usage(&self) -> usize {
+        unsafe { UserData::get(self.state).allocator().peak() }
+    }
+
+    /// Returns the memory limit set with `MrubyBuilder::memory_limit()`, if any.
+    pub fn memory_limit(&self) -> Option<usize> {
+        unsafe { UserData::get(self.state).allocator().limit() }
+    }
+
+    /// Runs a complete garbage collection cycle, finishing any cycle in progress first.
+    ///
+    /// In generational mode, this also collects old objects, which minor cycles skip. Like the
+    /// other collection methods, this does nothing while the collector is disabled.
+    pub fn full_gc(&mut self) {
+        unsafe { gc::collect(self.state, mruby_sys::mrb_full_gc) };
+    }
+
+    /// Runs a single step of the incremental garbage collector, the same amount of work done
+    /// automatically whenever enough objects were allocated.
+    ///
+    /// In generational mode, a minor cycle is run to completion instead.
+    pub fn incremental_gc(&mut self) {
+        unsafe { gc::collect(self.state, mruby_sys::mrb_incremental_gc) };
+    }
+
+    /// Runs a complete garbage collection cycle. This is the same as `full_gc()`.
+    pub fn garbage_collect(&mut self) {
+        unsafe { gc::collect(self.state, mruby_sys::mrb_garbage_collect) };
+    }
+
+    /// Returns whether the garbage collector is enabled.
+    pub fn gc_enabled(&self) -> bool {
+        unsafe { (*self.state).gc.disabled() == 0 }
+    }
+
+    /// Enables or disables the garbage collector, both for automatic and explicit collection.
+    ///
+    /// While disabled, the heap grows with every object allocated. This is what `GC.enable` and
+    /// `GC.disable` do in Ruby.
+    pub fn set_gc_enabled(&self, enabled: bool) {
+        unsafe {
+            (*self.state)
+                .gc
+                .set_disabled(!enabled as mruby_sys::mrb_bool)
+        };
+    }
+
+    /// Returns whether the garbage collector runs in generational mode, which is the default
+    /// unless the `disable-generational-gc` feature is enabled.
+    pub fn gc_generational_mode(&self) -> bool {
+        unsafe { (*self.state).gc.generational() != 0 }
+    }
+
+    /// Switches the garbage collector into or out of generational mode.
+    ///
+    /// Generational mode collects young objects in quick minor cycles and only occasionally
+    /// collects the whole heap, while the normal mode always collects the whole heap in small
+    /// incremental steps. Switching finishes the current cycle first, and fails while the
+    /// collector is disabled.
+    pub fn set_gc_generational_mode(&mut self, enabled: bool) -> Result<(), EvalError> {
+        unsafe {
+            let call = Call::enter(self.state);
+            let result = gc::set_generational_mode(self.state, enabled);
+            call.finish(result).map_err(EvalError::from)
+        }
+    }
+
+    /// Returns the interval ratio of the garbage collector, in percent.
+    pub fn gc_interval_ratio(&self) -> u32 {
+        unsafe { (*self.state).gc.interval_ratio as u32 }
+    }
+
+    /// Sets how many objects may be allocated after a cycle before the next one starts, as a
+    /// percentage of the objects which survived it. The default is 200.
+    ///
+    /// The new ratio takes effect once the current cycle is finished.
+    pub fn set_gc_interval_ratio(&self, ratio: u32) {
+        unsafe { (*self.state).gc.interval_ratio = ratio.min(c_int::MAX as u32) as c_int };
+    }
+
+    /// Returns the step ratio of the incremental garbage collector, in percent.
+    pub fn gc_step_ratio(&self) -> u32 {
+        unsafe { (*self.state).gc.step_ratio as u32 }
+    }
+
+    /// Sets how much work each incremental step does, as a percentage of the default step size.
+    /// The default is 200. Larger ratios mean fewer but longer pauses.
+    pub fn set_gc_step_ratio(&self, ratio: u32) {
+        unsafe { (*self.state).gc.step_ratio = ratio.min(c_int::MAX as u32) as c_int };
+    }
+
+    /// Returns a snapshot of the state of the garbage collector.
+    pub fn gc_stats(&self) -> GcStats {
+        unsafe { GcStats::new(&(*self.state).gc) }
+    }
+
+    /// Runs incremental garbage collection steps until the current cycle is complete or `budget`
+    /// is used up, whichever comes first.
+    ///
+    /// At least one step is run, and the last one may overrun the budget. Pauses are only bounded
+    /// in normal mode, i.e. with the `disable-generational-gc` feature or after
+    /// `set_gc_generational_mode(false)`, since generational mode completes a minor cycle in a
+    /// single step. Nothing is done while the collector is disabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use mruby::Mruby;
+    /// # use std::time::{Duration, Instant};
+    /// # fn main() -> Result<(), mruby::RubyException> {
+    /// # let mut ruby = Mruby::new().unwrap();
+    /// # ruby.eval::<()>("def update; Array.new(100) { |i| i.to_s }; nil; end; nil").unwrap();
+    /// ruby.set_gc_deferred(true);
+    /// for _ in 0..3 {
+    ///     let frame_end = Instant::now() + Duration::from_millis(16);
+    ///     ruby.arena_scope(|ruby| ruby.call_global_function::<(), _>("update", ()))?;
+    ///     ruby.gc_step_for(frame_end.saturating_duration_since(Instant::now()));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn gc_step_for(&mut self, budget: Duration) -> GcProgress {
+        unsafe { gc::step_for(self.state, budget) }
+    }
+
+    /// Returns whether automatic garbage collection is deferred.
+    pub fn gc_deferred(&self) -> bool {
+        unsafe { UserData::get(self.state).gc_deferred() }
+    }
+
+    /// Defers or resumes automatic garbage collection, which otherwise runs a step whenever
+    /// Ruby code has allocated enough objects.
+    ///
+    /// While deferred, collection only happens when the host calls `gc_step_for()` or one of the
+    /// other collection methods, so the host decides when pauses happen. Unlike disabling the
+    /// collector, this still allows those calls, as well as the full collection mruby runs when
+    /// an allocation fails. Ruby code calling `GC.start` resumes automatic collection until it
+    /// returns to Rust.
+    pub fn set_gc_deferred(&self, deferred: bool) {
+        unsafe { gc::set_deferred(self.state, deferred) };
+    }
+
+    /// Limits how long Ruby code run by this interpreter may execute from now on.
+    ///
+    /// Instructions are counted across all calls into Ruby until a new limit is set, and a
+    /// deadline applies to all of them as well. Once the limit is exceeded, the code being run is
+    /// aborted and `EvalError::Execution(ExecutionError::LimitExceeded)` is returned, or the
+    /// exception which converts into it from `call()`. Ruby code may rescue the exception used to
+    /// abort it, but is aborted again before it can execute any other instruction. The
+    /// interpreter stays usable, although every call into Ruby is aborted right away until the
+    /// limit is replaced or cleared.
+    ///
+    /// The limit is checked by the hook mruby calls before each instruction, so even a loop such
+    /// as `loop {}` is aborted.
+    ///
+    /// Setting a limit for the first time creates the exception used to abort running code, which
+    /// only fails if the memory limit is reached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use mruby::{EvalError, ExecutionError, Limit, Mruby};
+    /// # let mut ruby = Mruby::new().unwrap();
+    /// # ruby.eval::<()>("def on_tick; loop {}; end; nil").unwrap();
+    /// ruby.set_execution_limit(Limit::Instructions(100_000)).unwrap();
+    /// let result = ruby.call_global_function::<(), _>("on_tick", ());
+    /// assert_eq!(
+    ///     result.map_err(EvalError::from),
+    ///     Err(EvalError::Execution(ExecutionError::LimitExceeded))
+    /// );
+    /// ```
+    pub fn set_execution_limit(&mut self, limit: Limit) -> Result<(), EvalError> {
+        use crate::limit::ExecutionLimit;
+
+        unsafe {
+            let execution_limit = match UserData::get(self.state).execution_limit() {
+                Some(execution_limit) => execution_limit,
+                None => {
+                    let execution_limit = ExecutionLimit::new(self.state)?;
+                    UserData::get(self.state).set_execution_limit(execution_limit)
+                }
+            };
+
+            execution_limit.set(Some(limit));
+            hook::update(self.state);
+        }
+
+        Ok(())
+    }
+
+    /// Removes the limit set with `set_execution_limit()`, if any.
+    pub fn clear_execution_limit(&mut self) {
+        unsafe {
+            if let Some(execution_limit) = UserData::get(self.state).execution_limit() {
+                execution_limit.set(None);
+                hook::update(self.state);
+            }
+        }
+    }
+
+    /// Returns a handle for interrupting the Ruby code run by this interpreter from another
+    /// thread.
+    ///
+    /// Once interrupted, the running code is aborted before its next instruction and the call
+    /// into Ruby which ran it returns `EvalError::Execution(ExecutionError::Interrupted)`, or the
+    /// exception which converts into it from `call()`. As with `set_execution_limit()`, Ruby code
+    /// cannot keep running by rescuing the exception used to abort it. The interpreter stays
+    /// usable afterwards.
+    ///
+    /// mruby is always compiled with the hook called before each instruction, which is where the
+    /// request is checked, so even a loop such as `loop {}` can be interrupted.
+    ///
+    /// Creating the first handle also creates the exception used to abort running code, which
+    /// only fails if the memory limit is reached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use mruby::{EvalError, ExecutionError, Mruby};
+    /// # use std::thread;
+    /// # use std::time::Duration;
+    /// # let mut ruby = Mruby::new().unwrap();
+    /// let handle = ruby.interrupt_handle().unwrap();
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_secs(1));
+    ///     handle.interrupt();
+    /// });
+    ///
+    /// let result = ruby.eval::<()>("loop {}");
+    /// assert_eq!(result, Err(EvalError::Execution(ExecutionError::Interrupted)));
+    /// ```
+    pub fn interrupt_handle(&mut self) -> Result<InterruptHandle, EvalError> {
+        use crate::interrupt::Interrupt;
+
+        unsafe {
+            let interrupt = match UserData::get(self.state).interrupt() {
+                Some(interrupt) => interrupt,
+                None => {
+                    let interrupt = Interrupt::new(self.state)?;
+                    let interrupt = UserData::get(self.state).set_interrupt(interrupt);
+                    hook::update(self.state);
+                    interrupt
+                }
+            };
+
+            Ok(interrupt.handle())
+        }
+    }
+
+    /// Sends everything Ruby code prints to the standard output to `sink` instead, e.g. with
+    /// `puts`, `print`, `p` or `$stdout.write`.
+    ///
+    /// Use a `Capture` to read the output back afterwards. Other `IO` objects, such as files or
+    /// `IO.new(1)`, still write to their file descriptors.
+    ///
+    /// Redirecting the stream for the first time replaces the methods writing to it, which only
+    /// fails if the memory limit is reached.
+    pub fn set_stdout(&mut self, sink: Box<dyn Write + Send>) -> Result<(), EvalError> {
+        let result = unsafe { output::redirect(self.state, output::Stream::Stdout, sink) };
+        result.map_err(EvalError::from)
+    }
+
+    /// Sends everything Ruby code writes to the standard error to `sink` instead, e.g. with
+    /// `$stderr.puts`.
+    ///
+    /// Messages printed by mruby's C code itself, such as with `mrb_print_error()`, still go to
+    /// the process' standard error. No Ruby code run through this crate prints those. This fails
+    /// like `set_stdout()`.
+    pub fn set_stderr(&mut self, sink: Box<dyn Write + Send>) -> Result<(), EvalError> {
+        let result = unsafe { output::redirect(self.state, output::Stream::Stderr, sink) };
+        result.map_err(EvalError::from)
+    }
+
+    /// Runs `f`, then releases every object it created from the GC arena.
+    ///
+    /// Objects created from Rust, including the results of `eval()`, `load_bytecode()`, `call()`
+    /// and `get_global()`, are kept in the arena, which protects them from the garbage collector
+    /// until it is restored. Only Rust functions called from Ruby restore it when they return, so
+    /// the arena of a host driving the interpreter in a loop grows without bound. Wrapping each
+    /// iteration, e.g. each frame of a game, in a scope releases those objects again.
+    ///
+    /// Values cannot leave the scope; convert them into Rust types or `Root`s instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use mruby::Mruby;
+    /// # fn main() -> Result<(), mruby::RubyException> {
+    /// # let mut ruby = Mruby::new().unwrap();
+    /// # ruby.eval::<()>("def tick; ['moved', 'fired']; end; nil").unwrap();
+    /// # let handle = |events: Vec<String>| assert_eq!(events, ["moved", "fired"]);
+    /// for _ in 0..3 {
+    ///     let events: Vec<String> = ruby.arena_scope(|ruby| ruby.call_global_function("tick", ()))?;
+    ///     handle(events);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn arena_scope<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Mruby) -> R,
+    {
+        unsafe {
+            let scope = ArenaScope::enter(self.state);
+            let result = f(self);
+            drop(scope);
+            result
         }
     }
 
-    pub fn register_global<V: ToValue>(&mut self, name: &str, global: V) {
+    /// Converts `global` into a Ruby value and assigns it to the global variable `name`, which
+    /// includes the leading `$`.
+    ///
+    /// Only fails if the memory limit is reached. The objects created for the value are released
+    /// from the GC arena again, since the variable refers to them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains a null byte.
+    pub fn register_global<V: ToValue>(&mut self, name: &str, global: V) -> Result<(), EvalError> {
         use mruby_sys::{mrb_gv_set, mrb_intern_cstr};
 
-        let ser = Serializer::new(self.state);
-        let value = global.to_value(ser).into_inner();
         let owned = CString::new(name).expect("String contains null byte");
 
         unsafe {
-            let sym = mrb_intern_cstr(self.state, owned.as_ptr());
-            mrb_gv_set(self.state, sym, value);
+            let arena = ArenaIndex::save(self.state);
+            let result = catch_raised(self.state, || {
+                let value = global.to_value(Serializer::new(self.state)).into_inner();
+                raising(self.state, || {
+                    let sym = mrb_intern_cstr(self.state, owned.as_ptr());
+                    mrb_gv_set(self.state, sym, value);
+                })
+            });
+            arena.restore();
+            result.map_err(EvalError::from)
         }
     }
 
-    pub fn get_global<V: FromValue>(&mut self, name: &str) -> Result<V, CastError> {
-        use mruby_sys::{mrb_gv_get, mrb_intern_cstr};
+    /// Converts the value of the global variable `name`, which includes the leading `$`, into
+    /// `V`.
+    ///
+    /// Variables which are not set are `nil`. Fails if the value cannot be converted into `V`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains a null byte.
+    pub fn get_global<'mrb, V>(&'mrb mut self, name: &str) -> Result<V, EvalError>
+    where
+        V: FromValue<'mrb>,
+    {
+        use mruby_sys::{mrb_gc_protect, mrb_gv_get, mrb_intern_cstr};
 
         let owned = CString::new(name).expect("String contains null byte");
-        let value = unsafe {
-            let sym = mrb_intern_cstr(self.state, owned.as_ptr());
-            Value(mrb_gv_get(self.state, sym))
+        unsafe {
+            let value = protect(self.state, || {
+                let sym = mrb_intern_cstr(self.state, owned.as_ptr());
+                let value = mrb_gv_get(self.state, sym);
+                // NOTE: Ruby code may assign the variable again while the value is still in use.
+                mrb_gc_protect(self.state, value);
+                value
+            })?;
+
+            convert_result(self.state, value)
+        }
+    }
+
+    /// Runs the Ruby source code `code` and converts the value of its last expression into `T`.
+    ///
+    /// Fails with the exception the code raised, as `EvalError::Exception`, with the reason it was
+    /// aborted, e.g. `EvalError::Exit` if it called `exit`, or with `EvalError::Cast` if the value
+    /// cannot be converted into `T`.
+    ///
+    /// Exceptions raised through Rust functions called by the code unwind their Rust frames, which
+    /// is why the crate does not support `panic = "abort"`.
+    pub fn eval<'mrb, T: FromValue<'mrb>>(&'mrb mut self, code: &str) -> Result<T, EvalError> {
+        self.eval_with_filename(code, "(eval)")
+    }
+
+    /// Same as `eval()`, but reports `filename` as the file the code was loaded from, e.g. in the
+    /// backtraces of exceptions it raises.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `filename` contains a null byte.
+    pub fn eval_with_filename<'mrb, T>(
+        &'mrb mut self,
+        code: &str,
+        filename: &str,
+    ) -> Result<T, EvalError>
+    where
+        T: FromValue<'mrb>,
+    {
+        use mruby_sys::{mrb_gc_protect, mrb_load_nstring_cxt};
+        use mruby_sys::{mrbc_context_free, mrbc_context_new, mrbc_filename};
+
+        let filename = CString::new(filename).expect("String contains null byte");
+        let mut context = ptr::null_mut();
+        let call = unsafe { Call::enter(self.state) };
+        let result = unsafe {
+            root::release_dropped(self.state);
+            gc::defer_automatic(self.state);
+            let result = protect(self.state, || {
+                context = mrbc_context_new(self.state);
+                mrbc_filename(self.state, context, filename.as_ptr());
+
+                let ptr = code.as_ptr() as *const c_char;
+                let value = mrb_load_nstring_cxt(self.state, ptr, code.len(), context);
+                // NOTE: Unlike `mrb_funcall()`, mruby leaves the result of loading code
+                // unprotected.
+                mrb_gc_protect(self.state, value);
+                value
+            });
+
+            if !context.is_null() {
+                mrbc_context_free(self.state, context);
+            }
+            result
+        };
+
+        let value = match result {
+            Ok(value) => value,
+            Err(exc) => return call.finish(Err(exc)).map_err(EvalError::from),
+        };
+
+        unsafe { convert_result(self.state, value) }
+    }
+
+    /// Compiles the given Ruby source code into bytecode without running it.
+    ///
+    /// With `debug_info`, the result is byte-for-byte identical to the output of `mrbc -g`, so
+    /// `filename` and line numbers show up in the backtraces of exceptions raised by the code once
+    /// it is loaded with `load_bytecode()`. Without it, the bytecode is smaller, but backtraces
+    /// are empty.
+    pub fn compile(
+        &mut self,
+        source: &str,
+        filename: &str,
+        debug_info: bool,
+    ) -> Result<Bytecode, CompileError> {
+        unsafe { bytecode::compile(self.state, source, filename, debug_info) }
+    }
+
+    /// Loads and runs precompiled mruby bytecode, such as a `.mrb` file written by `mrbc`.
+    ///
+    /// The RITE header and checksum are validated before anything is executed. mruby copies the
+    /// loaded code into memory it owns, so `bytecode` can be dropped right afterwards.
+    pub fn load_bytecode<'mrb, T>(&'mrb mut self, bytecode: &[u8]) -> Result<T, EvalError>
+    where
+        T: FromValue<'mrb>,
+    {
+        bytecode::validate(bytecode)?;
+        let call = unsafe { Call::enter(self.state) };
+        let result = unsafe {
+            root::release_dropped(self.state);
+            gc::defer_automatic(self.state);
+            bytecode::load(self.state, bytecode)
         };
 
-        let de = Deserializer::new(self.state, value);
-        V::from_value(de)
+        let value = match result {
+            Ok(value) => value,
+            Err(exc) => return call.finish(Err(exc)).map_err(EvalError::from),
+        };
+
+        unsafe { convert_result(self.state, value) }
+    }
+
+    /// Calls the method `method` on `receiver` with the given arguments.
+    ///
+    /// The call is made with a handler installed for Ruby exceptions, as with `mrb_protect()`, so
+    /// this also works from within Rust functions called by Ruby. An exception raised by the
+    /// method is returned as is. A result which cannot be converted into `R` is reported like a
+    /// Rust function called from Ruby reports its arguments, as a `TypeError`, or an
+    /// `ArgumentError` for a sequence of the wrong length.
+    ///
+    /// Aborting the script, e.g. with `exit`, is reported as the exception which aborted it.
+    /// Converting it with `EvalError::from()` tells it apart from other exceptions, as
+    /// `EvalError::Exit` or `EvalError::Execution`.
+    ///
+    /// The receiver is converted like the arguments, so it can be a `Root` held on to from an
+    /// earlier call, or any Rust value.
+    ///
+    /// As with `eval()`, exceptions raised through Rust code unwind its frames, which requires
+    /// the `unwind` panic strategy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `receiver` is a value or `Root` belonging to a different interpreter.
+    pub fn call<'mrb, R, A>(
+        &'mrb mut self,
+        receiver: &(impl ToValue + ?Sized),
+        method: &str,
+        args: A,
+    ) -> Result<R, RubyException>
+    where
+        R: FromValue<'mrb>,
+        A: ToArgs,
+    {
+        unsafe {
+            let receiver = catch_raised(self.state, || {
+                receiver.to_value(Serializer::new(self.state)).into_inner()
+            })?;
+            call(self.state, receiver, method, args)?.map_err(|err| cast_error(self.state, err))
+        }
+    }
+
+    /// Calls the top-level method `method`, e.g. a function defined with `def` in a script, with
+    /// the given arguments.
+    ///
+    /// This is the same as calling the method on the top-level `self` with `call()`, and fails
+    /// the same way.
+    pub fn call_global_function<'mrb, R, A>(
+        &'mrb mut self,
+        method: &str,
+        args: A,
+    ) -> Result<R, RubyException>
+    where
+        R: FromValue<'mrb>,
+        A: ToArgs,
+    {
+        use mruby_sys::mrb_top_self;
+
+        unsafe {
+            let top_self = mrb_top_self(self.state);
+            call(self.state, top_self, method, args)?.map_err(|err| cast_error(self.state, err))
+        }
+    }
+
+    /// Defines a global Ruby function backed by the given Rust closure.
+    ///
+    /// The Ruby arguments are converted into `A` before calling the closure, raising an
+    /// `ArgumentError` or `TypeError` if they don't match. If the closure returns `Err`, a
+    /// `RuntimeError` containing the error message is raised in Ruby.
+    ///
+    /// The closure is owned by the interpreter and dropped along with it. Defining the function
+    /// only fails if the memory limit is reached.
+    pub fn define_global_fn<'mrb, F, A, R, E>(
+        &mut self,
+        name: &str,
+        func: F,
+    ) -> Result<(), EvalError>
+    where
+        F: Fn(A) -> Result<R, E> + Send + 'static,
+        A: FromArgs<'mrb>,
+        R: ToValue,
+        E: Display,
+    {
+        let func = Function::new(move |_: Value, args: A| func(args));
+        unsafe {
+            let func = UserData::get(self.state).add_function(func);
+            let kernel = (*self.state).kernel_module;
+            protect(self.state, || func.define(self.state, kernel, name))?;
+        }
+
+        Ok(())
+    }
+
+    /// Defines a method backed by the given Rust closure on `object` alone, through its
+    /// singleton class.
+    ///
+    /// The closure receives `object` converted into `S` along with the converted arguments, as
+    /// with `define_global_fn()`. Fails with a `TypeError` if `object` cannot have singleton
+    /// methods, e.g. if it is an integer or a symbol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `object` is a value or `Root` belonging to a different interpreter.
+    pub fn define_singleton_method<'mrb, F, S, A, R, E>(
+        &mut self,
+        object: &(impl ToValue + ?Sized),
+        name: &str,
+        func: F,
+    ) -> Result<(), EvalError>
+    where
+        F: Fn(S, A) -> Result<R, E> + Send + 'static,
+        S: FromValue<'mrb>,
+        A: FromArgs<'mrb>,
+        R: ToValue,
+        E: Display,
+    {
+        unsafe {
+            let object = catch_raised(self.state, || {
+                object.to_value(Serializer::new(self.state)).into_inner()
+            })?;
+            let singleton = protect(self.state, || {
+                mruby_sys::mrb_singleton_class(self.state, object)
+            })?;
+            let func = UserData::get(self.state).add_function(Function::new(func));
+            protect(self.state, || {
+                func.define(self.state, singleton.value.p as *mut _, name)
+            })?;
+            Ok(())
+        }
+    }
+
+    /// Defines the Ruby class described by `T`, or reopens it if it already exists.
+    ///
+    /// The returned handle can be passed to `new_instance()`, and can be looked up again later
+    /// with `class_of::<T>()`.
+    ///
+    /// Fails if `T::PARENT` does not name an existing class, if `T::NAME` is already bound to
+    /// something other than a class with that parent, or if any definition made by `T::define()`
+    /// fails. In the latter case, the definitions made before the failing one are kept.
+    pub fn define_class<T: Class + 'static>(&mut self) -> Result<ClassHandle, EvalError> {
+        unsafe { class::define::<T>(self.state).map_err(EvalError::from) }
+    }
+
+    /// Defines the Ruby module described by `T`, or reopens it if it already exists.
+    ///
+    /// Fails if `T::OUTER` does not name an existing module, if `T::NAME` is already bound to
+    /// something other than a module, or if any definition made by `T::define()` fails. In the
+    /// latter case, the definitions made before the failing one are kept.
+    pub fn define_module<T: Module>(&mut self) -> Result<(), EvalError> {
+        unsafe {
+            let mut builder = ModuleBuilder::new(self.state, T::NAME, T::OUTER)?;
+            T::define(&mut builder);
+            match builder.take_error() {
+                Some(exc) => Err(EvalError::from(exc)),
+                None => Ok(()),
+            }
+        }
+    }
+
+    /// Returns the class previously defined for `T` with `define_class()`, if any.
+    pub fn class_of<T: Class + 'static>(&self) -> Option<ClassHandle> {
+        unsafe {
+            let class = UserData::get(self.state).class(TypeId::of::<T>())?;
+            Some(ClassHandle::new(self.state, class, T::NAME))
+        }
+    }
+
+    /// Creates a new instance of `class` by calling `new` on it with the given arguments, and
+    /// converts it into `R`, e.g. a `Root` to call methods on it later.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `class` was defined on a different interpreter.
+    pub fn new_instance<'mrb, R, A>(
+        &'mrb mut self,
+        class: &ClassHandle,
+        args: A,
+    ) -> Result<R, EvalError>
+    where
+        R: FromValue<'mrb>,
+        A: ToArgs,
+    {
+        use mruby_sys::mrb_ext_class_value;
+
+        unsafe {
+            let receiver = mrb_ext_class_value(class.class_in(self.state));
+            call(self.state, receiver, "new", args)?.map_err(EvalError::from)
+        }
     }
 }
 
 impl Drop for Mruby {
     fn drop(&mut self) {
         unsafe {
+            let user_data = (*self.state).ud as *mut UserData;
             mruby_sys::mrb_close(self.state);
+            drop(Box::from_raw(user_data));
         }
     }
 }
@@ -79,11 +825,14 @@ unsafe impl Send for Mruby {}
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "gem-print")]
     use std::collections::BTreeMap;
-    use std::ffi::CString;
+    use std::sync::Arc;
 
     use super::*;
+    use crate::de::Deserializer;
 
+    #[cfg(feature = "gem-print")]
     #[derive(Debug, PartialEq, Symbol)]
     pub enum AllowedSymbols {
         Foo,
@@ -91,6 +840,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "gem-print")]
     fn round_trip() {
         let mut ruby = Mruby::new().unwrap();
 
@@ -101,7 +851,7 @@ mod tests {
 
         let sym = AllowedSymbols::BarBaz;
         let input = (42, Some(sym), [1, 2, 3], 64.5f32, map, true);
-        ruby.register_global("$example", &input);
+        ruby.register_global("$example", &input).unwrap();
         println!("  serialized (rust): {:?}", input);
 
         unsafe {
@@ -113,4 +863,1307 @@ mod tests {
         println!("deserialized (rust): {:?}", output);
         assert_eq!(input, output);
     }
+
+    #[test]
+    fn eval() {
+        let mut ruby = Mruby::new().unwrap();
+        let sum: i64 = ruby.eval("[1, 2, 3].inject(:+)").unwrap();
+        assert_eq!(sum, 6);
+
+        let greeting: String = ruby.eval(r#"name = "world"; "hello #{name}""#).unwrap();
+        assert_eq!(greeting, "hello world");
+
+        let result = ruby.eval::<bool>("42");
+        assert!(matches!(result, Err(EvalError::Cast(_))));
+    }
+
+    #[test]
+    fn eval_exception() {
+        let mut ruby = Mruby::new().unwrap();
+        let result = ruby.eval::<()>(r#"raise ArgumentError, "boom""#);
+        match result {
+            Err(EvalError::Exception(exc)) => {
+                assert_eq!(exc.class_name(), "ArgumentError");
+                assert_eq!(exc.message(), "boom");
+            }
+            other => panic!("expected exception, found {:?}", other),
+        }
+
+        let value: i32 = ruby.eval("1 + 1").expect("exception was not cleared");
+        assert_eq!(value, 2);
+    }
+
+    #[test]
+    fn exception_backtrace() {
+        let mut ruby = Mruby::new().unwrap();
+        let code = "def fail_loudly\n  raise 'oops'\nend\n\nfail_loudly\n";
+        let exc = match ruby.eval_with_filename::<()>(code, "script.rb") {
+            Err(EvalError::Exception(exc)) => exc,
+            other => panic!("expected exception, found {:?}", other),
+        };
+
+        assert_eq!(exc.class_name(), "RuntimeError");
+        assert_eq!(exc.message(), "oops");
+        assert_eq!(exc.backtrace()[0], "script.rb:2:in fail_loudly");
+        assert_eq!(
+            exc.to_string(),
+            "script.rb:2:in fail_loudly: oops (RuntimeError)"
+        );
+    }
+
+    #[test]
+    fn call() {
+        let mut ruby = Mruby::new().unwrap();
+        let text: Root = ruby.eval(r#""hello""#).unwrap();
+        let upper: String = ruby.call(&text, "upcase", ()).unwrap();
+        assert_eq!(upper, "HELLO");
+
+        let replaced: String = ruby.call(&text, "sub", ("l", "L")).unwrap();
+        assert_eq!(replaced, "heLlo");
+
+        let args = vec![1, 2, 3];
+        let array: Root = ruby.eval("[]").unwrap();
+        let pushed: Vec<i32> = ruby.call(&array, "push", &args).unwrap();
+        assert_eq!(pushed, args);
+
+        // Rust values are converted into receivers as well.
+        let sum: i64 = ruby.call(&40, "+", (2,)).unwrap();
+        assert_eq!(sum, 42);
+    }
+
+    #[test]
+    fn call_global_function() {
+        let mut ruby = Mruby::new().unwrap();
+        ruby.eval::<Value>("$ticks = 0.0; def on_tick(dt) $ticks += dt end")
+            .unwrap();
+
+        ruby.call_global_function::<f64, _>("on_tick", (0.5,))
+            .unwrap();
+        let total: f64 = ruby.call_global_function("on_tick", (0.25,)).unwrap();
+        assert_eq!(total, 0.75);
+    }
+
+    #[test]
+    fn call_exception() {
+        let mut ruby = Mruby::new().unwrap();
+        ruby.eval::<Value>("def checked(n) raise ArgumentError, 'negative' if n < 0; n end")
+            .unwrap();
+
+        match ruby.call_global_function::<i32, _>("checked", (-1,)) {
+            Err(exc) => {
+                assert_eq!(exc.class_name(), "ArgumentError");
+                assert_eq!(exc.message(), "negative");
+            }
+            other => panic!("expected exception, found {:?}", other),
+        }
+
+        if cfg!(feature = "gem-kernel-ext") {
+            match ruby.call_global_function::<i32, _>("Integer", ("nope",)) {
+                Err(exc) => assert_eq!(exc.class_name(), "ArgumentError"),
+                other => panic!("expected exception, found {:?}", other),
+            }
+        }
+
+        // Results which cannot be converted are reported like invalid arguments.
+        match ruby.call_global_function::<bool, _>("checked", (7,)) {
+            Err(exc) => assert_eq!(exc.class_name(), "TypeError"),
+            other => panic!("expected exception, found {:?}", other),
+        }
+
+        let missing = ruby.call_global_function::<(), _>("no_such_method", ());
+        assert!(missing.is_err());
+
+        let value: i32 = ruby.call_global_function("checked", (7,)).unwrap();
+        assert_eq!(value, 7);
+    }
+
+    #[test]
+    fn define_global_fn() {
+        let mut ruby = Mruby::new().unwrap();
+        ruby.define_global_fn("repeat", |(times, text): (i64, String)| {
+            if times >= 0 {
+                Ok(text.repeat(times as usize))
+            } else {
+                Err(format!("cannot repeat {} times", times))
+            }
+        })
+        .unwrap();
+
+        let repeated: String = ruby.eval(r#"repeat(3, "ab")"#).unwrap();
+        assert_eq!(repeated, "ababab");
+
+        match ruby.eval::<String>(r#"repeat(-1, "ab")"#) {
+            Err(EvalError::Exception(exc)) => {
+                assert_eq!(exc.class_name(), "RuntimeError");
+                assert_eq!(exc.message(), "cannot repeat -1 times");
+            }
+            other => panic!("expected exception, found {:?}", other),
+        }
+
+        match ruby.eval::<String>(r#"repeat("ab")"#) {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "ArgumentError"),
+            other => panic!("expected exception, found {:?}", other),
+        }
+
+        let rescued: String = ruby
+            .eval(r#"begin; repeat("ab", 3); rescue TypeError; "ok"; end"#)
+            .unwrap();
+        assert_eq!(rescued, "ok");
+    }
+
+    #[test]
+    fn define_global_fn_drops_closure() {
+        let counter = Arc::new(());
+        let mut ruby = Mruby::new().unwrap();
+        let captured = counter.clone();
+        ruby.define_global_fn("count", move |()| {
+            Ok::<_, String>(Arc::strong_count(&captured))
+        })
+        .unwrap();
+        let count: usize = ruby.eval("count").unwrap();
+        assert_eq!(count, 2);
+
+        drop(ruby);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn builder_def_method() {
+        let mut ruby = Mruby::new().unwrap();
+        let mut builder = unsafe { class::Builder::new(ruby.state, "Greeter", None).unwrap() };
+        builder.def_method("greet", |_: Value, (name,): (String,)| {
+            Ok::<_, String>(format!("hello, {}", name))
+        });
+
+        let greeting: String = ruby.eval(r#"Greeter.new.greet("world")"#).unwrap();
+        assert_eq!(greeting, "hello, world");
+    }
+
+    #[mruby_class]
+    pub struct Person {
+        name: String,
+    }
+
+    #[methods]
+    impl Person {
+        #[initialize]
+        fn new(first_name: String, last_name: Option<String>) -> Result<Self, String> {
+            if first_name.is_empty() {
+                return Err("name must not be empty".to_string());
+            }
+
+            let name = match last_name {
+                Some(last_name) => format!("{} {}", first_name, last_name),
+                None => first_name,
+            };
+
+            Ok(Person { name })
+        }
+
+        fn name(&self) -> String {
+            self.name.clone()
+        }
+
+        #[method(rename = "name=")]
+        fn set_name(&mut self, name: String) {
+            self.name = name;
+        }
+
+        fn greet(&self, greeting: String) -> String {
+            format!("{}, {}!", greeting, self.name)
+        }
+
+        fn species() -> &'static str {
+            "human"
+        }
+    }
+
+    #[test]
+    fn mruby_class() {
+        let mut ruby = Mruby::new().unwrap();
+        ruby.define_class::<Person>().unwrap();
+
+        let name: String = ruby.eval(r#"Person.new("Jane", "Doe").name"#).unwrap();
+        assert_eq!(name, "Jane Doe");
+
+        let greeting: String = ruby
+            .eval(r#"p = Person.new("John"); p.name = "Johnny"; p.greet("Hi")"#)
+            .unwrap();
+        assert_eq!(greeting, "Hi, Johnny!");
+
+        let species: String = ruby.eval("Person.species").unwrap();
+        assert_eq!(species, "human");
+    }
+
+    #[test]
+    fn mruby_class_errors() {
+        let mut ruby = Mruby::new().unwrap();
+        ruby.define_class::<Person>().unwrap();
+
+        match ruby.eval::<Value>(r#"Person.new("")"#) {
+            Err(EvalError::Exception(exc)) => {
+                assert_eq!(exc.class_name(), "RuntimeError");
+                assert_eq!(exc.message(), "name must not be empty");
+            }
+            other => panic!("expected RuntimeError, got {:?}", other),
+        }
+
+        match ruby.eval::<Value>("Person.new(42)") {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "TypeError"),
+            other => panic!("expected TypeError, got {:?}", other),
+        }
+
+        let code = r#"
+            class Impostor < Person
+              def initialize; end
+            end
+            Impostor.new.name
+        "#;
+        match ruby.eval::<Value>(code) {
+            Err(EvalError::Exception(exc)) => {
+                assert_eq!(exc.class_name(), "TypeError");
+                assert!(exc.message().contains("expected an instance of Person"));
+            }
+            other => panic!("expected TypeError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn define_class() {
+        let mut ruby = Mruby::new().unwrap();
+        assert!(ruby.class_of::<Person>().is_none());
+
+        let class = ruby.define_class::<Person>().unwrap();
+        assert_eq!(class.name(), "Person");
+        assert_eq!(ruby.class_of::<Person>(), Some(class.clone()));
+
+        let person: Root = ruby.new_instance(&class, ("Jane", "Doe")).unwrap();
+        let greeting: String = ruby.call(&person, "greet", ("Hello",)).unwrap();
+        assert_eq!(greeting, "Hello, Jane Doe!");
+
+        match ruby.new_instance::<Value, _>(&class, ()) {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "ArgumentError"),
+            other => panic!("expected ArgumentError, got {:?}", other),
+        }
+
+        // Defining the class again after its constant was removed creates a new one.
+        ruby.eval::<Value>("Object.__send__(:remove_const, :Person)")
+            .unwrap();
+        let class = ruby.define_class::<Person>().unwrap();
+        let person: Root = ruby.new_instance(&class, ("John", "Doe")).unwrap();
+        let greeting: String = ruby.call(&person, "greet", ("Hi",)).unwrap();
+        assert_eq!(greeting, "Hi, John Doe!");
+    }
+
+    #[test]
+    fn define_class_errors() {
+        #[derive(Debug)]
+        struct Orphan;
+
+        impl Class for Orphan {
+            const NAME: &'static str = "Orphan";
+            const PARENT: Option<&'static str> = Some("Missing");
+
+            fn define(_: &mut class::Builder) {}
+        }
+
+        #[derive(Debug)]
+        struct Gadget;
+
+        impl Class for Gadget {
+            const NAME: &'static str = "Gadget";
+            const PARENT: Option<&'static str> = None;
+
+            fn define(builder: &mut class::Builder) {
+                builder
+                    .def_const("VERSION", 1)
+                    .include_module("Missing")
+                    .def_const("SKIPPED", 2);
+            }
+        }
+
+        let mut ruby = Mruby::new().unwrap();
+        match ruby.define_class::<Orphan>() {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "NameError"),
+            other => panic!("expected NameError, got {:?}", other),
+        }
+
+        ruby.eval::<()>("Person = 1; nil").unwrap();
+        match ruby.define_class::<Person>() {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "TypeError"),
+            other => panic!("expected TypeError, got {:?}", other),
+        }
+        assert!(ruby.class_of::<Person>().is_none());
+
+        match ruby.define_class::<Gadget>() {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "NameError"),
+            other => panic!("expected NameError, got {:?}", other),
+        }
+        let defined: Vec<bool> = ruby
+            .eval("[Gadget.const_defined?(:VERSION), Gadget.const_defined?(:SKIPPED)]")
+            .unwrap();
+        assert_eq!(defined, [true, false]);
+
+        ruby.eval::<()>("Comparable2 = Class.new; nil").unwrap();
+        let mut builder = unsafe { class::Builder::new(ruby.state, "Robot", None).unwrap() };
+        builder.alias_method("hello", "missing");
+        assert_eq!(builder.take_error().unwrap().class_name(), "NameError");
+        builder.include_module("Comparable2");
+        assert_eq!(builder.take_error().unwrap().class_name(), "TypeError");
+        builder.attr_reader("not a valid name");
+        assert_eq!(builder.take_error().unwrap().class_name(), "NameError");
+    }
+
+    #[test]
+    fn builder_methods() {
+        let mut ruby = Mruby::new().unwrap();
+        ruby.eval::<Value>("module Loud; def shout; greet.upcase; end; end")
+            .unwrap();
+        ruby.eval::<Value>("module Polite; def greet; super + ', please'; end; end")
+            .unwrap();
+
+        let mut builder = unsafe { class::Builder::new(ruby.state, "Robot", None).unwrap() };
+        builder
+            .def_var("count", 3)
+            .def_method("greet", |_: Value, (): ()| Ok::<_, String>("hello"))
+            .def_method("secret", |_: Value, (): ()| Ok::<_, String>(42))
+            .alias_method("hello", "greet")
+            .undef_method("secret")
+            .attr_accessor("name")
+            .attr_reader("id")
+            .include_module("Loud")
+            .prepend_module("Polite");
+
+        let result: Vec<String> = ruby
+            .eval(
+                r#"
+                r = Robot.new
+                r.name = "bolt"
+                [r.name, r.hello, r.shout, r.respond_to?(:secret).to_s, r.respond_to?(:id=).to_s]
+                "#,
+            )
+            .unwrap();
+        assert_eq!(result, ["bolt", "hello", "HELLO, PLEASE", "false", "false"]);
+
+        if cfg!(feature = "gem-metaprog") {
+            let count: i64 = ruby.eval("Robot.class_variable_get(:@@count)").unwrap();
+            assert_eq!(count, 3);
+        }
+    }
+
+    #[test]
+    fn builder_singleton_methods() {
+        let mut ruby = Mruby::new().unwrap();
+        let object: Root = ruby.eval("$special = Object.new").unwrap();
+
+        let mut builder = unsafe { class::Builder::new(ruby.state, "Factory", None).unwrap() };
+        builder
+            .def_class_method("build", |_: Value, (n,): (i64,)| {
+                Ok::<_, String>(format!("widget #{}", n))
+            })
+            .def_singleton_method(&object, "magic", |_: Value, (n,): (i64,)| {
+                Ok::<_, String>(n * 2)
+            });
+        assert!(builder.take_error().is_none());
+
+        let result: Vec<String> = ruby
+            .eval(
+                "[Factory.build(1), $special.magic(21).to_s, Factory.new.respond_to?(:build).to_s]",
+            )
+            .unwrap();
+        assert_eq!(result, ["widget #1", "42", "false"]);
+
+        builder.def_singleton_method(&1, "magic", |_: Value, (): ()| Ok::<_, String>(()));
+        match builder.take_error() {
+            Some(exc) => assert_eq!(exc.class_name(), "TypeError"),
+            None => panic!("expected TypeError"),
+        }
+    }
+
+    #[test]
+    fn define_singleton_method() {
+        let mut ruby = Mruby::new().unwrap();
+        let object: Root = ruby.eval("$special = Object.new").unwrap();
+
+        let result = ruby.define_singleton_method(&object, "magic", |_: Value, (n,): (i64,)| {
+            Ok::<_, String>(n * 2)
+        });
+        assert!(result.is_ok());
+
+        let doubled: i64 = ruby.eval("$special.magic(21)").unwrap();
+        assert_eq!(doubled, 42);
+
+        let responds: bool = ruby.eval("Object.new.respond_to?(:magic)").unwrap();
+        assert!(!responds);
+
+        match ruby.define_singleton_method(&1, "magic", |_: Value, (): ()| Ok::<_, String>(())) {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "TypeError"),
+            other => panic!("expected TypeError, got {:?}", other),
+        }
+    }
+
+    struct Game;
+
+    impl Module for Game {
+        const NAME: &'static str = "Game";
+        const OUTER: Option<&'static str> = None;
+
+        fn define(builder: &mut ModuleBuilder) {
+            builder
+                .def_const("VERSION", "1.0")
+                .def_module("Input", |input| {
+                    input.def_module_function("pressed?", |(key,): (String,)| {
+                        Ok::<_, String>(key == "space")
+                    });
+                });
+        }
+    }
+
+    struct Audio;
+
+    impl Module for Audio {
+        const NAME: &'static str = "Audio";
+        const OUTER: Option<&'static str> = Some("Game");
+
+        fn define(builder: &mut ModuleBuilder) {
+            builder
+                .def_const("CHANNELS", 2)
+                .def_module_function("volume", |(level,): (i64,)| Ok::<_, String>(level.min(10)));
+        }
+    }
+
+    #[test]
+    fn define_module() {
+        let mut ruby = Mruby::new().unwrap();
+        ruby.define_module::<Game>().unwrap();
+        ruby.define_module::<Audio>().unwrap();
+
+        let result: (String, bool, i64, i64) = ruby
+            .eval(r#"[Game::VERSION, Game::Input.pressed?("space"), Game::Audio::CHANNELS, Game::Audio.volume(11)]"#)
+            .unwrap();
+        assert_eq!(result, ("1.0".to_string(), true, 2, 10));
+
+        let included: i64 = ruby
+            .eval("class Mixer; include Game::Audio; def max; volume(99); end; end; Mixer.new.max")
+            .unwrap();
+        assert_eq!(included, 10);
+
+        let name: String = ruby.eval("Game::Audio.to_s").unwrap();
+        assert_eq!(name, "Game::Audio");
+    }
+
+    #[test]
+    fn define_module_errors() {
+        let mut ruby = Mruby::new().unwrap();
+        match ruby.define_module::<Audio>() {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "NameError"),
+            other => panic!("expected NameError, got {:?}", other),
+        }
+
+        ruby.eval::<()>("module Game; Input = 1; end; nil").unwrap();
+        match ruby.define_module::<Game>() {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "TypeError"),
+            other => panic!("expected TypeError, got {:?}", other),
+        }
+        let version: String = ruby.eval("Game::VERSION").unwrap();
+        assert_eq!(version, "1.0");
+
+        ruby.eval::<()>("Game = 1; nil").unwrap();
+        match ruby.define_module::<Game>() {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "TypeError"),
+            other => panic!("expected TypeError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn dynamic_object() {
+        let mut ruby = Mruby::new().unwrap();
+        let point: Object = ruby
+            .eval(
+                r#"
+                class Shape; end
+                class Point < Shape
+                  ORIGIN = 0
+                  def initialize; @x = 1; @y = 2; end
+                  def sum(z); @x + @y + z; end
+                end
+                Point.new
+                "#,
+            )
+            .unwrap();
+
+        assert_eq!(point.class_name(), "Point");
+        assert_eq!(point.superclass_name(), Some("Shape".to_string()));
+        assert_eq!(point.instance_variables(), ["@x", "@y"]);
+        if cfg!(feature = "gem-metaprog") {
+            assert!(point.methods().contains(&"sum".to_string()));
+            assert_eq!(point.constants(), ["ORIGIN"]);
+        }
+        assert!(point.respond_to("sum"));
+        assert!(!point.respond_to("product"));
+
+        assert_eq!(point.get_ivar::<i64>("x"), Ok(1));
+        point.set_ivar("@y", 40).unwrap();
+        assert_eq!(point.call::<i64, _>("sum", (1,)).unwrap(), 42);
+        assert_eq!(point.get_ivar::<Option<i64>>("z"), Ok(None));
+
+        let round_trip: String = point
+            .call("class", ())
+            .map(|c: Object| c.class_name())
+            .unwrap();
+        assert_eq!(round_trip, "Class");
+
+        let number: Object = ruby.eval("7").unwrap();
+        assert_eq!(number.class_name(), "Fixnum");
+        assert!(number.set_ivar("x", 1).is_err());
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Texture {
+        id: u32,
+        refs: Arc<()>,
+    }
+
+    impl Class for Texture {
+        const NAME: &'static str = "Texture";
+        const PARENT: Option<&'static str> = None;
+
+        fn define(builder: &mut class::Builder) {
+            builder.def_method_ref("id", |this: &Texture, (): ()| Ok::<_, String>(this.id));
+        }
+    }
+
+    #[test]
+    fn serialize_object() {
+        let refs = Arc::new(());
+        let mut ruby = Mruby::new().unwrap();
+        let texture = Texture {
+            id: 7,
+            refs: refs.clone(),
+        };
+        let value = Serializer::new(ruby.state).serialize_object(texture);
+        ruby.register_global("$texture", value.clone()).unwrap();
+
+        let result: (String, u32) = ruby.eval("[$texture.class.to_s, $texture.id]").unwrap();
+        assert_eq!(result, ("Texture".to_string(), 7));
+
+        {
+            let de = Deserializer::new(ruby.state, value.clone().into_inner());
+            let mut texture = de.deserialize_object_mut::<Texture>().unwrap();
+            texture.id = 8;
+        }
+
+        let id = Deserializer::new(ruby.state, value.clone().into_inner())
+            .deserialize_object_ref::<Texture>()
+            .map(|texture| texture.id);
+        assert_eq!(id, Ok(8));
+
+        let wrong = Deserializer::new(ruby.state, value.clone().into_inner())
+            .deserialize_object::<Person>();
+        assert!(wrong.is_err());
+
+        let texture = Deserializer::new(ruby.state, value.clone().into_inner())
+            .deserialize_object::<Texture>()
+            .unwrap();
+        assert_eq!(texture.id, 8);
+        assert!(Deserializer::new(ruby.state, value.clone().into_inner())
+            .deserialize_object_ref::<Texture>()
+            .is_err());
+
+        match ruby.eval::<Value>("$texture.id") {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "TypeError"),
+            other => panic!("expected TypeError, got {:?}", other),
+        }
+
+        drop(texture);
+        let value = Serializer::new(ruby.state).serialize_object(Texture {
+            id: 9,
+            refs: refs.clone(),
+        });
+        ruby.register_global("$texture", value).unwrap();
+        assert_eq!(Arc::strong_count(&refs), 2);
+
+        drop(ruby);
+        assert_eq!(Arc::strong_count(&refs), 1);
+    }
+
+    #[test]
+    fn compile_bytecode() {
+        let source = "def fail_loudly\n  raise 'oops'\nend\n\n$answer = 40\n$answer + 2\n";
+        let mut compiler = Mruby::new().unwrap();
+        let bytecode = compiler.compile(source, "script.rb", true).unwrap();
+        assert_eq!(&bytecode.as_bytes()[..8], b"RITE0006");
+        assert_eq!(compiler.get_global::<Option<i32>>("$answer"), Ok(None));
+        let stripped = compiler.compile(source, "script.rb", false).unwrap();
+        assert!(stripped.as_bytes().len() < bytecode.as_bytes().len());
+        drop(compiler);
+
+        fn fail_loudly(ruby: &mut Mruby) -> RubyException {
+            match ruby.call_global_function::<(), _>("fail_loudly", ()) {
+                Err(exc) => exc,
+                other => panic!("expected exception, found {:?}", other),
+            }
+        }
+
+        // The loaded code does not refer to the binary, which may be overwritten and freed.
+        let mut ruby = Mruby::new().unwrap();
+        let mut bytes = bytecode.into_vec();
+        let value: i32 = ruby.load_bytecode(&bytes).unwrap();
+        assert_eq!(value, 42);
+        bytes.iter_mut().for_each(|byte| *byte = 0);
+        drop(bytes);
+
+        let exc = fail_loudly(&mut ruby);
+        assert_eq!(exc.message(), "oops");
+        assert_eq!(exc.backtrace()[0], "script.rb:2:in fail_loudly");
+
+        let mut ruby = Mruby::new().unwrap();
+        // The binary does not need to be aligned.
+        let mut unaligned = vec![0];
+        unaligned.extend_from_slice(stripped.as_bytes());
+        let value: i32 = ruby.load_bytecode(&unaligned[1..]).unwrap();
+        assert_eq!(value, 42);
+        assert!(fail_loudly(&mut ruby).backtrace().is_empty());
+
+        let err = ruby
+            .compile("def broken(\n", "broken.rb", true)
+            .unwrap_err();
+        assert_eq!(err.filename(), "broken.rb");
+        assert_eq!(err.line(), Some(2));
+        assert!(err.to_string().starts_with("broken.rb:2:"), "{}", err);
+    }
+
+    #[test]
+    fn load_bytecode_errors() {
+        let mut ruby = Mruby::new().unwrap();
+        let bytes = ruby
+            .compile("$loaded = true", "loaded.rb", true)
+            .unwrap()
+            .into_vec();
+
+        let load = |ruby: &mut Mruby, bytes: &[u8]| match ruby.load_bytecode::<()>(bytes) {
+            Err(EvalError::Bytecode(err)) => err,
+            other => panic!("expected bytecode error, found {:?}", other),
+        };
+
+        assert_eq!(load(&mut ruby, b"RITE"), BytecodeError::Truncated);
+        assert_eq!(load(&mut ruby, &[0; 64]), BytecodeError::InvalidHeader);
+        assert_eq!(
+            load(&mut ruby, &bytes[..bytes.len() - 1]),
+            BytecodeError::Truncated
+        );
+
+        let mut old = bytes.clone();
+        old[4..8].copy_from_slice(b"0004");
+        assert_eq!(load(&mut ruby, &old), BytecodeError::UnsupportedVersion);
+
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 12;
+        corrupt[last] ^= 0xff;
+        assert_eq!(load(&mut ruby, &corrupt), BytecodeError::ChecksumMismatch);
+
+        assert_eq!(ruby.get_global::<Option<bool>>("$loaded"), Ok(None));
+        assert_eq!(ruby.load_bytecode::<bool>(&bytes), Ok(true));
+        assert_eq!(ruby.get_global::<bool>("$loaded"), Ok(true));
+    }
+
+    #[test]
+    fn include_ruby() {
+        static GREETING: &[u8] = include_ruby!("tests/scripts/greeting.rb");
+        static GREETING_SOURCE: &[u8] = include_ruby!("tests/scripts/greeting.rb", source);
+
+        let mut ruby = Mruby::new().unwrap();
+        let greeting: String = ruby.load_bytecode(GREETING).unwrap();
+        assert_eq!(greeting, "Hello, world!");
+
+        let source = std::str::from_utf8(GREETING_SOURCE).unwrap();
+        assert!(source.starts_with("def greet(name)"));
+        let greeting: String = ruby.eval(source).unwrap();
+        assert_eq!(greeting, "Hello, world!");
+    }
+
+    #[test]
+    fn memory_limit() {
+        let mut ruby = Mruby::new().unwrap();
+        assert_eq!(ruby.memory_limit(), None);
+        let baseline = ruby.memory_usage();
+        assert!(baseline > 0);
+
+        let len: usize = ruby.eval("$big = 'a' * (4 << 20); $big.size").unwrap();
+        assert_eq!(len, 4 << 20);
+        assert!(ruby.memory_usage() >= baseline + len);
+        assert!(ruby.peak_memory_usage() >= ruby.memory_usage());
+
+        let mut ruby = Mruby::builder().memory_limit(2 << 20).build().unwrap();
+        assert_eq!(ruby.memory_limit(), Some(2 << 20));
+
+        match ruby.eval::<usize>("('a' * (4 << 20)).size") {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "NoMemoryError"),
+            other => panic!("expected NoMemoryError, found {:?}", other),
+        }
+        assert!(ruby.peak_memory_usage() <= 2 << 20);
+
+        let len: usize = ruby
+            .eval("('a' * 1024).size")
+            .expect("interpreter is still usable");
+        assert_eq!(len, 1024);
+
+        // Converting the return value of a Rust function allocates as well.
+        ruby.define_global_fn("big", |(): ()| Ok::<_, String>("a".repeat(4 << 20)))
+            .unwrap();
+        match ruby.eval::<usize>("big.size") {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "NoMemoryError"),
+            other => panic!("expected NoMemoryError, found {:?}", other),
+        }
+
+        let len: usize = ruby.eval("'a'.size").expect("interpreter is still usable");
+        assert_eq!(len, 1);
+
+        // So does converting values from outside of any call into Ruby.
+        match ruby.register_global("$big", "a".repeat(4 << 20)) {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "NoMemoryError"),
+            other => panic!("expected NoMemoryError, found {:?}", other),
+        }
+        assert!(ruby.peak_memory_usage() <= 2 << 20);
+        assert_eq!(ruby.get_global::<Option<String>>("$big"), Ok(None));
+
+        // Startup cannot be refused memory, but is still held to the limit.
+        match Mruby::builder().memory_limit(1024).build() {
+            Err(Error::MemoryLimit) => {}
+            other => panic!("expected Error::MemoryLimit, found {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn exit() {
+        let mut ruby = Mruby::new().unwrap();
+        let result = ruby.eval::<Value>("begin; exit 3; ensure; $ensured = true; end");
+        assert_eq!(result.map(|_| ()), Err(EvalError::Exit(3)));
+        assert_eq!(ruby.get_global::<bool>("$ensured"), Ok(true));
+
+        assert_eq!(
+            ruby.eval::<Value>("exit").map(|_| ()),
+            Err(EvalError::Exit(0))
+        );
+        assert_eq!(
+            ruby.eval::<Value>("exit(false)").map(|_| ()),
+            Err(EvalError::Exit(1))
+        );
+        assert_eq!(
+            ruby.eval::<Value>("exit!(2)").map(|_| ()),
+            Err(EvalError::Exit(2))
+        );
+
+        let status: i64 = ruby
+            .eval("begin; exit 4; rescue => e; 0; rescue SystemExit => e; e.status; end")
+            .unwrap();
+        assert_eq!(status, 4);
+
+        ruby.eval::<Value>("def quit(status); exit(status); end")
+            .unwrap();
+        let result = ruby.call_global_function::<Value, _>("quit", (5,));
+        assert_eq!(
+            result.map(|_| ()).map_err(EvalError::from),
+            Err(EvalError::Exit(5))
+        );
+
+        // Only the class defined on startup ends the script, not another class with its name.
+        let code = "Object.__send__(:remove_const, :SystemExit)
+                    class SystemExit < Exception; end
+                    raise SystemExit";
+        match ruby.eval::<Value>(code) {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "SystemExit"),
+            other => panic!("expected plain exception, found {:?}", other.map(|_| ())),
+        }
+        ruby.full_gc();
+        assert_eq!(
+            ruby.eval::<Value>("exit 6").map(|_| ()),
+            Err(EvalError::Exit(6))
+        );
+    }
+
+    #[test]
+    fn sandboxed() {
+        let mut ruby = Mruby::sandboxed(Capabilities::none()).unwrap();
+        assert_eq!(ruby.capabilities(), Capabilities::none());
+
+        let mut denied = vec![
+            "open('Cargo.toml')",
+            "`echo`",
+            "eval('1 + 1')",
+            "instance_eval('1 + 1')",
+            "exit(1)",
+        ];
+
+        if cfg!(feature = "gem-io") {
+            denied.push("File.read('Cargo.toml')");
+        }
+
+        if cfg!(feature = "gem-method") {
+            denied.push("Class.instance_method(:new).bind(File).call('Cargo.toml')");
+        }
+
+        for code in &denied {
+            match ruby.eval::<Value>(code) {
+                Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "SecurityError"),
+                other => panic!("expected SecurityError from {}, found {:?}", code, other),
+            }
+        }
+
+        let rescued: bool = ruby
+            .eval("begin; open('Cargo.toml'); rescue; false; rescue Exception; true; end")
+            .unwrap();
+        assert!(rescued);
+
+        let size: usize = ruby.eval("'hello'.instance_eval { size }").unwrap();
+        assert_eq!(size, 5);
+
+        let capabilities = Capabilities {
+            io: true,
+            ..Capabilities::none()
+        };
+        let capabilities = Mruby::sandboxed(capabilities).unwrap().capabilities();
+        assert!(capabilities.io);
+        assert!(!capabilities.eval);
+        let capabilities = Mruby::new().unwrap().capabilities();
+        assert_eq!(capabilities, Capabilities::all());
+    }
+
+    #[test]
+    fn bundled_gems() {
+        let mut ruby = Mruby::new().unwrap();
+        let gems = [
+            ("File", cfg!(feature = "gem-io")),
+            ("Socket", cfg!(feature = "gem-socket")),
+            ("Fiber", cfg!(feature = "gem-fiber")),
+            ("Rational", cfg!(feature = "gem-rational")),
+        ];
+
+        for &(class, enabled) in &gems {
+            let code = format!("Object.const_defined?(:{})", class);
+            let defined: bool = ruby.eval(&code).unwrap();
+            assert_eq!(defined, enabled, "{} defined: {}", class, defined);
+        }
+    }
+
+    #[test]
+    fn execution_limit() {
+        use std::time::{Duration, Instant};
+
+        let mut ruby = Mruby::new().unwrap();
+        ruby.set_execution_limit(Limit::Instructions(10_000))
+            .unwrap();
+        let sum: i64 = ruby.eval("(1..10).inject(:+)").unwrap();
+        assert_eq!(sum, 55);
+
+        let result = ruby.eval::<bool>("loop {}; true");
+        assert_eq!(
+            result,
+            Err(EvalError::Execution(ExecutionError::LimitExceeded))
+        );
+
+        let code = "loop { begin; loop {}; rescue Exception; end }; true";
+        ruby.set_execution_limit(Limit::Instructions(10_000))
+            .unwrap();
+        let result = ruby.eval::<bool>(code);
+        assert_eq!(
+            result,
+            Err(EvalError::Execution(ExecutionError::LimitExceeded))
+        );
+
+        let deadline = Instant::now() + Duration::from_millis(50);
+        ruby.set_execution_limit(Limit::Deadline(deadline)).unwrap();
+        let result = ruby.eval::<bool>("loop {}; true");
+        assert_eq!(
+            result,
+            Err(EvalError::Execution(ExecutionError::LimitExceeded))
+        );
+        assert!(Instant::now() >= deadline);
+
+        ruby.clear_execution_limit();
+        ruby.eval::<Value>("def spin; loop {}; end").unwrap();
+        ruby.set_execution_limit(Limit::Instructions(10_000))
+            .unwrap();
+        let result = ruby.call_global_function::<bool, _>("spin", ());
+        assert_eq!(
+            result.map_err(EvalError::from),
+            Err(EvalError::Execution(ExecutionError::LimitExceeded))
+        );
+
+        ruby.clear_execution_limit();
+        let sum: i64 = ruby
+            .eval("(1..10).inject(:+)")
+            .expect("interpreter is still usable");
+        assert_eq!(sum, 55);
+
+        let code = "class ExecutionLimitExceeded < Exception; end; raise ExecutionLimitExceeded";
+        match ruby.eval::<Value>(code) {
+            Err(EvalError::Exception(exc)) => {
+                assert_eq!(exc.class_name(), "ExecutionLimitExceeded")
+            }
+            other => panic!("expected plain exception, found {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn interrupt() {
+        use std::thread;
+        use std::time::Duration;
+
+        fn assert_send_sync_clone<T: Send + Sync + Clone>() {}
+        assert_send_sync_clone::<InterruptHandle>();
+
+        let mut ruby = Mruby::new().unwrap();
+        let handle = ruby.interrupt_handle().unwrap();
+        let code = "loop { begin; loop {}; rescue Exception; end }; true";
+
+        let interrupter = handle.clone();
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            interrupter.interrupt();
+        });
+
+        let result = ruby.eval::<bool>(code);
+        assert_eq!(
+            result,
+            Err(EvalError::Execution(ExecutionError::Interrupted))
+        );
+        thread.join().unwrap();
+
+        let sum: i64 = ruby
+            .eval("(1..10).inject(:+)")
+            .expect("interpreter is still usable");
+        assert_eq!(sum, 55);
+
+        ruby.eval::<Value>("def spin; loop {}; end").unwrap();
+        handle.interrupt();
+        let result = ruby.call_global_function::<bool, _>("spin", ());
+        assert_eq!(
+            result.map_err(EvalError::from),
+            Err(EvalError::Execution(ExecutionError::Interrupted))
+        );
+
+        // Nested calls leave the request pending for the code around them, which cannot rescue
+        // the error the Rust function fails with, or its panic, either.
+        let interrupter = handle.clone();
+        ruby.define_global_fn("nested", move |(main,): (Object,)| {
+            interrupter.interrupt();
+            main.call::<Value, _>("spin", ()).map(|_| ())
+        })
+        .unwrap();
+        let interrupter = handle.clone();
+        ruby.define_global_fn("nested!", move |(main,): (Object,)| {
+            interrupter.interrupt();
+            main.call::<Value, _>("spin", ()).unwrap();
+            Ok::<_, EvalError>(())
+        })
+        .unwrap();
+        ruby.eval::<Value>(
+            "def sum; (1..10).inject(:+); end
+             def rescue_nested; begin; nested(self); rescue; end; true; end
+             def rescue_nested!; begin; nested!(self); rescue; end; true; end",
+        )
+        .unwrap();
+
+        // Calls made through an `Object` clear the request once it aborted them as well.
+        let main: Object = ruby.eval("self").unwrap();
+        handle.interrupt();
+        assert_eq!(
+            main.call::<bool, _>("spin", ()),
+            Err(EvalError::Execution(ExecutionError::Interrupted))
+        );
+        assert_eq!(main.call::<i64, _>("sum", ()), Ok(55));
+
+        for method in &["rescue_nested", "rescue_nested!"] {
+            assert_eq!(
+                main.call::<bool, _>(method, ()),
+                Err(EvalError::Execution(ExecutionError::Interrupted))
+            );
+            assert_eq!(main.call::<i64, _>("sum", ()), Ok(55));
+        }
+
+        // Rust code calling such a function sees the abort rather than the error it failed with.
+        assert_eq!(
+            main.call::<(), _>("nested!", (&main,)),
+            Err(EvalError::Execution(ExecutionError::Interrupted))
+        );
+
+        let code = "class ExecutionInterrupted < Exception; end; raise ExecutionInterrupted";
+        match ruby.eval::<Value>(code) {
+            Err(EvalError::Exception(exc)) => {
+                assert_eq!(exc.class_name(), "ExecutionInterrupted")
+            }
+            other => panic!("expected plain exception, found {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "gem-print")]
+    fn redirect_output() {
+        let mut ruby = Mruby::new().unwrap();
+        let stdout = Capture::new();
+        let stderr = Capture::new();
+        ruby.set_stdout(Box::new(stdout.clone())).unwrap();
+        ruby.set_stderr(Box::new(stderr.clone())).unwrap();
+
+        ruby.eval::<Value>("puts 'hello', 42; print 'a', 'b'; p :sym")
+            .unwrap();
+        assert_eq!(stdout.to_string_lossy(), "hello\n42\nab:sym\n");
+
+        if cfg!(feature = "gem-io") {
+            stdout.clear();
+            let code = "$stdout.write(1); STDOUT.puts 'out'; $stderr.puts 'err'; STDERR.flush";
+            ruby.eval::<Value>(code).unwrap();
+            assert_eq!(stdout.to_string_lossy(), "1out\n");
+            assert_eq!(stderr.to_string_lossy(), "err\n");
+        }
+
+        let replaced = Capture::new();
+        ruby.set_stdout(Box::new(replaced.clone())).unwrap();
+        ruby.eval::<Value>("puts 'again'").unwrap();
+        assert_eq!(replaced.contents(), b"again\n");
+        assert!(!stdout.to_string_lossy().contains("again"));
+    }
+
+    #[test]
+    fn rooted_values() {
+        use std::collections::HashMap;
+        use std::thread;
+
+        fn assert_send<T: Send>() {}
+        assert_send::<Root>();
+
+        let mut ruby = Mruby::new().unwrap();
+        let mut callbacks = HashMap::new();
+        callbacks.insert(
+            "double",
+            ruby.eval::<Root>("Proc.new { |x| x * 2 }").unwrap(),
+        );
+        let text: Root = ruby.eval("'rooted ' * 4").unwrap();
+
+        ruby.eval::<Value>("Array.new(100_000) { |i| i.to_s * 10 }; GC.start")
+            .unwrap();
+
+        let result: i64 = ruby.call(&callbacks["double"], "call", (21,)).unwrap();
+        assert_eq!(result, 42);
+        let length: i64 = ruby.call(&text, "length", ()).unwrap();
+        assert_eq!(length, 28);
+        ruby.eval::<Value>("def shout(s) s.upcase.chop end")
+            .unwrap();
+        let shouted: String = ruby.call_global_function("shout", (&text,)).unwrap();
+        assert_eq!(shouted, "ROOTED ROOTED ROOTED ROOTED");
+
+        thread::spawn(move || drop(callbacks)).join().unwrap();
+        ruby.eval::<Value>("GC.start").unwrap();
+
+        let other = Mruby::new().unwrap();
+        drop(ruby);
+        drop(text);
+        drop(other);
+    }
+
+    // NOTE: This compiles, since lifetimes cannot tell two live interpreters apart.
+    #[test]
+    #[should_panic(expected = "value belongs to a different interpreter")]
+    fn values_belong_to_their_interpreter() {
+        let mut ruby = Mruby::new().unwrap();
+        let mut other = Mruby::new().unwrap();
+        let array: Value = ruby.eval("[1, 2, 3]").unwrap();
+        other.register_global("$array", &array).unwrap();
+    }
+
+    #[test]
+    fn gc_arena_scoping() {
+        use std::collections::HashMap;
+
+        fn arena_index(ruby: &Mruby) -> i32 {
+            unsafe { (*ruby.state).gc.arena_idx }
+        }
+
+        let mut ruby = Mruby::new().unwrap();
+        let start = arena_index(&ruby);
+
+        let strings: Vec<String> = (0..100_000).map(|i| i.to_string()).collect();
+        ruby.register_global("$strings", &strings).unwrap();
+        assert_eq!(arena_index(&ruby), start);
+
+        let size = ruby.arena_scope(|ruby| ruby.eval::<i64>("GC.start; $strings.size"));
+        assert_eq!(size.unwrap(), 100_000);
+        let read: Vec<String> = ruby.arena_scope(|ruby| ruby.get_global("$strings").unwrap());
+        assert_eq!(read, strings);
+
+        ruby.arena_scope(|ruby| {
+            ruby.eval::<()>(
+                "$map = {}; 1000.times { |i| $map[i.to_s] = { 'n' => [i.to_s] } }; nil",
+            )
+            .unwrap();
+            let before = arena_index(ruby);
+            let map: HashMap<String, HashMap<String, Vec<String>>> =
+                ruby.get_global("$map").unwrap();
+            assert!(arena_index(ruby) <= before + 3);
+            assert_eq!(map["999"]["n"], ["999"]);
+        });
+        assert_eq!(arena_index(&ruby), start);
+
+        for i in 0..1000 {
+            let length = ruby.arena_scope(|ruby| {
+                let text: Root = ruby.eval("'x' * 100").unwrap();
+                let copy: Root = ruby.call(&text, "dup", ()).unwrap();
+                ruby.call::<i64, _>(&copy, "length", ()).unwrap() + i
+            });
+            assert_eq!(length, 100 + i);
+        }
+        assert_eq!(arena_index(&ruby), start);
+
+        ruby.arena_scope(|ruby| {
+            let text = ruby.eval::<Value>("'y' * 100").unwrap().into_inner();
+            ruby.full_gc();
+            let text = Value::new(ruby.state, text);
+            assert_eq!(ruby.call::<i64, _>(&text, "length", ()).unwrap(), 100);
+        });
+
+        let strings = vec![String::from("ok"); 100];
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            ruby.register_global("$invalid", (&strings, "null\0byte"))
+                .unwrap();
+        }));
+        assert!(result.is_err());
+        assert_eq!(arena_index(&ruby), start);
+    }
+
+    #[test]
+    fn borrowed_strings() {
+        use std::borrow::Cow;
+
+        let mut ruby = Mruby::new().unwrap();
+        let script = ruby
+            .eval::<Object>(
+                "Class.new do
+                   def text; $text = 'hello'; end
+                   def frozen; $frozen = 'frozen'.freeze; end
+                   def symbol(name); name.to_sym; end
+                   def churn
+                     $text.upcase!; $text = $frozen = nil
+                     GC.start; Array.new(1000) { |i| i.to_s * 10 }; nil
+                   end
+                 end.new",
+            )
+            .unwrap();
+
+        // Methods of an object may run Ruby code while earlier results are still borrowed.
+        let text: &str = script.call("text", ()).unwrap();
+        let frozen: Cow<str> = script.call("frozen", ()).unwrap();
+        let short: &str = script.call("symbol", ("foo",)).unwrap();
+        let long: &str = script.call("symbol", ("a_much_longer_symbol",)).unwrap();
+
+        let other: &str = script.call("symbol", ("bar",)).unwrap();
+        script.call::<(), _>("churn", ()).unwrap();
+
+        assert_eq!(text, "hello");
+        assert_eq!(frozen, "frozen");
+        assert_eq!(short, "foo");
+        assert_eq!(other, "bar");
+        assert_eq!(long, "a_much_longer_symbol");
+    }
+
+    #[test]
+    fn gc_control() {
+        let mut ruby = Mruby::new().unwrap();
+        assert!(ruby.gc_enabled());
+        let generational = cfg!(not(feature = "disable-generational-gc"));
+        assert_eq!(ruby.gc_generational_mode(), generational);
+        assert_eq!(ruby.gc_stats().generational, generational);
+
+        assert_eq!((ruby.gc_interval_ratio(), ruby.gc_step_ratio()), (200, 200));
+        ruby.set_gc_interval_ratio(150);
+        ruby.set_gc_step_ratio(400);
+        let ratios: (i64, i64) = ruby.eval("[GC.interval_ratio, GC.step_ratio]").unwrap();
+        assert_eq!(ratios, (150, 400));
+
+        ruby.eval::<()>("$garbage = Array.new(10_000) { |i| i.to_s }; nil")
+            .unwrap();
+        ruby.full_gc();
+        let before = ruby.gc_stats();
+        ruby.eval::<()>("$garbage = nil").unwrap();
+        ruby.full_gc();
+        let after = ruby.gc_stats();
+        assert!(after.live_objects + 10_000 <= before.live_objects);
+        assert!(after.heap_pages > 0);
+        assert_eq!(after.phase, GcPhase::Root);
+        assert_eq!(after.threshold, after.live_after_mark / 100 * 150);
+
+        ruby.set_gc_generational_mode(!generational).unwrap();
+        assert_eq!(ruby.gc_generational_mode(), !generational);
+        ruby.incremental_gc();
+
+        ruby.set_gc_enabled(false);
+        assert!(!ruby.gc_stats().enabled);
+        assert!(ruby.eval::<bool>("GC.enable").unwrap());
+        ruby.set_gc_enabled(false);
+        let live = ruby.gc_stats().live_objects;
+        ruby.eval::<()>("Array.new(10_000) { |i| i.to_s }; nil")
+            .unwrap();
+        ruby.garbage_collect();
+        assert!(ruby.gc_stats().live_objects >= live + 10_000);
+        match ruby.set_gc_generational_mode(generational) {
+            Err(EvalError::Exception(exc)) => assert_eq!(exc.class_name(), "RuntimeError"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        ruby.set_gc_enabled(true);
+        ruby.set_gc_generational_mode(generational).unwrap();
+        assert_eq!(ruby.gc_generational_mode(), generational);
+        ruby.garbage_collect();
+        assert!(ruby.gc_stats().live_objects < live + 10_000);
+    }
+
+    #[test]
+    fn gc_time_budget() {
+        use std::time::Duration;
+
+        let mut ruby = Mruby::new().unwrap();
+        ruby.set_gc_generational_mode(false).unwrap();
+        ruby.eval::<()>("$kept = Array.new(20_000) { |i| i.to_s }; nil")
+            .unwrap();
+        ruby.full_gc();
+        let live = ruby.gc_stats().live_objects;
+
+        ruby.set_gc_deferred(true);
+        assert!(ruby.gc_deferred());
+        ruby.eval::<()>("50_000.times { |i| i.to_s }; nil").unwrap();
+        let stats = ruby.gc_stats();
+        assert_eq!(stats.phase, GcPhase::Root);
+        assert!(stats.live_objects >= live + 50_000);
+
+        let progress = ruby.gc_step_for(Duration::from_secs(0));
+        assert_eq!((progress.steps, progress.complete), (1, false));
+        assert_eq!(progress.phase, GcPhase::Mark);
+        assert_eq!(progress.pages_to_sweep, ruby.gc_stats().heap_pages);
+
+        ruby.eval::<()>("10_000.times { |i| i.to_s }; GC.start; nil")
+            .unwrap();
+        ruby.eval::<()>("10_000.times { |i| i.to_s }; nil").unwrap();
+        assert_eq!(ruby.gc_stats().threshold, usize::MAX);
+
+        let progress = ruby.gc_step_for(Duration::from_secs(60));
+        assert!(progress.complete);
+        assert_eq!(
+            (progress.phase, progress.pages_to_sweep),
+            (GcPhase::Root, 0)
+        );
+        let progress = ruby.gc_step_for(Duration::from_secs(60));
+        assert!(progress.complete && progress.steps > 1);
+        assert!(ruby.gc_stats().live_objects < live + 10_000);
+        let kept: Vec<String> = ruby.get_global("$kept").unwrap();
+        assert_eq!(kept.len(), 20_000);
+
+        ruby.set_gc_deferred(false);
+        assert!(!ruby.gc_deferred());
+        ruby.eval::<()>("50_000.times { |i| i.to_s }; nil").unwrap();
+        assert_ne!(ruby.gc_stats().threshold, usize::MAX);
+
+        ruby.set_gc_enabled(false);
+        let progress = ruby.gc_step_for(Duration::from_secs(60));
+        assert_eq!((progress.steps, progress.complete), (0, false));
+    }
 }
@@ -0,0 +1,91 @@
+use std::time::Instant;
+
+use mruby_sys::{mrb_state, mrb_value};
+
+use crate::error::{Abort, ExecutionError, RubyException, LIMIT_EXCEEDED_CLASS};
+use crate::hook::new_abort_exception;
+
+/// Number of instructions executed between two checks of a `Limit::Deadline`.
+///
+/// Reading the clock is far more expensive than executing an instruction, so it is only done
+/// every so often. This must be a power of two.
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+/// A limit on how long Ruby code may run, set with `Mruby::set_execution_limit()`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Limit {
+    /// Allows executing at most this many VM instructions.
+    Instructions(u64),
+    /// Allows running until the given point in time.
+    Deadline(Instant),
+}
+
+/// Execution limit of an interpreter, along with the exception raised once it is exceeded.
+#[derive(Debug)]
+pub(crate) struct ExecutionLimit {
+    limit: Option<Limit>,
+    executed: u64,
+    exceeded: bool,
+    exception: mrb_value,
+}
+
+impl ExecutionLimit {
+    pub unsafe fn new(state: *mut mrb_state) -> Result<Self, RubyException> {
+        let msg = "execution limit exceeded";
+        Ok(ExecutionLimit {
+            limit: None,
+            executed: 0,
+            exceeded: false,
+            exception: new_abort_exception(
+                state,
+                LIMIT_EXCEEDED_CLASS,
+                msg,
+                Abort::Execution(ExecutionError::LimitExceeded),
+            )?,
+        })
+    }
+
+    /// Replaces the current limit, resetting the count of executed instructions.
+    pub fn set(&mut self, limit: Option<Limit>) {
+        self.limit = limit;
+        self.executed = 0;
+        self.exceeded = false;
+    }
+
+    /// Returns whether a limit is currently set.
+    pub fn is_set(&self) -> bool {
+        self.limit.is_some()
+    }
+
+    /// Returns whether the limit was exceeded, without counting an instruction.
+    pub fn is_exceeded(&self) -> bool {
+        self.exceeded
+    }
+
+    /// Returns the exception raised once the limit is exceeded.
+    pub fn exception(&self) -> mrb_value {
+        self.exception
+    }
+
+    /// Records the execution of one more instruction, returning whether the limit is exceeded.
+    ///
+    /// Once exceeded, this keeps returning `true` until a new limit is set.
+    pub fn tick(&mut self) -> bool {
+        if self.exceeded {
+            return true;
+        }
+
+        self.exceeded = match self.limit {
+            Some(Limit::Instructions(max)) => self.executed >= max,
+            Some(Limit::Deadline(deadline))
+                if self.executed & (DEADLINE_CHECK_INTERVAL - 1) == 0 =>
+            {
+                Instant::now() >= deadline
+            }
+            Some(Limit::Deadline(_)) | None => false,
+        };
+
+        self.executed += 1;
+        self.exceeded
+    }
+}
@@ -6,7 +6,6 @@ macro_rules! replace_expr {
     };
 }
 
-#[macro_use]
 macro_rules! count_tokens {
     ( $($elem:tt)* ) => {
         <[()]>::len(&[$(replace_expr!($elem ())),*])
@@ -0,0 +1,150 @@
+use std::alloc::{self, Layout};
+use std::mem;
+use std::os::raw::c_void;
+use std::ptr;
+
+use mruby_sys::mrb_state;
+
+/// Size of the header storing the size of each allocation.
+///
+/// This is also the alignment of every allocation, matching what `malloc()` guarantees, which
+/// mruby relies on.
+const HEADER_SIZE: usize = mem::align_of::<MaxAlign>();
+
+const _: () = assert!(HEADER_SIZE >= mem::size_of::<usize>());
+
+/// Stand-in for C's `max_align_t`, which is aligned like the most strictly aligned scalar type.
+#[allow(dead_code)]
+#[repr(C)]
+union MaxAlign {
+    int: u128,
+    float: f64,
+    ptr: *const c_void,
+    size: usize,
+}
+
+/// Memory allocator of an interpreter, which keeps track of the bytes in use and enforces an
+/// optional upper limit on them.
+#[derive(Debug, Default)]
+pub(crate) struct Allocator {
+    limit: Option<usize>,
+    current: usize,
+    peak: usize,
+}
+
+impl Allocator {
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Returns the number of bytes currently allocated by mruby.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Returns the largest number of bytes allocated by mruby at any one time.
+    pub fn peak(&self) -> usize {
+        self.peak
+    }
+
+    /// Returns whether more bytes are in use than the limit allows, which can only happen through
+    /// allocations made while the limit could not be enforced, or before it was set.
+    pub fn is_exceeded(&self) -> bool {
+        !self.fits(0, 0)
+    }
+
+    /// Returns whether `additional` more bytes can be allocated after freeing `freed` bytes.
+    fn fits(&self, freed: usize, additional: usize) -> bool {
+        match self.limit {
+            Some(limit) => self.current - freed + additional <= limit,
+            None => true,
+        }
+    }
+
+    fn record(&mut self, freed: usize, allocated: usize) {
+        self.current = self.current - freed + allocated;
+        self.peak = self.peak.max(self.current);
+    }
+
+    unsafe fn alloc(&mut self, size: usize, limited: bool) -> *mut c_void {
+        let layout = match layout(size) {
+            Some(layout) if !limited || self.fits(0, size) => layout,
+            _ => return ptr::null_mut(),
+        };
+
+        let base = alloc::alloc(layout);
+        if base.is_null() {
+            return ptr::null_mut();
+        }
+
+        self.record(0, size);
+        (base as *mut usize).write(size);
+        base.add(HEADER_SIZE) as *mut c_void
+    }
+
+    unsafe fn realloc(&mut self, ptr: *mut c_void, size: usize, limited: bool) -> *mut c_void {
+        let base = (ptr as *mut u8).sub(HEADER_SIZE);
+        let old_size = (base as *mut usize).read();
+        if layout(size).is_none() || (limited && !self.fits(old_size, size)) {
+            return ptr::null_mut();
+        }
+
+        let old_layout = layout(old_size).expect("layout was valid when allocated");
+        let base = alloc::realloc(base, old_layout, size + HEADER_SIZE);
+        if base.is_null() {
+            return ptr::null_mut();
+        }
+
+        self.record(old_size, size);
+        (base as *mut usize).write(size);
+        base.add(HEADER_SIZE) as *mut c_void
+    }
+
+    unsafe fn free(&mut self, ptr: *mut c_void) {
+        if ptr.is_null() {
+            return;
+        }
+
+        let base = (ptr as *mut u8).sub(HEADER_SIZE);
+        let size = (base as *mut usize).read();
+        self.record(size, 0);
+        alloc::dealloc(base, layout(size).expect("layout was valid when allocated"));
+    }
+}
+
+/// Implementation of `mrb_allocf` backed by the `Allocator` passed in `ud`.
+///
+/// As with `realloc()`, this allocates when `ptr` is null and frees when `size` is zero. It
+/// returns null when the allocation would exceed the memory limit, in which case mruby runs the
+/// garbage collector and tries again before raising `NoMemoryError`.
+pub(crate) unsafe extern "C" fn allocf(
+    mrb: *mut mrb_state,
+    ptr: *mut c_void,
+    size: usize,
+    ud: *mut c_void,
+) -> *mut c_void {
+    // NOTE: mruby aborts the process when it fails to allocate memory without a handler to raise
+    // `NoMemoryError` to, e.g. during `mrb_open()` or when called from Rust outside of a protected
+    // call. The limit is only enforced when the error can be caught. Other allocations still count
+    // towards the memory usage, and `MrubyBuilder::build()` fails if startup exceeds the limit.
+    let limited = !mrb.is_null() && !(*mrb).jmp.is_null();
+
+    let allocator = &mut *(ud as *mut Allocator);
+    if size == 0 {
+        allocator.free(ptr);
+        ptr::null_mut()
+    } else if ptr.is_null() {
+        allocator.alloc(size, limited)
+    } else {
+        allocator.realloc(ptr, size, limited)
+    }
+}
+
+fn layout(size: usize) -> Option<Layout> {
+    let size = size.checked_add(HEADER_SIZE)?;
+    Layout::from_size_align(size, HEADER_SIZE).ok()
+}
@@ -1,2 +1,162 @@
+use std::ffi::CString;
+use std::fmt::Display;
+
+use mruby_sys::{mrb_define_module, mrb_define_module_under, mrb_state, RClass};
+
+use crate::class::get_module;
+use crate::de::FromArgs;
+use crate::error::RubyException;
+use crate::function::{catch_raised, protect, raising, Function};
+use crate::ser::{Serializer, ToValue};
+use crate::userdata::UserData;
+use crate::value::Value;
+
+/// A Rust type which can be registered as a Ruby module with `Mruby::define_module()`.
+pub trait Module {
+    /// The name of the Ruby module.
+    const NAME: &'static str;
+    /// The path of the enclosing module, e.g. `Some("Game")`, or `None` for a top-level module.
+    const OUTER: Option<&'static str>;
+
+    /// Defines the constants, functions and nested modules of the module.
+    fn define(builder: &mut ModuleBuilder);
+}
+
+/// Defines the constants, functions and nested modules of a Ruby module, as passed to
+/// `Module::define()`.
+///
+/// As with `class::Builder`, a failed definition records its exception and skips the remaining
+/// ones, and `Mruby::define_module()` returns the error.
 #[derive(Debug)]
-pub struct Module;
+pub struct ModuleBuilder {
+    state: *mut mrb_state,
+    module: *mut RClass,
+    error: Option<RubyException>,
+}
+
+impl ModuleBuilder {
+    /// Defines the module `name`, or reopens it if it already exists.
+    ///
+    /// Fails if `outer` is given and no module exists at that path, or if `name` is already bound
+    /// to something other than a module.
+    pub(crate) unsafe fn new(
+        state: *mut mrb_state,
+        name: &str,
+        outer: Option<&str>,
+    ) -> Result<Self, RubyException> {
+        let name = CString::new(name).expect("String contains null byte");
+        let outer = match outer {
+            Some(path) => Some(get_module(state, path)?),
+            None => None,
+        };
+
+        let module = protect(state, || match outer {
+            None => mrb_define_module(state, name.as_ptr()),
+            Some(outer) => mrb_define_module_under(state, outer, name.as_ptr()),
+        })?;
+
+        Ok(ModuleBuilder {
+            state,
+            module,
+            error: None,
+        })
+    }
+
+    /// Returns the exception raised by the first definition which failed, if any.
+    pub(crate) fn take_error(&mut self) -> Option<RubyException> {
+        self.error.take()
+    }
+
+    pub fn def_const<N, V>(&mut self, name: N, value: V) -> &mut Self
+    where
+        N: AsRef<str>,
+        V: ToValue,
+    {
+        use mruby_sys::mrb_define_const;
+
+        let name = CString::new(name.as_ref()).expect("String contains null byte");
+        self.define(|state, module| unsafe {
+            let value = value.to_value(Serializer::new(state)).into_inner();
+            raising(state, || {
+                mrb_define_const(state, module, name.as_ptr(), value)
+            });
+            Ok(())
+        })
+    }
+
+    /// Defines a module function backed by the given Rust closure.
+    ///
+    /// As with `Module#module_function` in Ruby, the function can be called on the module itself
+    /// as well as from classes which include the module. The arguments are converted as with
+    /// `Mruby::define_global_fn()`.
+    pub fn def_module_function<'mrb, N, F, A, R, E>(&mut self, name: N, func: F) -> &mut Self
+    where
+        N: AsRef<str>,
+        F: Fn(A) -> Result<R, E> + Send + 'static,
+        A: FromArgs<'mrb>,
+        R: ToValue,
+        E: Display,
+    {
+        use mruby_sys::{mrb_ext_class_value, mrb_singleton_class};
+
+        let func = Function::new(move |_: Value, args: A| func(args));
+        self.define(|state, module| unsafe {
+            let func = UserData::get(state).add_function(func);
+            raising(state, || {
+                let singleton = mrb_singleton_class(state, mrb_ext_class_value(module));
+                func.define(state, singleton.value.p as *mut RClass, name.as_ref());
+                func.define(state, module, name.as_ref());
+            });
+            Ok(())
+        })
+    }
+
+    /// Defines the module `name` nested inside this one, or reopens it if it already exists, and
+    /// passes its builder to `define`.
+    ///
+    /// Fails if `name` is already bound to something other than a module, in which case `define`
+    /// is not called.
+    pub fn def_module<N, F>(&mut self, name: N, define: F) -> &mut Self
+    where
+        N: AsRef<str>,
+        F: FnOnce(&mut ModuleBuilder),
+    {
+        let name = CString::new(name.as_ref()).expect("String contains null byte");
+        let mut nested = None;
+        self.define(|state, module| unsafe {
+            let module = raising(state, || {
+                mrb_define_module_under(state, module, name.as_ptr())
+            });
+            nested = Some(module);
+            Ok(())
+        });
+
+        if let Some(module) = nested {
+            let mut builder = ModuleBuilder {
+                state: self.state,
+                module,
+                error: None,
+            };
+
+            define(&mut builder);
+            self.error = builder.error;
+        }
+
+        self
+    }
+
+    /// Runs `definition` unless an earlier one failed, recording the exception it raises or
+    /// returns, if any, as `class::Builder` does.
+    fn define<F>(&mut self, definition: F) -> &mut Self
+    where
+        F: FnOnce(*mut mrb_state, *mut RClass) -> Result<(), RubyException>,
+    {
+        if self.error.is_none() {
+            let (state, module) = (self.state, self.module);
+            let result = unsafe { catch_raised(state, || definition(state, module)) };
+            self.error = result.and_then(|result| result).err();
+        }
+
+        self
+    }
+}
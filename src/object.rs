@@ -1,3 +1,15 @@
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+use mruby_sys::{mrb_state, mrb_sym, mrb_value};
+
+use crate::class::{Builder, Class};
+use crate::de::{Deserializer, FromValue};
+use crate::error::EvalError;
+use crate::function::{call, catch_raised, convert_result, protect};
+use crate::ser::{Serializer, ToArgs, ToValue};
+use crate::value::Value;
+
 /// Type containing a dynamic mruby class instance with runtime reflection.
 ///
 /// This type is useful for interacting with mruby classes that don't have an equivalent Rust class
@@ -5,5 +17,191 @@
 /// pretty easy to work with. If you're trying to deserialize something and you're not sure what
 /// static type to specify, just make it "Object" and it will allow you to query the name, parent
 /// class name, methods, constants, instance variables, etc. at runtime.
-#[derive(Debug)]
-pub struct Object;
+#[derive(Clone, Debug)]
+pub struct Object<'mrb> {
+    state: *mut mrb_state,
+    value: Value<'mrb>,
+}
+
+impl<'mrb> Object<'mrb> {
+    pub(crate) const fn new(state: *mut mrb_state, value: Value<'mrb>) -> Self {
+        Object { state, value }
+    }
+
+    /// Returns the underlying Ruby value.
+    pub const fn as_value(&self) -> &Value<'mrb> {
+        &self.value
+    }
+
+    /// Returns the name of the object's class, e.g. `"String"`.
+    ///
+    /// This is empty if the name cannot be built, e.g. when the memory limit is reached.
+    pub fn class_name(&self) -> String {
+        use mruby_sys::mrb_obj_classname;
+
+        unsafe {
+            match protect(self.state, || {
+                mrb_obj_classname(self.state, *self.value.get_ref())
+            }) {
+                Ok(name) => CStr::from_ptr(name).to_string_lossy().into_owned(),
+                Err(_) => String::new(),
+            }
+        }
+    }
+
+    /// Returns the name of the superclass of the object's class, or `None` for `BasicObject`.
+    pub fn superclass_name(&self) -> Option<String> {
+        use mruby_sys::{mrb_class_name, mrb_class_real, mrb_obj_class};
+
+        unsafe {
+            let class = mrb_obj_class(self.state, *self.value.get_ref());
+            let parent = mrb_class_real((*class).super_);
+            if parent.is_null() {
+                return None;
+            }
+
+            let name = protect(self.state, || mrb_class_name(self.state, parent)).ok()?;
+            Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+        }
+    }
+
+    /// Returns the names of the instance variables set on the object, including the leading `@`.
+    pub fn instance_variables(&self) -> Vec<String> {
+        use mruby_sys::mrb_obj_instance_variables;
+
+        unsafe {
+            let names = protect(self.state, || {
+                mrb_obj_instance_variables(self.state, *self.value.get_ref())
+            });
+            names
+                .ok()
+                .and_then(|names| Vec::from_value(Deserializer::new(self.state, names)).ok())
+                .unwrap_or_default()
+        }
+    }
+
+    /// Returns the names of the public methods the object responds to.
+    ///
+    /// This relies on `Object#methods` from `mruby-metaprog`, so it is empty without the
+    /// `gem-metaprog` feature.
+    pub fn methods(&self) -> Vec<String> {
+        self.reflect(*self.value.get_ref(), "methods")
+    }
+
+    /// Returns the names of the constants defined on the object if it is a class or module, or
+    /// on its class otherwise.
+    ///
+    /// Like `methods()`, this is empty without the `gem-metaprog` feature.
+    pub fn constants(&self) -> Vec<String> {
+        use mruby_sys::{mrb_ext_class_value, mrb_obj_class, MRB_TT_CLASS, MRB_TT_MODULE};
+
+        let value = *self.value.get_ref();
+        let module = match value.tt {
+            MRB_TT_CLASS | MRB_TT_MODULE => value,
+            _ => unsafe { mrb_ext_class_value(mrb_obj_class(self.state, value)) },
+        };
+
+        self.reflect(module, "constants")
+    }
+
+    /// Returns whether the object responds to the method `name`.
+    pub fn respond_to(&self, name: &str) -> bool {
+        use mruby_sys::mrb_respond_to;
+
+        unsafe {
+            match lookup_symbol(self.state, name) {
+                Some(sym) => mrb_respond_to(self.state, *self.value.get_ref(), sym) != 0,
+                None => false,
+            }
+        }
+    }
+
+    /// Returns the instance variable `name`, which may be given with or without the leading `@`.
+    ///
+    /// Instance variables which are not set are `nil`.
+    pub fn get_ivar<T: FromValue<'mrb>>(&self, name: &str) -> Result<T, EvalError> {
+        use mruby_sys::{mrb_ext_nil_value, mrb_gc_protect, mrb_iv_get};
+
+        let name = ivar_name(name);
+        unsafe {
+            // NOTE: Like the result of a method call, the value is kept in the arena while it is
+            // converted, since the instance variable may be reassigned in the meantime.
+            let receiver = *self.value.get_ref();
+            let value = protect(self.state, || {
+                let value = match lookup_symbol(self.state, &name) {
+                    Some(sym) => mrb_iv_get(self.state, receiver, sym),
+                    None => mrb_ext_nil_value(),
+                };
+                mrb_gc_protect(self.state, value);
+                value
+            })?;
+            convert_result(self.state, value)
+        }
+    }
+
+    /// Sets the instance variable `name`, which may be given with or without the leading `@`.
+    ///
+    /// Fails if the object is frozen or cannot have instance variables, e.g. if it is an integer.
+    pub fn set_ivar<V: ToValue>(&self, name: &str, value: V) -> Result<(), EvalError> {
+        use mruby_sys::{mrb_intern, mrb_iv_name_sym_check, mrb_iv_set};
+
+        let name = ivar_name(name);
+        unsafe {
+            let receiver = *self.value.get_ref();
+            let value = catch_raised(self.state, || {
+                value.to_value(Serializer::new(self.state)).into_inner()
+            })?;
+            protect(self.state, || {
+                let sym = mrb_intern(self.state, name.as_ptr() as *const c_char, name.len());
+                mrb_iv_name_sym_check(self.state, sym);
+                mrb_iv_set(self.state, receiver, sym, value);
+            })?;
+            Ok(())
+        }
+    }
+
+    /// Calls the method `method` on the object, as with `Mruby::call()`.
+    pub fn call<R, A>(&self, method: &str, args: A) -> Result<R, EvalError>
+    where
+        R: FromValue<'mrb>,
+        A: ToArgs,
+    {
+        let result = unsafe { call(self.state, *self.value.get_ref(), method, args)? };
+        result.map_err(EvalError::from)
+    }
+
+    fn reflect(&self, receiver: mrb_value, method: &str) -> Vec<String> {
+        let result = unsafe { call(self.state, receiver, method, ()) };
+        result.ok().and_then(Result::ok).unwrap_or_default()
+    }
+}
+
+impl<'mrb> Class for Object<'mrb> {
+    const NAME: &'static str = "Object";
+    const PARENT: Option<&'static str> = None;
+
+    fn define(_: &mut Builder) {}
+}
+
+/// Returns the symbol for `name` if it has been interned already.
+///
+/// Unlike `mrb_intern()`, this never allocates, so it cannot raise. A name which was never interned
+/// cannot be the name of a method or an instance variable either.
+unsafe fn lookup_symbol(state: *mut mrb_state, name: &str) -> Option<mrb_sym> {
+    use mruby_sys::{mrb_check_intern, mrb_ext_symbol_to_sym, MRB_TT_SYMBOL};
+
+    let sym = mrb_check_intern(state, name.as_ptr() as *const c_char, name.len());
+    if sym.tt == MRB_TT_SYMBOL {
+        Some(mrb_ext_symbol_to_sym(sym))
+    } else {
+        None
+    }
+}
+
+fn ivar_name(name: &str) -> String {
+    if name.starts_with('@') {
+        name.to_owned()
+    } else {
+        format!("@{}", name)
+    }
+}
@@ -0,0 +1,206 @@
+use std::io::{self, Write};
+use std::slice;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use mruby_sys::{mrb_int, mrb_state, mrb_value, RClass};
+
+use crate::error::RubyException;
+use crate::function::{catch_raised, funcall, new_exception, raising, Function};
+use crate::userdata::UserData;
+
+/// A standard output stream of an interpreter.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// An in-memory output sink which can be read back after running code, e.g. in tests.
+///
+/// Clones share the same buffer, so one clone can be passed to `Mruby::set_stdout()` while
+/// another is kept to read the output.
+///
+/// # Example
+///
+/// ```rust
+/// # use mruby::{Capture, Mruby};
+/// # fn main() -> Result<(), mruby::EvalError> {
+/// # let mut ruby = Mruby::new().unwrap();
+/// # if !ruby.eval::<bool>("respond_to?(:puts, true)")? {
+/// #     return Ok(()); // `puts` needs the `gem-print` feature.
+/// # }
+/// let output = Capture::new();
+/// ruby.set_stdout(Box::new(output.clone()))?;
+/// ruby.eval::<()>("puts 'hello'")?;
+/// assert_eq!(output.to_string_lossy(), "hello\n");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Capture {
+    /// Creates an empty capture buffer.
+    pub fn new() -> Self {
+        Capture::default()
+    }
+
+    /// Returns a copy of everything written so far.
+    pub fn contents(&self) -> Vec<u8> {
+        self.buffer().clone()
+    }
+
+    /// Returns everything written so far as a string, replacing invalid UTF-8 sequences.
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.buffer()).into_owned()
+    }
+
+    /// Discards everything written so far.
+    pub fn clear(&self) {
+        self.buffer().clear();
+    }
+
+    fn buffer(&self) -> MutexGuard<'_, Vec<u8>> {
+        // NOTE: Writing to a `Vec` cannot panic halfway, so a poisoned buffer is still intact.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sends everything Ruby code writes to `stream` to `sink` from now on.
+///
+/// The Ruby methods writing to the stream are replaced the first time it is redirected. Later
+/// calls only swap the sink.
+pub(crate) unsafe fn redirect(
+    state: *mut mrb_state,
+    stream: Stream,
+    sink: Box<dyn Write + Send>,
+) -> Result<(), RubyException> {
+    if UserData::get(state).output(stream).is_none() {
+        catch_raised(state, || install(state, stream))?;
+    }
+
+    UserData::get(state).set_output(stream, sink);
+    Ok(())
+}
+
+/// Replaces the methods writing to the process' standard output or error.
+///
+/// `mruby-print` implements `print`, `puts` and `p` with `Kernel#__printstr__`, which is replaced
+/// for the standard output. With `mruby-io`, `Kernel#print` and `Kernel#puts` are implemented with
+/// `$stdout.write` instead, so `write` and `flush` are also replaced on the `STDOUT` and `STDERR`
+/// objects, if they exist. Other `IO` objects are unaffected.
+unsafe fn install(state: *mut mrb_state, stream: Stream) {
+    use mruby_sys::mrb_singleton_class;
+    use mruby_sys::{mrb_const_defined, mrb_const_get, mrb_ext_class_value, mrb_intern};
+
+    if stream == Stream::Stdout {
+        let printstr = Function::from_callback(|state, _this, args| {
+            let arg = single_arg(state, args)?;
+            if arg.tt == mruby_sys::MRB_TT_STRING {
+                write(state, Stream::Stdout, string_bytes(state, arg))?;
+            }
+
+            Ok(arg)
+        });
+
+        define(state, (*state).kernel_module, "__printstr__", printstr);
+    }
+
+    let name = match stream {
+        Stream::Stdout => "STDOUT",
+        Stream::Stderr => "STDERR",
+    };
+
+    let object = mrb_ext_class_value((*state).object_class);
+    let sym = raising(state, || {
+        mrb_intern(state, name.as_ptr() as *const _, name.len())
+    });
+    if mrb_const_defined(state, object, sym) == 0 {
+        return;
+    }
+
+    let singleton = raising(state, || {
+        let io = mrb_const_get(state, object, sym);
+        mrb_singleton_class(state, io).value.p as *mut RClass
+    });
+
+    let write_method = Function::from_callback(move |state, _this, args| {
+        use mruby_sys::{mrb_ext_fixnum_value, MRB_TT_STRING};
+
+        let mut arg = single_arg(state, args)?;
+        if arg.tt != MRB_TT_STRING {
+            arg = funcall(state, arg, "to_s", &[])
+                .map_err(|exc| new_exception(state, "TypeError", exc.message()))?;
+        }
+
+        let bytes = string_bytes(state, arg);
+        write(state, stream, bytes)?;
+        Ok(mrb_ext_fixnum_value(bytes.len() as mrb_int))
+    });
+
+    let flush_method = Function::from_callback(move |state, this, _args| {
+        match UserData::get(state).output(stream) {
+            Some(sink) => sink.flush(),
+            None => Ok(()),
+        }
+        .map_err(|e| new_exception(state, "IOError", e))?;
+
+        Ok(this)
+    });
+
+    define(state, singleton, "write", write_method);
+    define(state, singleton, "flush", flush_method);
+}
+
+unsafe fn define(state: *mut mrb_state, class: *mut RClass, name: &str, func: Function) {
+    let func = UserData::get(state).add_function(func);
+    raising(state, || func.define(state, class, name));
+}
+
+unsafe fn single_arg(state: *mut mrb_state, args: &[mrb_value]) -> Result<mrb_value, mrb_value> {
+    match *args {
+        [arg] => Ok(arg),
+        _ => {
+            let msg = format!("wrong number of arguments ({} for 1)", args.len());
+            Err(new_exception(state, "ArgumentError", msg))
+        }
+    }
+}
+
+/// Returns the contents of the Ruby string `string`, which must not be modified or freed while
+/// the returned slice is in use.
+unsafe fn string_bytes<'a>(state: *mut mrb_state, string: mrb_value) -> &'a [u8] {
+    use mruby_sys::{mrb_string_value_len, mrb_string_value_ptr};
+
+    let ptr = mrb_string_value_ptr(state, string) as *const u8;
+    let len = mrb_string_value_len(state, string) as usize;
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr, len)
+    }
+}
+
+/// Writes `bytes` to the sink of `stream`, raising `IOError` if that fails.
+unsafe fn write(state: *mut mrb_state, stream: Stream, bytes: &[u8]) -> Result<(), mrb_value> {
+    let result = match UserData::get(state).output(stream) {
+        Some(sink) => sink.write_all(bytes),
+        None => match stream {
+            Stream::Stdout => io::stdout().write_all(bytes),
+            Stream::Stderr => io::stderr().write_all(bytes),
+        },
+    };
+
+    result.map_err(|e| new_exception(state, "IOError", e))
+}
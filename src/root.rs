@@ -0,0 +1,124 @@
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::mem;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+use mruby_sys::{mrb_state, mrb_value};
+
+use crate::arena;
+use crate::function::raising;
+use crate::userdata::UserData;
+use crate::value::Value;
+use crate::Mruby;
+
+/// A Ruby value which is kept alive by the garbage collector for as long as the handle exists.
+///
+/// Plain `Value`s are only protected by the GC arena, which is released at the end of
+/// `Mruby::arena_scope()` and when a Rust function called from Ruby returns, so the objects they
+/// refer to may be freed as soon as Ruby code runs after that. A `Root` can be stored anywhere,
+/// e.g. in a map of callbacks, and passed to later calls into Ruby.
+///
+/// Roots are created by converting a Ruby value into `Root`:
+///
+/// ```rust
+/// # use mruby::{Mruby, Root};
+/// # fn main() -> Result<(), mruby::EvalError> {
+/// # let mut ruby = Mruby::new().unwrap();
+/// let callback: Root = ruby.eval("Proc.new { |x| x * 2 }")?;
+/// let result: i64 = ruby.call(&callback, "call", (21,))?;
+/// assert_eq!(result, 42);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Dropping a root does not touch the interpreter, so it may happen on any thread and after the
+/// interpreter is gone. The value is released the next time the interpreter runs Ruby code.
+pub struct Root {
+    value: mrb_value,
+    roots: Arc<Roots>,
+}
+
+impl Root {
+    /// Registers `value` with the garbage collector of `state`.
+    pub(crate) unsafe fn new(state: *mut mrb_state, value: mrb_value) -> Self {
+        use mruby_sys::mrb_gc_register;
+
+        release_dropped(state);
+        raising(state, || mrb_gc_register(state, value));
+        Root {
+            value,
+            roots: UserData::get(state).roots().clone(),
+        }
+    }
+
+    /// Returns the rooted value, which is kept in the GC arena like any other value handed out by
+    /// `ruby`, so it stays valid even if the root is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the root was created by a different interpreter.
+    pub fn get<'mrb>(&self, ruby: &'mrb Mruby) -> Value<'mrb> {
+        unsafe {
+            let value = self.value_in(ruby.state);
+            arena::keep(ruby.state, *value.get_ref());
+            value
+        }
+    }
+
+    /// Returns the rooted value for use with `state`, panicking if it belongs to another one.
+    pub(crate) unsafe fn value_in<'mrb>(&self, state: *mut mrb_state) -> Value<'mrb> {
+        assert!(
+            Arc::ptr_eq(&self.roots, UserData::get(state).roots()),
+            "rooted value belongs to a different interpreter"
+        );
+
+        Value::new(state, self.value)
+    }
+}
+
+impl Debug for Root {
+    fn fmt(&self, fmt: &mut Formatter) -> FmtResult {
+        fmt.debug_struct(stringify!(Root))
+            .field("value", &Value::new(ptr::null_mut(), self.value))
+            .finish()
+    }
+}
+
+impl Drop for Root {
+    fn drop(&mut self) {
+        self.roots.dropped().push(Released(self.value));
+    }
+}
+
+// NOTE: The rooted value is only ever dereferenced through its interpreter, which requires access
+// to the `Mruby` instance on its own thread.
+unsafe impl Send for Root {}
+
+/// Values of dropped `Root`s which are still registered with the garbage collector, shared between
+/// an interpreter and its roots.
+#[derive(Debug, Default)]
+pub(crate) struct Roots {
+    dropped: Mutex<Vec<Released>>,
+}
+
+impl Roots {
+    fn dropped(&self) -> std::sync::MutexGuard<'_, Vec<Released>> {
+        // NOTE: Pushing to or draining a `Vec` cannot panic halfway, so a poisoned list is intact.
+        self.dropped.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Debug)]
+struct Released(mrb_value);
+
+unsafe impl Send for Released {}
+
+/// Unregisters the values of all roots dropped since the last call from the garbage collector.
+pub(crate) unsafe fn release_dropped(state: *mut mrb_state) {
+    use mruby_sys::mrb_gc_unregister;
+
+    let dropped = mem::take(&mut *UserData::get(state).roots().dropped());
+    for Released(value) in dropped {
+        mrb_gc_unregister(state, value);
+    }
+}
@@ -0,0 +1,295 @@
+use std::ffi::CStr;
+use std::ptr;
+
+use mruby_sys::{mrb_aspec, mrb_func_t, mrb_state, mrb_sym, mrb_value, RClass};
+
+use crate::de::{Deserializer, FromValue};
+use crate::error::RubyException;
+use crate::function::{funcall, new_exception, raising};
+use crate::symbol::Symbol;
+use crate::userdata::UserData;
+
+/// Equivalent of `MRB_ARGS_ANY()`, accepting any number of arguments.
+const ARGS_ANY: mrb_aspec = 1 << 12;
+
+/// Name of the exception class raised by methods disabled in a sandbox.
+const SECURITY_ERROR_CLASS: &str = "SecurityError";
+
+/// Classes and modules which give access to files, sockets and processes.
+const IO_CONSTANTS: &[&str] = &[
+    "IO",
+    "File",
+    "FileTest",
+    "BasicSocket",
+    "IPSocket",
+    "TCPSocket",
+    "TCPServer",
+    "UDPSocket",
+    "UNIXSocket",
+    "UNIXServer",
+    "Socket",
+    "Addrinfo",
+];
+
+/// `Kernel` methods which open files or run commands.
+const IO_METHODS: &[&str] = &["open", "`", "system"];
+
+/// `Kernel` methods which evaluate strings as code.
+const EVAL_METHODS: &[&str] = &["eval"];
+
+/// `Kernel` methods which terminate the process.
+const EXIT_METHODS: &[&str] = &["exit", "exit!", "abort"];
+
+/// Objects which give access to environment variables.
+const ENV_CONSTANTS: &[&str] = &["ENV"];
+
+/// Ruby features which a sandboxed interpreter may use, passed to `Mruby::sandboxed()`.
+///
+/// The default value allows nothing, so new capabilities are denied unless explicitly allowed:
+///
+/// ```rust
+/// # use mruby::{Capabilities, Mruby};
+/// let ruby = Mruby::sandboxed(Capabilities { eval: true, ..Default::default() }).unwrap();
+/// assert!(!ruby.capabilities().io);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Capabilities {
+    /// Allows opening files and sockets and running commands, e.g. with `File`, `IO.popen`,
+    /// `Socket` or `Kernel#open`.
+    ///
+    /// Writing to the standard streams with `puts`, `print` or `STDOUT` is always allowed.
+    pub io: bool,
+    /// Allows evaluating strings as code with `eval` and `instance_eval`. Blocks can always be
+    /// passed to `instance_eval`.
+    pub eval: bool,
+    /// Allows ending the script early with `exit` and `exit!`, which raise `SystemExit` and make
+    /// the call into Ruby return `EvalError::Exit`. The process itself is never terminated.
+    pub exit: bool,
+    /// Allows reading and changing environment variables through `ENV`.
+    pub env: bool,
+}
+
+impl Capabilities {
+    /// Returns capabilities allowing everything, as with an interpreter which is not sandboxed.
+    pub fn all() -> Self {
+        Capabilities {
+            io: true,
+            eval: true,
+            exit: true,
+            env: true,
+        }
+    }
+
+    /// Returns capabilities allowing nothing.
+    pub fn none() -> Self {
+        Capabilities::default()
+    }
+}
+
+/// Sandbox state of an interpreter created with `Mruby::sandboxed()`.
+#[derive(Debug)]
+pub(crate) struct Sandbox {
+    capabilities: Capabilities,
+    /// The implementation of `BasicObject#instance_eval` replaced by the sandbox, if any.
+    instance_eval: mrb_func_t,
+}
+
+impl Sandbox {
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+}
+
+/// Replaces the methods giving access to the features not allowed by `capabilities` with methods
+/// raising `SecurityError`.
+///
+/// This must be done before running any Ruby code, which could otherwise keep references to the
+/// original methods, e.g. with `alias`.
+pub(crate) unsafe fn restrict(
+    state: *mut mrb_state,
+    capabilities: Capabilities,
+) -> Result<(), RubyException> {
+    use mruby_sys::mrb_define_class;
+
+    let name = format!("{}\0", SECURITY_ERROR_CLASS);
+    raising(state, || {
+        mrb_define_class(state, name.as_ptr() as *const _, (*state).eException_class)
+    });
+
+    let mut instance_eval = None;
+    if !capabilities.io {
+        IO_CONSTANTS
+            .iter()
+            .try_for_each(|name| deny_constant(state, name))?;
+        IO_METHODS
+            .iter()
+            .for_each(|name| deny_kernel_method(state, name));
+    }
+
+    if !capabilities.eval {
+        EVAL_METHODS
+            .iter()
+            .for_each(|name| deny_kernel_method(state, name));
+        instance_eval = replace_instance_eval(state);
+    }
+
+    if !capabilities.exit {
+        EXIT_METHODS
+            .iter()
+            .for_each(|name| deny_kernel_method(state, name));
+    }
+
+    if !capabilities.env {
+        ENV_CONSTANTS
+            .iter()
+            .try_for_each(|name| deny_constant(state, name))?;
+    }
+
+    let sandbox = Sandbox {
+        capabilities,
+        instance_eval,
+    };
+
+    UserData::get(state).set_sandbox(sandbox);
+    Ok(())
+}
+
+/// Denies the `Kernel` method `name`, which may be called both with and without a receiver.
+unsafe fn deny_kernel_method(state: *mut mrb_state, name: &str) {
+    let kernel = (*state).kernel_module;
+    deny_method(state, kernel, intern(state, name));
+    deny_method(
+        state,
+        singleton_class(state, class_value(kernel)),
+        intern(state, name),
+    );
+}
+
+/// Denies all class methods of the class or module bound to the constant `name`, along with
+/// `new`, `allocate` and `initialize`, if the constant is defined.
+///
+/// Existing instances, such as `STDOUT`, can still be used.
+unsafe fn deny_constant(state: *mut mrb_state, name: &str) -> Result<(), RubyException> {
+    use mruby_sys::{mrb_const_defined, mrb_const_get, MRB_TT_CLASS, MRB_TT_MODULE};
+
+    let object = class_value((*state).object_class);
+    let sym = intern(state, name);
+    if mrb_const_defined(state, object, sym) == 0 {
+        return Ok(());
+    }
+
+    let value = raising(state, || mrb_const_get(state, object, sym));
+    let singleton = singleton_class(state, value);
+    let methods = funcall(state, value, "singleton_methods", &[])?;
+    let methods = Vec::<Symbol>::from_value(Deserializer::new(state, methods))
+        .expect("`singleton_methods` returns an array of symbols");
+
+    for method in &methods {
+        deny_method(state, singleton, intern(state, method.as_ref()));
+    }
+
+    if value.tt == MRB_TT_CLASS || value.tt == MRB_TT_MODULE {
+        let class = value.value.p as *mut RClass;
+        deny_method(state, singleton, intern(state, "new"));
+        deny_method(state, singleton, intern(state, "allocate"));
+        deny_method(state, class, intern(state, "initialize"));
+    }
+
+    Ok(())
+}
+
+/// Replaces `BasicObject#instance_eval` with a version which only accepts a block, returning the
+/// original implementation.
+unsafe fn replace_instance_eval(state: *mut mrb_state) -> mrb_func_t {
+    use mruby_sys::{mrb_class_get, mrb_define_method_id, mrb_method_search};
+
+    let basic_object = raising(state, || {
+        mrb_class_get(state, b"BasicObject\0".as_ptr() as *const _)
+    });
+    let sym = intern(state, "instance_eval");
+    let method = raising(state, || mrb_method_search(state, basic_object, sym));
+    if method.func_p == 0 {
+        return None;
+    }
+
+    raising(state, || {
+        mrb_define_method_id(state, basic_object, sym, Some(instance_eval), ARGS_ANY)
+    });
+    method.__bindgen_anon_1.func
+}
+
+unsafe fn deny_method(state: *mut mrb_state, class: *mut RClass, sym: mrb_sym) {
+    use mruby_sys::mrb_define_method_id;
+
+    raising(state, || {
+        mrb_define_method_id(state, class, sym, Some(deny), ARGS_ANY)
+    });
+}
+
+unsafe fn intern(state: *mut mrb_state, name: &str) -> mrb_sym {
+    use mruby_sys::mrb_intern;
+
+    raising(state, || {
+        mrb_intern(state, name.as_ptr() as *const _, name.len())
+    })
+}
+
+unsafe fn class_value(class: *mut RClass) -> mrb_value {
+    use mruby_sys::mrb_ext_class_value;
+
+    mrb_ext_class_value(class)
+}
+
+unsafe fn singleton_class(state: *mut mrb_state, value: mrb_value) -> *mut RClass {
+    use mruby_sys::mrb_singleton_class;
+
+    raising(state, || mrb_singleton_class(state, value)).value.p as *mut RClass
+}
+
+/// Creates the `SecurityError` raised when calling the method currently being executed.
+unsafe fn security_error(state: *mut mrb_state) -> mrb_value {
+    use mruby_sys::mrb_sym2name;
+
+    let mid = (*(*(*state).c).ci).mid;
+    let name = CStr::from_ptr(mrb_sym2name(state, mid)).to_string_lossy();
+    let msg = format!("`{}' is not allowed in this sandbox", name);
+    new_exception(state, SECURITY_ERROR_CLASS, msg)
+}
+
+/// Implementation of all methods denied by the sandbox.
+///
+/// NOTE: Raising unwinds the stack with `longjmp()`, so this must not hold any Rust values which
+/// need to be dropped. The same applies to `instance_eval()` below.
+unsafe extern "C" fn deny(state: *mut mrb_state, _this: mrb_value) -> mrb_value {
+    use mruby_sys::mrb_exc_raise;
+
+    mrb_exc_raise(state, security_error(state));
+    unreachable!("mrb_exc_raise() returned")
+}
+
+/// Implementation of `BasicObject#instance_eval` which raises `SecurityError` unless a block is
+/// given, and otherwise calls the original implementation.
+unsafe extern "C" fn instance_eval(state: *mut mrb_state, this: mrb_value) -> mrb_value {
+    use mruby_sys::{mrb_ext_is_value_nil, mrb_ext_nil_value, mrb_get_args, mrb_int};
+
+    let mut argv: *mut mrb_value = ptr::null_mut();
+    let mut argc: mrb_int = 0;
+    let mut block = mrb_ext_nil_value();
+    mrb_get_args(
+        state,
+        b"*!&\0".as_ptr() as *const _,
+        &mut argv,
+        &mut argc,
+        &mut block,
+    );
+
+    let original = match UserData::get(state).sandbox() {
+        Some(sandbox) => sandbox.instance_eval,
+        None => None,
+    };
+
+    match original {
+        Some(original) if mrb_ext_is_value_nil(block) == 0 => original(state, this),
+        _ => deny(state, this),
+    }
+}
@@ -1,4 +1,4 @@
-pub use self::serializer::{ArraySerializer, Serializer};
+pub use self::serializer::{ArgsSerializer, ArraySerializer, Serializer};
 
 use std::borrow::Cow;
 use std::cell::{Cell, RefCell};
@@ -9,55 +9,78 @@ use std::sync::Arc;
 
 use mruby_sys::{mrb_float, mrb_int};
 
+use crate::object::Object;
+use crate::root::Root;
 use crate::symbol::Symbol;
 use crate::value::Value;
 
 mod serializer;
 
 pub trait ToValue {
-    fn to_value(&self, ser: Serializer) -> Value;
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb>;
 }
 
-impl ToValue for Value {
-    fn to_value(&self, _: Serializer) -> Value {
-        self.clone()
+/// A list of arguments which can be passed to a Ruby method.
+///
+/// This is implemented for `()` and tuples of `ToValue` types, where each tuple element becomes
+/// one positional argument, as well as for slices and vectors of arguments whose length is only
+/// known at runtime.
+pub trait ToArgs {
+    fn to_args<'mrb>(&self, ser: Serializer<'mrb>) -> Vec<Value<'mrb>>;
+}
+
+impl<'a> ToValue for Value<'a> {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
+        ser.serialize_value(self)
+    }
+}
+
+impl<'a> ToValue for Object<'a> {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
+        ser.serialize_value(self.as_value())
+    }
+}
+
+impl ToValue for Root {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
+        ser.serialize_root(self)
     }
 }
 
 impl ToValue for Symbol {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_symbol(self)
     }
 }
 
 impl ToValue for () {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_undef()
     }
 }
 
 impl ToValue for bool {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_bool(*self)
     }
 }
 
 impl ToValue for char {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_char(*self)
     }
 }
 
 #[cfg(not(feature = "disable-floats"))]
 impl ToValue for f32 {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_float(mrb_float::from(*self))
     }
 }
 
 #[cfg(all(not(feature = "disable-floats"), not(feature = "use-f32")))]
 impl ToValue for f64 {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_float(*self)
     }
 }
@@ -66,7 +89,7 @@ macro_rules! impl_value_integer {
     ( $($ty:ident)* ) => {
         $(
             impl ToValue for $ty {
-                fn to_value(&self, ser: Serializer) -> Value {
+                fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
                     ser.serialize_integer(*self as mrb_int)
                 }
             }
@@ -78,13 +101,13 @@ impl_value_integer!(i8 i32 i64 isize);
 impl_value_integer!(u8 u32 u64 usize);
 
 impl ToValue for str {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_string(self)
     }
 }
 
 impl ToValue for String {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_string(self)
     }
 }
@@ -93,13 +116,13 @@ impl<'a, T> ToValue for Cow<'a, T>
 where
     T: ToOwned + ToValue + ?Sized + 'a,
 {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         T::to_value(self.as_ref(), ser)
     }
 }
 
 impl<T: ToValue> ToValue for Option<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         if let Some(ref inner) = *self {
             inner.to_value(ser)
         } else {
@@ -109,67 +132,67 @@ impl<T: ToValue> ToValue for Option<T> {
 }
 
 impl<'a, T: ToValue + ?Sized + 'a> ToValue for &'a T {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         (*self).to_value(ser)
     }
 }
 
-impl<'a, T: ToValue + ?Sized + 'a> ToValue for Arc<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+impl<T: ToValue + ?Sized> ToValue for Arc<T> {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         self.as_ref().to_value(ser)
     }
 }
 
-impl<'a, T: ToValue + ?Sized + 'a> ToValue for Box<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+impl<T: ToValue + ?Sized> ToValue for Box<T> {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         self.as_ref().to_value(ser)
     }
 }
 
-impl<'a, T: ToValue + ?Sized + 'a> ToValue for Rc<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+impl<T: ToValue + ?Sized> ToValue for Rc<T> {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         self.as_ref().to_value(ser)
     }
 }
 
 impl<T: ToValue> ToValue for [T] {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_array(self)
     }
 }
 
 impl<T: ToValue> ToValue for BinaryHeap<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_array(self)
     }
 }
 
 impl<T: ToValue> ToValue for BTreeSet<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_array(self)
     }
 }
 
 impl<T: ToValue, S> ToValue for HashSet<T, S> {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_array(self)
     }
 }
 
 impl<T: ToValue> ToValue for LinkedList<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_array(self)
     }
 }
 
 impl<T: ToValue> ToValue for Vec<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_array(self)
     }
 }
 
 impl<T: ToValue> ToValue for VecDeque<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_array(self)
     }
 }
@@ -179,7 +202,7 @@ macro_rules! impl_value_array {
         $(
             impl<T: ToValue> ToValue for [T; $arity] {
                 #[allow(non_snake_case)]
-                fn to_value(&self, ser: Serializer) -> Value {
+                fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
                     (&self[..]).to_value(ser)
                 }
             }
@@ -198,7 +221,7 @@ macro_rules! impl_value_tuple {
             )*
         {
             #[allow(non_snake_case)]
-            fn to_value(&self, ser: Serializer) -> Value {
+            fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
                 let ($(ref $field,)*) = self;
                 ser.serialize_array_hetero()
                     $(.next_element($field))*
@@ -227,7 +250,7 @@ where
     K: ToValue + Ord,
     V: ToValue,
 {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_hash(self)
     }
 }
@@ -237,19 +260,78 @@ where
     K: ToValue + Eq + Hash,
     V: ToValue,
 {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         ser.serialize_hash(self)
     }
 }
 
 impl<T: ToValue + ?Sized> ToValue for Cell<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         (&self).to_value(ser)
     }
 }
 
 impl<T: ToValue + ?Sized> ToValue for RefCell<T> {
-    fn to_value(&self, ser: Serializer) -> Value {
+    fn to_value<'mrb>(&self, ser: Serializer<'mrb>) -> Value<'mrb> {
         self.borrow().to_value(ser)
     }
 }
+
+impl ToArgs for () {
+    fn to_args<'mrb>(&self, ser: Serializer<'mrb>) -> Vec<Value<'mrb>> {
+        ser.serialize_args().finish()
+    }
+}
+
+impl<T: ToValue> ToArgs for [T] {
+    fn to_args<'mrb>(&self, ser: Serializer<'mrb>) -> Vec<Value<'mrb>> {
+        self.iter()
+            .fold(ser.serialize_args(), |args, arg| args.next_arg(arg))
+            .finish()
+    }
+}
+
+impl<T: ToValue> ToArgs for Vec<T> {
+    fn to_args<'mrb>(&self, ser: Serializer<'mrb>) -> Vec<Value<'mrb>> {
+        self.as_slice().to_args(ser)
+    }
+}
+
+impl<'a, T: ToArgs + ?Sized + 'a> ToArgs for &'a T {
+    fn to_args<'mrb>(&self, ser: Serializer<'mrb>) -> Vec<Value<'mrb>> {
+        (*self).to_args(ser)
+    }
+}
+
+macro_rules! impl_args_tuple {
+    ( $($field:ident)+ ) => (
+        impl<$($field),*> ToArgs for ($($field,)*)
+        where
+            $(
+                $field: ToValue,
+            )*
+        {
+            #[allow(non_snake_case)]
+            fn to_args<'mrb>(&self, ser: Serializer<'mrb>) -> Vec<Value<'mrb>> {
+                let ($(ref $field,)*) = self;
+                ser.serialize_args()
+                    $(.next_arg($field))*
+                    .finish()
+            }
+        }
+    );
+}
+
+impl_args_tuple!(A);
+impl_args_tuple!(A B);
+impl_args_tuple!(A B C);
+impl_args_tuple!(A B C D);
+impl_args_tuple!(A B C D E);
+impl_args_tuple!(A B C D E F);
+impl_args_tuple!(A B C D E F G);
+impl_args_tuple!(A B C D E F G H);
+impl_args_tuple!(A B C D E F G H I);
+impl_args_tuple!(A B C D E F G H I J);
+impl_args_tuple!(A B C D E F G H I J K);
+impl_args_tuple!(A B C D E F G H I J K L);
+impl_args_tuple!(A B C D E F G H I J K L M);
@@ -1,72 +1,103 @@
 use std::ffi::CString;
+use std::marker::PhantomData;
 
-use mruby_sys::{mrb_ary_new_from_values, mrb_bool, mrb_float, mrb_int, mrb_state, mrb_value};
+use mruby_sys::{mrb_bool, mrb_float, mrb_int, mrb_state};
 
 use super::ToValue;
+use crate::arena::ArenaIndex;
 use crate::class::Class;
+use crate::data;
+use crate::function::raising;
+use crate::root::Root;
 use crate::symbol::ToSymbol;
 use crate::value::Value;
+use crate::Mruby;
 
 #[derive(Debug)]
-pub struct Serializer(*mut mrb_state);
+pub struct Serializer<'mrb> {
+    state: *mut mrb_state,
+    _marker: PhantomData<&'mrb Mruby>,
+}
 
-impl Serializer {
+impl<'mrb> Serializer<'mrb> {
     pub(crate) const fn new(state: *mut mrb_state) -> Self {
-        Serializer(state)
+        Serializer {
+            state,
+            _marker: PhantomData,
+        }
     }
 
-    pub fn serialize_array<T, U>(self, val: T) -> Value
+    /// Converts the elements of `val` into a new Ruby array.
+    ///
+    /// Each element is pushed into the array before the next one is converted, so only the array
+    /// itself is kept in the GC arena.
+    pub fn serialize_array<T, U>(self, val: T) -> Value<'mrb>
     where
         T: IntoIterator<Item = U>,
         U: ToValue,
     {
-        let Serializer(state) = self;
-        let array: Vec<mrb_value> = val
-            .into_iter()
-            .map(|v| v.to_value(Serializer(state)))
-            .map(Value::into_inner)
-            .collect();
+        use mruby_sys::{mrb_ary_new_capa, mrb_ary_push};
+
+        let state = self.state;
+        let iter = val.into_iter();
 
         unsafe {
-            let array = mrb_ary_new_from_values(state, array.len() as mrb_int, array.as_ptr());
-            Value(array)
+            let capa = iter.size_hint().0 as mrb_int;
+            let array = raising(state, || mrb_ary_new_capa(state, capa));
+            for elem in iter {
+                let arena = ArenaIndex::save(state);
+                let value = elem.to_value(Serializer::new(state)).into_inner();
+                raising(state, || mrb_ary_push(state, array, value));
+                arena.restore();
+            }
+
+            Value::new(state, array)
         }
     }
 
     #[inline]
-    pub fn serialize_array_hetero(self) -> ArraySerializer {
-        let Serializer(state) = self;
+    pub fn serialize_args(self) -> ArgsSerializer<'mrb> {
+        let state = self.state;
+        ArgsSerializer::new(state)
+    }
+
+    #[inline]
+    pub fn serialize_array_hetero(self) -> ArraySerializer<'mrb> {
+        let state = self.state;
         ArraySerializer::new(state)
     }
 
     #[inline]
-    pub fn serialize_bool(self, val: bool) -> Value {
+    pub fn serialize_bool(self, val: bool) -> Value<'mrb> {
         use mruby_sys::mrb_ext_bool_value;
-        unsafe { Value(mrb_ext_bool_value(val as mrb_bool)) }
+        unsafe { Value::new(self.state, mrb_ext_bool_value(val as mrb_bool)) }
     }
 
-    pub fn serialize_char(self, val: char) -> Value {
+    pub fn serialize_char(self, val: char) -> Value<'mrb> {
         let mut buf = [0u8; 2];
         let s = val.encode_utf8(&mut buf);
         s.to_value(self)
     }
 
     #[inline]
-    pub fn serialize_integer(self, val: mrb_int) -> Value {
+    pub fn serialize_integer(self, val: mrb_int) -> Value<'mrb> {
         use mruby_sys::mrb_ext_fixnum_value;
-        unsafe { Value(mrb_ext_fixnum_value(val)) }
+        unsafe { Value::new(self.state, mrb_ext_fixnum_value(val)) }
     }
 
     #[inline]
     #[cfg(not(feature = "disable-floats"))]
-    pub fn serialize_float(self, val: mrb_float) -> Value {
+    pub fn serialize_float(self, val: mrb_float) -> Value<'mrb> {
         use mruby_sys::mrb_ext_float_value;
 
-        let Serializer(state) = self;
-        unsafe { Value(mrb_ext_float_value(state, val)) }
+        let state = self.state;
+        unsafe { Value::new(state, mrb_ext_float_value(state, val)) }
     }
 
-    pub fn serialize_hash<M, K, V>(self, map: M) -> Value
+    /// Converts the entries of `map` into a new Ruby hash.
+    ///
+    /// Like `serialize_array()`, only the hash itself is kept in the GC arena.
+    pub fn serialize_hash<M, K, V>(self, map: M) -> Value<'mrb>
     where
         M: IntoIterator<Item = (K, V)>,
         K: ToValue,
@@ -74,90 +105,149 @@ impl Serializer {
     {
         use mruby_sys::{mrb_hash_new, mrb_hash_new_capa, mrb_hash_set};
 
-        let Serializer(state) = self;
+        let state = self.state;
         let iter = map.into_iter();
 
+        let size = iter.size_hint().1;
         let hash = unsafe {
-            if let (_, Some(size)) = iter.size_hint() {
-                mrb_hash_new_capa(state, size as mrb_int)
-            } else {
-                mrb_hash_new(state)
-            }
+            raising(state, || match size {
+                Some(size) => mrb_hash_new_capa(state, size as mrb_int),
+                None => mrb_hash_new(state),
+            })
         };
 
         for (key, value) in iter {
-            let k = key.to_value(Serializer(state)).into_inner();
-            let v = value.to_value(Serializer(state)).into_inner();
             unsafe {
-                mrb_hash_set(state, hash, k, v);
+                let arena = ArenaIndex::save(state);
+                let k = key.to_value(Serializer::new(state)).into_inner();
+                let v = value.to_value(Serializer::new(state)).into_inner();
+                raising(state, || mrb_hash_set(state, hash, k, v));
+                arena.restore();
             }
         }
 
-        Value(hash)
+        Value::new(state, hash)
     }
 
     #[inline]
-    pub fn serialize_nil(self) -> Value {
+    pub fn serialize_nil(self) -> Value<'mrb> {
         use mruby_sys::mrb_ext_nil_value;
-        unsafe { Value(mrb_ext_nil_value()) }
+        unsafe { Value::new(self.state, mrb_ext_nil_value()) }
+    }
+
+    /// Moves `obj` into a new Ruby instance of its class, which is defined first if necessary.
+    ///
+    /// The value is dropped when the Ruby object is garbage collected or the interpreter is
+    /// closed, whichever comes first.
+    pub fn serialize_object<T: Class + Send + 'static>(self, obj: T) -> Value<'mrb> {
+        let state = self.state;
+        unsafe { Value::new(state, data::wrap(state, obj)) }
     }
 
-    pub fn serialize_object<T: Class>(self, _obj: T) -> Value {
-        unimplemented!()
+    /// Returns `value` for use as part of another value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` belongs to a different interpreter.
+    pub fn serialize_value(self, value: &Value) -> Value<'mrb> {
+        value.assert_interpreter(self.state);
+        Value::new(self.state, *value.get_ref())
     }
 
-    pub fn serialize_string<S: AsRef<str>>(self, val: S) -> Value {
+    /// Returns the value kept alive by `root`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `root` was created by a different interpreter.
+    pub fn serialize_root(self, root: &Root) -> Value<'mrb> {
+        let state = self.state;
+        unsafe { root.value_in(state) }
+    }
+
+    pub fn serialize_string<S: AsRef<str>>(self, val: S) -> Value<'mrb> {
         use mruby_sys::mrb_str_new_cstr;
 
-        let Serializer(state) = self;
+        let state = self.state;
         let cstr = CString::new(val.as_ref()).expect("String contains null byte");
 
-        unsafe { Value(mrb_str_new_cstr(state, cstr.as_ptr())) }
+        unsafe {
+            let string = raising(state, || mrb_str_new_cstr(state, cstr.as_ptr()));
+            Value::new(state, string)
+        }
     }
 
-    pub fn serialize_symbol<T: ToSymbol>(self, sym: T) -> Value {
+    pub fn serialize_symbol<T: ToSymbol>(self, sym: T) -> Value<'mrb> {
         use mruby_sys::{mrb_ext_symbol_value, mrb_intern_cstr};
 
-        let Serializer(state) = self;
+        let state = self.state;
         let name = CString::new(sym.as_str()).expect("String contains null byte");
-        let symbol = unsafe { mrb_intern_cstr(state, name.as_ptr()) };
+        let symbol = unsafe { raising(state, || mrb_intern_cstr(state, name.as_ptr())) };
 
-        unsafe { Value(mrb_ext_symbol_value(symbol)) }
+        unsafe { Value::new(state, mrb_ext_symbol_value(symbol)) }
     }
 
     #[inline]
-    pub fn serialize_undef(self) -> Value {
+    pub fn serialize_undef(self) -> Value<'mrb> {
         use mruby_sys::mrb_ext_undef_value;
-        unsafe { Value(mrb_ext_undef_value()) }
+        unsafe { Value::new(self.state, mrb_ext_undef_value()) }
+    }
+}
+
+#[derive(Debug)]
+pub struct ArgsSerializer<'mrb> {
+    state: *mut mrb_state,
+    args: Vec<Value<'mrb>>,
+}
+
+impl<'mrb> ArgsSerializer<'mrb> {
+    fn new(state: *mut mrb_state) -> Self {
+        ArgsSerializer {
+            state,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn next_arg<T: ToValue>(mut self, arg: T) -> Self {
+        let ser = Serializer::new(self.state);
+        self.args.push(arg.to_value(ser));
+        self
+    }
+
+    #[inline]
+    pub fn finish(self) -> Vec<Value<'mrb>> {
+        self.args
     }
 }
 
 #[derive(Debug)]
-pub struct ArraySerializer {
+pub struct ArraySerializer<'mrb> {
     state: *mut mrb_state,
-    array: Value,
+    array: Value<'mrb>,
 }
 
-impl ArraySerializer {
+impl<'mrb> ArraySerializer<'mrb> {
     fn new(state: *mut mrb_state) -> Self {
         use mruby_sys::mrb_ary_new;
-        let array = unsafe { Value(mrb_ary_new(state)) };
+        let array = unsafe { Value::new(state, raising(state, || mrb_ary_new(state))) };
         ArraySerializer { state, array }
     }
 
     pub fn next_element<T: ToValue>(self, elem: T) -> Self {
         use mruby_sys::mrb_ary_push;
 
-        let ser = Serializer(self.state);
-        let value = elem.to_value(ser).into_inner();
+        let ser = Serializer::new(self.state);
         unsafe {
-            mrb_ary_push(self.state, *self.array.get_ref(), value);
+            let arena = ArenaIndex::save(self.state);
+            let value = elem.to_value(ser).into_inner();
+            let array = *self.array.get_ref();
+            raising(self.state, || mrb_ary_push(self.state, array, value));
+            arena.restore();
             self
         }
     }
 
     #[inline]
-    pub fn finish(self) -> Value {
+    pub fn finish(self) -> Value<'mrb> {
         self.array
     }
 }
@@ -46,7 +46,7 @@ impl Display for Symbol {
 
 impl Debug for Symbol {
     fn fmt(&self, fmt: &mut Formatter) -> FmtResult {
-        write!(fmt, "{}({})", stringify!(Symbol), self.to_string())
+        write!(fmt, "{}({})", stringify!(Symbol), self)
     }
 }
 
@@ -67,7 +67,7 @@ impl Display for InvalidSymbolError {
 
 impl Error for InvalidSymbolError {}
 
-pub trait FromSymbol<'a>: FromValue {
+pub trait FromSymbol<'a>: FromValue<'a> {
     fn from_name(s: &'a str) -> Result<Self, InvalidSymbolError>;
 }
 
@@ -92,7 +92,7 @@ impl<'a> FromSymbol<'a> for String {
 impl<'a, T> FromSymbol<'a> for Cow<'a, T>
 where
     T: FromSymbol<'a> + ToOwned + 'a,
-    Self: From<T> + FromValue,
+    Self: From<T> + FromValue<'a>,
 {
     fn from_name(s: &'a str) -> Result<Self, InvalidSymbolError> {
         T::from_name(s).map(Cow::from)
@@ -121,11 +121,11 @@ impl ToSymbol for str {
 
 impl ToSymbol for String {
     fn as_str(&self) -> &str {
-        &self
+        self
     }
 }
 
-impl<'a, T> ToSymbol for &'a T
+impl<T> ToSymbol for &T
 where
     T: ToSymbol + ?Sized,
 {
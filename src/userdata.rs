@@ -0,0 +1,208 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::io::Write;
+use std::sync::Arc;
+
+use mruby_sys::{mrb_state, RClass};
+
+use crate::arena::Kept;
+use crate::data::DataType;
+use crate::error::Abort;
+use crate::function::Function;
+use crate::interrupt::Interrupt;
+use crate::limit::ExecutionLimit;
+use crate::memory::Allocator;
+use crate::output::Stream;
+use crate::root::Roots;
+use crate::sandbox::Sandbox;
+
+/// Rust-side state owned by an interpreter.
+///
+/// A pointer to this structure is stored in `mrb_state.ud` for the lifetime of the `Mruby`
+/// instance, so it can be reached from C callbacks which only receive the raw `mrb_state`. It is
+/// freed after `mrb_close()`, which guarantees nothing in Ruby can refer to it anymore.
+#[derive(Default)]
+pub(crate) struct UserData {
+    // NOTE: Ruby methods hold raw pointers to these functions, so each one is boxed to keep its
+    // address stable when the vector reallocates.
+    #[allow(clippy::vec_box)]
+    functions: Vec<Box<Function>>,
+    // NOTE: Ruby objects point to these data types, so they are boxed for the same reason.
+    data_types: HashMap<TypeId, Box<DataType>>,
+    classes: HashMap<TypeId, *mut RClass>,
+    // NOTE: Exceptions raised to abort running code are told apart by their class rather than by
+    // its name, since Ruby code can define classes with any name.
+    abort_classes: HashMap<*mut RClass, Abort>,
+    // NOTE: The interpreter holds a raw pointer to the allocator, which must stay valid until
+    // `mrb_close()` has freed everything.
+    allocator: Box<Allocator>,
+    sandbox: Option<Sandbox>,
+    roots: Arc<Roots>,
+    kept: Kept,
+    stdout: Option<Box<dyn Write + Send>>,
+    stderr: Option<Box<dyn Write + Send>>,
+    gc_deferred: bool,
+    call_depth: u32,
+    execution_limit: Option<ExecutionLimit>,
+    interrupt: Option<Interrupt>,
+}
+
+impl UserData {
+    /// Returns the user data attached to the given interpreter.
+    ///
+    /// The returned reference must not be held across calls back into Ruby, since those may
+    /// re-enter Rust code which accesses the user data as well.
+    pub unsafe fn get<'a>(state: *mut mrb_state) -> &'a mut UserData {
+        &mut *((*state).ud as *mut UserData)
+    }
+
+    /// Returns the allocator of the interpreter.
+    pub fn allocator(&mut self) -> &mut Allocator {
+        &mut self.allocator
+    }
+
+    /// Returns the execution limit of the interpreter, if one was ever set.
+    pub fn execution_limit(&mut self) -> Option<&mut ExecutionLimit> {
+        self.execution_limit.as_mut()
+    }
+
+    /// Sets the execution limit of the interpreter, returning a reference to it.
+    pub fn set_execution_limit(&mut self, limit: ExecutionLimit) -> &mut ExecutionLimit {
+        self.execution_limit.get_or_insert(limit)
+    }
+
+    /// Returns the interrupt state of the interpreter, if an interrupt handle was ever created.
+    pub fn interrupt(&self) -> Option<&Interrupt> {
+        self.interrupt.as_ref()
+    }
+
+    /// Sets the interrupt state of the interpreter, returning a reference to it.
+    pub fn set_interrupt(&mut self, interrupt: Interrupt) -> &Interrupt {
+        self.interrupt.get_or_insert(interrupt)
+    }
+
+    /// Returns the sandbox state of the interpreter, if it was created with `Mruby::sandboxed()`.
+    pub fn sandbox(&self) -> Option<&Sandbox> {
+        self.sandbox.as_ref()
+    }
+
+    pub fn set_sandbox(&mut self, sandbox: Sandbox) {
+        self.sandbox = Some(sandbox);
+    }
+
+    /// Returns the list of dropped roots, which is shared with every `Root` of the interpreter.
+    pub fn roots(&self) -> &Arc<Roots> {
+        &self.roots
+    }
+
+    /// Returns the values handed out to Rust code which are kept in the GC arena.
+    pub fn kept(&mut self) -> &mut Kept {
+        &mut self.kept
+    }
+
+    /// Returns the sink which Ruby code writing to `stream` is redirected to, if any.
+    pub fn output(&mut self, stream: Stream) -> Option<&mut (dyn Write + Send + 'static)> {
+        match stream {
+            Stream::Stdout => self.stdout.as_deref_mut(),
+            Stream::Stderr => self.stderr.as_deref_mut(),
+        }
+    }
+
+    /// Redirects `stream` to `sink`, returning the previous sink, if any.
+    pub fn set_output(
+        &mut self,
+        stream: Stream,
+        sink: Box<dyn Write + Send>,
+    ) -> Option<Box<dyn Write + Send>> {
+        match stream {
+            Stream::Stdout => self.stdout.replace(sink),
+            Stream::Stderr => self.stderr.replace(sink),
+        }
+    }
+
+    /// Returns whether automatic garbage collection is deferred with `Mruby::set_gc_deferred()`.
+    pub fn gc_deferred(&self) -> bool {
+        self.gc_deferred
+    }
+
+    pub fn set_gc_deferred(&mut self, deferred: bool) {
+        self.gc_deferred = deferred;
+    }
+
+    /// Returns how many calls into Ruby from the Rust side are running, which is more than one
+    /// while a Rust function called from Ruby calls back into it.
+    pub fn call_depth(&self) -> u32 {
+        self.call_depth
+    }
+
+    pub fn set_call_depth(&mut self, depth: u32) {
+        self.call_depth = depth;
+    }
+
+    /// Takes ownership of `func`, returning a reference to it which stays valid until the
+    /// interpreter is closed.
+    pub fn add_function(&mut self, func: Function) -> &Function {
+        self.functions.push(Box::new(func));
+        self.functions.last().expect("function was just pushed")
+    }
+
+    /// Returns the data type registered for the Rust type `id`, if any.
+    pub fn data_type(&self, id: TypeId) -> Option<&DataType> {
+        self.data_types.get(&id).map(|data_type| &**data_type)
+    }
+
+    /// Returns the class defined for the Rust type `id` with `Mruby::define_class()`, if any.
+    pub fn class(&self, id: TypeId) -> Option<*mut RClass> {
+        self.classes.get(&id).cloned()
+    }
+
+    /// Records `class` as the class defined for the Rust type `id`.
+    pub fn add_class(&mut self, id: TypeId, class: *mut RClass) {
+        self.classes.insert(id, class);
+    }
+
+    /// Returns what raising an instance of `class` aborts running code for, if anything.
+    pub fn abort_kind(&self, class: *mut RClass) -> Option<Abort> {
+        self.abort_classes.get(&class).cloned()
+    }
+
+    /// Returns the class of the exceptions raised to abort running code for `abort`, if defined.
+    pub fn abort_class(&self, abort: Abort) -> Option<*mut RClass> {
+        self.abort_classes
+            .iter()
+            .find(|&(_, &kind)| kind == abort)
+            .map(|(&class, _)| class)
+    }
+
+    /// Records `class` as the class of the exceptions raised to abort running code for `abort`.
+    pub fn add_abort_class(&mut self, class: *mut RClass, abort: Abort) {
+        self.abort_classes.insert(class, abort);
+    }
+
+    /// Takes ownership of `data_type`, registering it for the Rust type `id`.
+    pub fn add_data_type(&mut self, id: TypeId, data_type: Box<DataType>) {
+        self.data_types.insert(id, data_type);
+    }
+}
+
+impl Debug for UserData {
+    fn fmt(&self, fmt: &mut Formatter) -> FmtResult {
+        fmt.debug_struct(stringify!(UserData))
+            .field("functions", &self.functions.len())
+            .field("data_types", &self.data_types.len())
+            .field("classes", &self.classes.len())
+            .field("abort_classes", &self.abort_classes)
+            .field("allocator", &self.allocator)
+            .field("sandbox", &self.sandbox)
+            .field("roots", &self.roots)
+            .field("kept", &self.kept.len())
+            .field("stdout", &self.stdout.is_some())
+            .field("stderr", &self.stderr.is_some())
+            .field("gc_deferred", &self.gc_deferred)
+            .field("call_depth", &self.call_depth)
+            .field("execution_limit", &self.execution_limit)
+            .field("interrupt", &self.interrupt)
+            .finish()
+    }
+}
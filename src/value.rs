@@ -1,45 +1,74 @@
 use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::marker::PhantomData;
 
 use mruby_sys::*;
 
+use crate::Mruby;
+
+/// A Ruby value belonging to the interpreter it was obtained from.
+///
+/// Values borrow their interpreter, so they cannot outlive it. The lifetime does not tell two
+/// interpreters apart though, so passing a value to a different interpreter still compiles. It is
+/// checked at runtime instead, and panics.
+///
+/// Values handed out by the interpreter are kept in its GC arena until the enclosing
+/// `Mruby::arena_scope()` ends, or the Rust function called from Ruby returns. Use a `Root` to
+/// keep one alive for longer.
 #[derive(Clone)]
-pub struct Value(pub(crate) mrb_value);
+pub struct Value<'mrb> {
+    value: mrb_value,
+    state: *mut mrb_state,
+    _marker: PhantomData<&'mrb Mruby>,
+}
+
+impl<'mrb> Value<'mrb> {
+    pub(crate) const fn new(state: *mut mrb_state, value: mrb_value) -> Self {
+        Value {
+            value,
+            state,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Panics if the value belongs to an interpreter other than `state`.
+    pub(crate) fn assert_interpreter(&self, state: *mut mrb_state) {
+        assert!(
+            self.state == state,
+            "value belongs to a different interpreter"
+        );
+    }
 
-impl Value {
     pub const fn get_ref(&self) -> &mrb_value {
-        &self.0
+        &self.value
     }
 
     pub const fn into_inner(self) -> mrb_value {
-        self.0
+        self.value
     }
 
     pub const fn is_array(&self) -> bool {
-        let Value(ref value) = *self;
+        let value = &self.value;
         value.tt == MRB_TT_ARRAY
     }
 
     // TODO: Switch to `const fn` once `match` statements are stabilized. See:
     // https://github.com/rust-lang/rust/issues/49146
     pub fn is_bool(&self) -> bool {
-        match self.0.tt {
-            MRB_TT_FALSE | MRB_TT_TRUE => true,
-            _ => false,
-        }
+        matches!(self.value.tt, MRB_TT_FALSE | MRB_TT_TRUE)
     }
 
     pub const fn is_exception(&self) -> bool {
-        let Value(ref value) = *self;
+        let value = &self.value;
         value.tt == MRB_TT_EXCEPTION
     }
 
     pub const fn is_fixnum(&self) -> bool {
-        let Value(ref value) = *self;
+        let value = &self.value;
         value.tt == MRB_TT_FIXNUM
     }
 
     pub const fn is_float(&self) -> bool {
-        let Value(ref value) = *self;
+        let value = &self.value;
         value.tt == MRB_TT_FLOAT
     }
 
@@ -48,39 +77,39 @@ impl Value {
     //
     // TODO: Investigate whether FFI calls are permitted in `const fn`.
     pub fn is_nil(&self) -> bool {
-        let Value(ref value) = *self;
+        let value = &self.value;
         unsafe { mrb_ext_is_value_nil(*value) == 1 }
     }
 
     pub const fn is_object(&self) -> bool {
-        let Value(ref value) = *self;
+        let value = &self.value;
         value.tt == MRB_TT_OBJECT
     }
 
     pub const fn is_hash(&self) -> bool {
-        let Value(ref value) = *self;
+        let value = &self.value;
         value.tt == MRB_TT_HASH
     }
 
     pub const fn is_string(&self) -> bool {
-        let Value(ref value) = *self;
+        let value = &self.value;
         value.tt == MRB_TT_STRING
     }
 
     pub const fn is_symbol(&self) -> bool {
-        let Value(ref value) = *self;
+        let value = &self.value;
         value.tt == MRB_TT_SYMBOL
     }
 
     pub const fn is_undef(&self) -> bool {
-        let Value(ref value) = *self;
+        let value = &self.value;
         value.tt == MRB_TT_UNDEF
     }
 }
 
-impl Debug for Value {
+impl<'mrb> Debug for Value<'mrb> {
     fn fmt(&self, fmt: &mut Formatter) -> FmtResult {
-        let Value(ref inner) = *self;
+        let inner = &self.value;
 
         let mut debug = fmt.debug_struct(stringify!(Value));
         debug.field("type", &inner.tt);